@@ -0,0 +1,265 @@
+use std::cmp::Ordering;
+use std::fmt;
+use std::ops::{Add, Neg, Sub};
+
+// A signed integer with no upper bound, stored as a sign and a magnitude of
+// base 2^32 digits, least significant first. Only what's needed to add up
+// primitive integers without overflowing is implemented.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct BigInt {
+    negative: bool,
+    digits: Vec<u32>,
+}
+
+impl BigInt {
+    pub fn zero() -> BigInt {
+        BigInt::default()
+    }
+
+    pub fn from_u128(value: u128) -> BigInt {
+        let mut digits = Vec::new();
+        let mut rest = value;
+        while rest > 0 {
+            digits.push(rest as u32);
+            rest >>= 32;
+        }
+        BigInt {
+            negative: false,
+            digits,
+        }
+    }
+
+    pub fn from_i128(value: i128) -> BigInt {
+        let magnitude = BigInt::from_u128(value.unsigned_abs());
+        if value < 0 { -magnitude } else { magnitude }
+    }
+
+    pub fn is_negative(&self) -> bool {
+        self.negative
+    }
+
+    pub fn to_u128(&self) -> Option<u128> {
+        if self.negative || self.digits.len() > 4 {
+            return None;
+        }
+        Some(
+            self.digits
+                .iter()
+                .rev()
+                .fold(0u128, |acc, &d| (acc << 32) | u128::from(d)),
+        )
+    }
+
+    pub fn to_i128(&self) -> Option<i128> {
+        let magnitude = if self.digits.len() > 4 {
+            return None;
+        } else {
+            self.digits
+                .iter()
+                .rev()
+                .fold(0u128, |acc, &d| (acc << 32) | u128::from(d))
+        };
+        if self.negative {
+            0i128.checked_sub_unsigned(magnitude)
+        } else {
+            i128::try_from(magnitude).ok()
+        }
+    }
+
+    fn normalize(mut self) -> BigInt {
+        while self.digits.last() == Some(&0) {
+            self.digits.pop();
+        }
+        if self.digits.is_empty() {
+            self.negative = false;
+        }
+        self
+    }
+}
+
+fn compare_magnitude(a: &[u32], b: &[u32]) -> Ordering {
+    a.len()
+        .cmp(&b.len())
+        .then_with(|| a.iter().rev().cmp(b.iter().rev()))
+}
+
+fn add_magnitude(a: &[u32], b: &[u32]) -> Vec<u32> {
+    let mut digits = Vec::with_capacity(a.len().max(b.len()) + 1);
+    let mut carry = 0u64;
+    for i in 0..a.len().max(b.len()) {
+        let sum = u64::from(*a.get(i).unwrap_or(&0)) + u64::from(*b.get(i).unwrap_or(&0)) + carry;
+        digits.push(sum as u32);
+        carry = sum >> 32;
+    }
+    if carry > 0 {
+        digits.push(carry as u32);
+    }
+    digits
+}
+
+// Assumes `a` is at least as large as `b`.
+fn sub_magnitude(a: &[u32], b: &[u32]) -> Vec<u32> {
+    let mut digits = Vec::with_capacity(a.len());
+    let mut borrow = 0i64;
+    for (i, &digit) in a.iter().enumerate() {
+        let mut diff = i64::from(digit) - i64::from(*b.get(i).unwrap_or(&0)) - borrow;
+        borrow = if diff < 0 {
+            diff += 1 << 32;
+            1
+        } else {
+            0
+        };
+        digits.push(diff as u32);
+    }
+    digits
+}
+
+impl Add for BigInt {
+    type Output = BigInt;
+
+    fn add(self, rhs: BigInt) -> BigInt {
+        if self.negative == rhs.negative {
+            return BigInt {
+                negative: self.negative,
+                digits: add_magnitude(&self.digits, &rhs.digits),
+            }
+            .normalize();
+        }
+        match compare_magnitude(&self.digits, &rhs.digits) {
+            Ordering::Less => BigInt {
+                negative: rhs.negative,
+                digits: sub_magnitude(&rhs.digits, &self.digits),
+            },
+            _ => BigInt {
+                negative: self.negative,
+                digits: sub_magnitude(&self.digits, &rhs.digits),
+            },
+        }
+        .normalize()
+    }
+}
+
+impl Sub for BigInt {
+    type Output = BigInt;
+
+    fn sub(self, rhs: BigInt) -> BigInt {
+        self + -rhs
+    }
+}
+
+impl Neg for BigInt {
+    type Output = BigInt;
+
+    fn neg(self) -> BigInt {
+        BigInt {
+            negative: !self.negative,
+            digits: self.digits,
+        }
+        .normalize()
+    }
+}
+
+impl Ord for BigInt {
+    fn cmp(&self, other: &BigInt) -> Ordering {
+        match (self.negative, other.negative) {
+            (false, true) => Ordering::Greater,
+            (true, false) => Ordering::Less,
+            (false, false) => compare_magnitude(&self.digits, &other.digits),
+            (true, true) => compare_magnitude(&other.digits, &self.digits),
+        }
+    }
+}
+
+impl PartialOrd for BigInt {
+    fn partial_cmp(&self, other: &BigInt) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl fmt::Display for BigInt {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.digits.is_empty() {
+            return write!(f, "0");
+        }
+
+        // Peel off nine decimal digits at a time by long division.
+        let mut rest = self.digits.clone();
+        let mut chunks = Vec::new();
+        while !rest.is_empty() {
+            let mut remainder = 0u64;
+            for digit in rest.iter_mut().rev() {
+                let value = (remainder << 32) | u64::from(*digit);
+                *digit = (value / 1_000_000_000) as u32;
+                remainder = value % 1_000_000_000;
+            }
+            chunks.push(remainder as u32);
+            while rest.last() == Some(&0) {
+                rest.pop();
+            }
+        }
+
+        if self.negative {
+            write!(f, "-")?;
+        }
+        let mut chunks = chunks.iter().rev();
+        if let Some(first) = chunks.next() {
+            write!(f, "{first}")?;
+        }
+        for chunk in chunks {
+            write!(f, "{chunk:09}")?;
+        }
+        Ok(())
+    }
+}
+
+macro_rules! impl_from_unsigned {
+    ($($t:ty),*) => {
+        $(impl From<$t> for BigInt {
+            fn from(value: $t) -> BigInt {
+                BigInt::from_u128(value as u128)
+            }
+        })*
+    };
+}
+
+macro_rules! impl_from_signed {
+    ($($t:ty),*) => {
+        $(impl From<$t> for BigInt {
+            fn from(value: $t) -> BigInt {
+                BigInt::from_i128(value as i128)
+            }
+        })*
+    };
+}
+
+impl_from_unsigned!(u8, u16, u32, u64, u128, usize);
+impl_from_signed!(i8, i16, i32, i64, i128, isize);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn adds_past_u128_max() {
+        let sum = BigInt::from(u128::MAX) + BigInt::from(u128::MAX);
+        assert_eq!(sum.to_string(), "680564733841876926926749214863536422910");
+        assert_eq!(sum.to_u128(), None);
+    }
+
+    #[test]
+    fn mixed_signs_cancel() {
+        let sum = BigInt::from(i128::MIN) + BigInt::from(u128::MAX);
+        assert_eq!(sum.to_i128(), Some(i128::MAX));
+        assert_eq!((BigInt::from(5) - BigInt::from(5u8)), BigInt::zero());
+        assert_eq!(BigInt::from(-7).to_string(), "-7");
+        assert!(BigInt::from(-7) < BigInt::from(-6));
+    }
+
+    #[test]
+    fn round_trips_extremes() {
+        assert_eq!(BigInt::from(i128::MIN).to_i128(), Some(i128::MIN));
+        assert_eq!(BigInt::from(i128::MIN).to_string(), i128::MIN.to_string());
+        assert_eq!(BigInt::from(u128::MAX).to_u128(), Some(u128::MAX));
+        assert_eq!(BigInt::from(-1).to_u128(), None);
+    }
+}
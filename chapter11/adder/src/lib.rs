@@ -1,9 +1,120 @@
+use std::fmt;
+
+mod big;
+
+pub use big::BigInt;
+
+// Implemented for every primitive integer so the add functions below work on
+// any of them. Each method forwards to the inherent method of the same name.
+pub trait Integer: Copy + PartialOrd + fmt::Debug + Into<BigInt> {
+    const MIN: Self;
+    const MAX: Self;
+    const TWO: Self;
+
+    fn checked_add(self, rhs: Self) -> Option<Self>;
+    fn saturating_add(self, rhs: Self) -> Self;
+    fn wrapping_add(self, rhs: Self) -> Self;
+}
+
+macro_rules! impl_integer {
+    ($($t:ty),*) => {
+        $(impl Integer for $t {
+            const MIN: $t = <$t>::MIN;
+            const MAX: $t = <$t>::MAX;
+            const TWO: $t = 2;
+
+            fn checked_add(self, rhs: $t) -> Option<$t> {
+                <$t>::checked_add(self, rhs)
+            }
+
+            fn saturating_add(self, rhs: $t) -> $t {
+                <$t>::saturating_add(self, rhs)
+            }
+
+            fn wrapping_add(self, rhs: $t) -> $t {
+                <$t>::wrapping_add(self, rhs)
+            }
+        })*
+    };
+}
+
+impl_integer!(
+    u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize
+);
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct OverflowError {
+    left: String,
+    right: String,
+}
+
+impl fmt::Display for OverflowError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "adding {} and {} overflows", self.left, self.right)
+    }
+}
+
+impl std::error::Error for OverflowError {}
+
+// Panics on overflow in both debug and release builds, instead of wrapping
+// silently in release.
 pub fn add(left: u64, right: u64) -> u64 {
-    left + right
+    match try_add(left, right) {
+        Ok(sum) => sum,
+        Err(e) => panic!("attempt to add with overflow: {e}"),
+    }
 }
 
 pub fn add_two(a: u64) -> u64 {
-    a + 2
+    add(a, 2)
+}
+
+pub fn checked_add<T: Integer>(left: T, right: T) -> Option<T> {
+    left.checked_add(right)
+}
+
+pub fn saturating_add<T: Integer>(left: T, right: T) -> T {
+    left.saturating_add(right)
+}
+
+pub fn wrapping_add<T: Integer>(left: T, right: T) -> T {
+    left.wrapping_add(right)
+}
+
+pub fn try_add<T: Integer>(left: T, right: T) -> Result<T, OverflowError> {
+    left.checked_add(right).ok_or_else(|| OverflowError {
+        left: format!("{left:?}"),
+        right: format!("{right:?}"),
+    })
+}
+
+pub fn checked_add_two<T: Integer>(a: T) -> Option<T> {
+    checked_add(a, T::TWO)
+}
+
+pub fn saturating_add_two<T: Integer>(a: T) -> T {
+    saturating_add(a, T::TWO)
+}
+
+pub fn wrapping_add_two<T: Integer>(a: T) -> T {
+    wrapping_add(a, T::TWO)
+}
+
+pub fn try_add_two<T: Integer>(a: T) -> Result<T, OverflowError> {
+    try_add(a, T::TWO)
+}
+
+// Never overflows, whatever the integer type.
+pub fn wide_add<T: Integer>(left: T, right: T) -> BigInt {
+    left.into() + right.into()
+}
+
+// Sums any number of integers exactly, even when the total doesn't fit in a
+// u128.
+pub fn wide_sum<T: Integer, I: IntoIterator<Item = T>>(values: I) -> BigInt {
+    values
+        .into_iter()
+        .fold(BigInt::zero(), |total, value| total + value.into())
 }
 
 #[cfg(test)]
@@ -13,7 +124,7 @@ mod tests {
     #[test]
     fn it_adds_two() {
         let result = add_two(2);
-        assert_ne!(result, 4);
+        assert_eq!(result, 4);
     }
 
     #[test]
@@ -23,8 +134,9 @@ mod tests {
     }
 
     #[test]
-    fn another() {
-        panic!("Make this test fail!");
+    #[should_panic(expected = "overflow")]
+    fn add_panics_on_overflow() {
+        add(u64::MAX, 1);
     }
 
     #[test]
@@ -36,4 +148,23 @@ mod tests {
             Err(String::from("two plus two does not equal four"))
         }
     }
+
+    #[test]
+    fn variants_differ_on_overflow() {
+        assert_eq!(checked_add_two(u64::MAX), None);
+        assert_eq!(saturating_add_two(u64::MAX), u64::MAX);
+        assert_eq!(wrapping_add_two(u64::MAX), 1);
+        assert_eq!(
+            try_add_two(i8::MAX).unwrap_err().to_string(),
+            "adding 127 and 2 overflows"
+        );
+        assert_eq!(saturating_add(i32::MIN, -1), i32::MIN);
+    }
+
+    #[test]
+    fn wide_sum_exceeds_u128() {
+        let total = wide_sum([u128::MAX, u128::MAX, 2]);
+        assert_eq!(total.to_string(), "680564733841876926926749214863536422912");
+        assert_eq!(wide_add(u64::MAX, 1).to_u128(), Some(1 << 64));
+    }
 }
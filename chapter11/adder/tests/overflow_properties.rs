@@ -0,0 +1,55 @@
+use adder::{BigInt, Integer, checked_add, saturating_add, try_add, wide_add, wrapping_add};
+use proptest::prelude::*;
+
+// Whenever the checked sum exists, every variant must agree with it. When it
+// doesn't, saturating clamps to a bound and the wide sum lies outside the
+// type's range.
+fn variants_agree<T: Integer>(a: T, b: T) {
+    match checked_add(a, b) {
+        Some(sum) => {
+            assert_eq!(saturating_add(a, b), sum);
+            assert_eq!(wrapping_add(a, b), sum);
+            assert_eq!(try_add(a, b).ok(), Some(sum));
+            assert_eq!(wide_add(a, b), sum.into());
+        }
+        None => {
+            let saturated = saturating_add(a, b);
+            assert!(saturated == T::MAX || saturated == T::MIN);
+            assert!(try_add(a, b).is_err());
+            let wide = wide_add(a, b);
+            assert!(wide > T::MAX.into() || wide < T::MIN.into());
+        }
+    }
+}
+
+macro_rules! agree_for {
+    ($($name:ident: $t:ty),*) => {
+        proptest! {
+            $(#[test]
+            fn $name(a in any::<$t>(), b in any::<$t>()) {
+                variants_agree(a, b);
+            })*
+        }
+    };
+}
+
+agree_for!(
+    agree_u8: u8, agree_u16: u16, agree_u32: u32, agree_u64: u64, agree_u128: u128,
+    agree_usize: usize, agree_i8: i8, agree_i16: i16, agree_i32: i32, agree_i64: i64,
+    agree_i128: i128, agree_isize: isize
+);
+
+proptest! {
+    #[test]
+    fn wide_add_matches_native_i128(a in any::<i64>(), b in any::<i64>()) {
+        let expected = i128::from(a) + i128::from(b);
+        prop_assert_eq!(wide_add(a, b).to_i128(), Some(expected));
+        prop_assert_eq!(wide_add(a, b).to_string(), expected.to_string());
+    }
+
+    #[test]
+    fn wide_add_is_commutative(a in any::<u128>(), b in any::<i128>()) {
+        let (a, b) = (BigInt::from(a), BigInt::from(b));
+        prop_assert_eq!(a.clone() + b.clone(), b + a);
+    }
+}
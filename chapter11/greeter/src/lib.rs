@@ -1,6 +1,361 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::sync::LazyLock;
+
+mod template;
+
+pub use template::{Template, TemplateError};
+
+static BUILTIN: LazyLock<Greeter> = LazyLock::new(Greeter::new);
+
 pub fn greeting(name: &str) -> String {
-    //format!("Hello {name}!")
-    format!("Hello")
+    BUILTIN
+        .greet(name, &GreetingOptions::default())
+        .expect("built-in templates cover English")
+}
+
+pub fn greeting_in(name: &str, options: &GreetingOptions) -> Result<String, GreetError> {
+    BUILTIN.greet(name, options)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Formality {
+    #[default]
+    Casual,
+    Formal,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TimeOfDay {
+    Morning,
+    Afternoon,
+    Evening,
+}
+
+impl TimeOfDay {
+    pub fn from_hour(hour: u8) -> TimeOfDay {
+        match hour {
+            5..=11 => TimeOfDay::Morning,
+            12..=17 => TimeOfDay::Afternoon,
+            _ => TimeOfDay::Evening,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct GreetingOptions {
+    pub locale: String,
+    pub formality: Formality,
+    pub time: Option<TimeOfDay>,
+}
+
+impl Default for GreetingOptions {
+    fn default() -> GreetingOptions {
+        GreetingOptions::new("en")
+    }
+}
+
+impl GreetingOptions {
+    pub fn new(locale: &str) -> GreetingOptions {
+        GreetingOptions {
+            locale: String::from(locale),
+            formality: Formality::Casual,
+            time: None,
+        }
+    }
+
+    pub fn formal(mut self) -> GreetingOptions {
+        self.formality = Formality::Formal;
+        self
+    }
+
+    pub fn at(mut self, time: TimeOfDay) -> GreetingOptions {
+        self.time = Some(time);
+        self
+    }
+
+    pub fn at_hour(self, hour: u8) -> GreetingOptions {
+        self.at(TimeOfDay::from_hour(hour))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum GreetError {
+    UnknownLocale(String),
+    NoTemplate {
+        locale: String,
+        formality: Formality,
+    },
+}
+
+impl fmt::Display for GreetError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            GreetError::UnknownLocale(locale) => write!(f, "no greetings for locale `{locale}`"),
+            GreetError::NoTemplate { locale, formality } => {
+                write!(f, "locale `{locale}` has no {formality:?} greeting")
+            }
+        }
+    }
+}
+
+impl std::error::Error for GreetError {}
+
+#[derive(Debug)]
+pub enum LoadError {
+    Io(io::Error),
+    Invalid { line: usize, problem: Problem },
+}
+
+#[derive(Debug, PartialEq)]
+pub enum Problem {
+    Template(TemplateError),
+    TemplateWithoutName,
+    NotKeyValue,
+    OutsideSection,
+    BadSection(String),
+    DuplicateSection(String),
+    UnknownKey(String),
+    DuplicateKey(String),
+    MissingKey(&'static str),
+}
+
+impl fmt::Display for LoadError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            LoadError::Io(e) => write!(f, "could not read templates: {e}"),
+            LoadError::Invalid { line, problem } => {
+                write!(f, "line {line}: ")?;
+                match problem {
+                    Problem::Template(e) => write!(f, "{e}"),
+                    Problem::TemplateWithoutName => write!(f, "template never uses `{{name}}`"),
+                    Problem::NotKeyValue => write!(f, "expected `key = value`"),
+                    Problem::OutsideSection => {
+                        write!(f, "key comes before any [locale.formality] section")
+                    }
+                    Problem::BadSection(s) => write!(
+                        f,
+                        "`[{s}]` is not a [locale.casual] or [locale.formal] section"
+                    ),
+                    Problem::DuplicateSection(s) => {
+                        write!(f, "`[{s}]` already appears earlier in the file")
+                    }
+                    Problem::UnknownKey(k) => write!(f, "unknown key `{k}`"),
+                    Problem::DuplicateKey(k) => write!(f, "`{k}` is set twice"),
+                    Problem::MissingKey(k) => write!(f, "section is missing `{k}`"),
+                }
+            }
+        }
+    }
+}
+
+impl std::error::Error for LoadError {}
+
+impl From<io::Error> for LoadError {
+    fn from(e: io::Error) -> LoadError {
+        LoadError::Io(e)
+    }
+}
+
+const KEYS: [&str; 6] = [
+    "template",
+    "default",
+    "morning",
+    "afternoon",
+    "evening",
+    "formality",
+];
+
+#[derive(Debug, Clone)]
+struct Entry {
+    template: Template,
+    default: String,
+    morning: String,
+    afternoon: String,
+    evening: String,
+    // What `{formality}` expands to.
+    formality: String,
+}
+
+impl Entry {
+    fn salutation(&self, time: Option<TimeOfDay>) -> &str {
+        match time {
+            None => &self.default,
+            Some(TimeOfDay::Morning) => &self.morning,
+            Some(TimeOfDay::Afternoon) => &self.afternoon,
+            Some(TimeOfDay::Evening) => &self.evening,
+        }
+    }
+}
+
+// Holds greeting templates keyed by locale and formality. Starts out with the
+// built-in English, French, Spanish and Japanese greetings; template files can
+// add locales or replace the built-in ones.
+#[derive(Debug, Clone)]
+pub struct Greeter {
+    entries: HashMap<(String, Formality), Entry>,
+}
+
+impl Default for Greeter {
+    fn default() -> Greeter {
+        Greeter::new()
+    }
+}
+
+impl Greeter {
+    pub fn new() -> Greeter {
+        let mut greeter = Greeter::empty();
+        greeter
+            .load_str(include_str!("builtin.txt"))
+            .expect("built-in templates are valid");
+        greeter
+    }
+
+    pub fn empty() -> Greeter {
+        Greeter {
+            entries: HashMap::new(),
+        }
+    }
+
+    pub fn locales(&self) -> Vec<&str> {
+        let mut locales: Vec<&str> = self.entries.keys().map(|(l, _)| l.as_str()).collect();
+        locales.sort();
+        locales.dedup();
+        locales
+    }
+
+    pub fn load_file<P: AsRef<Path>>(&mut self, path: P) -> Result<(), LoadError> {
+        let text = fs::read_to_string(path)?;
+        self.load_str(&text)
+    }
+
+    // The whole file is validated before anything is added, so a bad file
+    // leaves the greeter unchanged.
+    pub fn load_str(&mut self, text: &str) -> Result<(), LoadError> {
+        let mut loaded = Vec::new();
+        let mut section: Option<(usize, String, Formality, HashMap<&str, String>)> = None;
+
+        let invalid = |line, problem| LoadError::Invalid { line, problem };
+
+        for (index, raw) in text.lines().enumerate() {
+            let line_no = index + 1;
+            let line = raw.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            if let Some(header) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+                if let Some(done) = section.take() {
+                    loaded.push(finish_section(done)?);
+                }
+                let (locale, formality) = parse_section(header)
+                    .ok_or_else(|| invalid(line_no, Problem::BadSection(String::from(header))))?;
+                let seen = loaded
+                    .iter()
+                    .any(|(l, f, _)| *l == locale && *f == formality);
+                if seen {
+                    let header = String::from(header.trim());
+                    return Err(invalid(line_no, Problem::DuplicateSection(header)));
+                }
+                section = Some((line_no, locale, formality, HashMap::new()));
+                continue;
+            }
+
+            let (key, value) = line
+                .split_once('=')
+                .ok_or_else(|| invalid(line_no, Problem::NotKeyValue))?;
+            let (key, value) = (key.trim(), value.trim());
+            let Some((_, _, _, values)) = section.as_mut() else {
+                return Err(invalid(line_no, Problem::OutsideSection));
+            };
+            let Some(&key) = KEYS.iter().find(|&&k| k == key) else {
+                return Err(invalid(line_no, Problem::UnknownKey(String::from(key))));
+            };
+            if key == "template" {
+                let template =
+                    Template::parse(value).map_err(|e| invalid(line_no, Problem::Template(e)))?;
+                if !template.mentions_name() {
+                    return Err(invalid(line_no, Problem::TemplateWithoutName));
+                }
+            }
+            if values.insert(key, String::from(value)).is_some() {
+                return Err(invalid(line_no, Problem::DuplicateKey(String::from(key))));
+            }
+        }
+        if let Some(done) = section.take() {
+            loaded.push(finish_section(done)?);
+        }
+
+        for (locale, formality, entry) in loaded {
+            self.entries.insert((locale, formality), entry);
+        }
+        Ok(())
+    }
+
+    // Falls back from a regional locale like `fr-CA` to its language, `fr`.
+    pub fn greet(&self, name: &str, options: &GreetingOptions) -> Result<String, GreetError> {
+        let requested = options.locale.to_lowercase().replace('_', "-");
+        let language = requested.split('-').next().unwrap_or_default();
+
+        let locale = [requested.as_str(), language]
+            .into_iter()
+            .find(|l| self.entries.keys().any(|(known, _)| known == l))
+            .ok_or_else(|| GreetError::UnknownLocale(options.locale.clone()))?;
+        let entry = self
+            .entries
+            .get(&(String::from(locale), options.formality))
+            .ok_or_else(|| GreetError::NoTemplate {
+                locale: String::from(locale),
+                formality: options.formality,
+            })?;
+
+        Ok(entry
+            .template
+            .render(name, entry.salutation(options.time), &entry.formality))
+    }
+}
+
+fn parse_section(header: &str) -> Option<(String, Formality)> {
+    let (locale, formality) = header.trim().rsplit_once('.')?;
+    let locale = locale.trim().to_lowercase().replace('_', "-");
+    if locale.is_empty() || locale.contains(char::is_whitespace) {
+        return None;
+    }
+    let formality = match formality.trim() {
+        "casual" => Formality::Casual,
+        "formal" => Formality::Formal,
+        _ => return None,
+    };
+    Some((locale, formality))
+}
+
+fn finish_section(
+    (line, locale, formality, mut values): (usize, String, Formality, HashMap<&str, String>),
+) -> Result<(String, Formality, Entry), LoadError> {
+    let mut take = |key: &'static str| {
+        values.remove(key).ok_or(LoadError::Invalid {
+            line,
+            problem: Problem::MissingKey(key),
+        })
+    };
+    let template = Template::parse(&take("template")?).expect("template was validated when read");
+    // `formality` is only required when the template uses it.
+    let wording = match take("formality") {
+        Err(missing) if template.mentions_formality() => return Err(missing),
+        result => result.unwrap_or_default(),
+    };
+    let entry = Entry {
+        template,
+        default: take("default")?,
+        morning: take("morning")?,
+        afternoon: take("afternoon")?,
+        evening: take("evening")?,
+        formality: wording,
+    };
+    Ok((locale, formality, entry))
 }
 
 #[cfg(test)]
@@ -15,4 +370,139 @@ mod tests {
             "Greeting did not contain name, value was `{result}`"
         );
     }
+
+    #[test]
+    fn greets_in_each_built_in_locale() {
+        let greet = |options: GreetingOptions| greeting_in("Carol", &options).unwrap();
+
+        assert_eq!(
+            greet(GreetingOptions::new("en").at_hour(9)),
+            "Morning, Carol!"
+        );
+        assert_eq!(
+            greet(GreetingOptions::new("fr").formal().at(TimeOfDay::Evening)),
+            "Bonsoir, Carol."
+        );
+        assert_eq!(
+            greet(GreetingOptions::new("es-MX").at_hour(15)),
+            "¡Buenas tardes, Carol!"
+        );
+        assert_eq!(
+            greet(GreetingOptions::new("ja").formal().at_hour(7)),
+            "Carol様、おはようございます。"
+        );
+        assert_eq!(
+            greeting_in("Carol", &GreetingOptions::new("de")),
+            Err(GreetError::UnknownLocale(String::from("de")))
+        );
+    }
+
+    #[test]
+    fn loads_user_templates() {
+        let mut greeter = Greeter::new();
+        greeter
+            .load_str(
+                "[de.casual]\n\
+                 template = {salutation}, {name}!\n\
+                 default = Hallo\n\
+                 morning = Moin\n\
+                 afternoon = Hallo\n\
+                 evening = Guten Abend\n",
+            )
+            .unwrap();
+
+        let options = GreetingOptions::new("de").at_hour(20);
+        assert_eq!(
+            greeter.greet("Carol", &options).unwrap(),
+            "Guten Abend, Carol!"
+        );
+        assert_eq!(
+            greeter.greet("Carol", &options.formal()),
+            Err(GreetError::NoTemplate {
+                locale: String::from("de"),
+                formality: Formality::Formal
+            })
+        );
+        assert_eq!(greeter.locales(), vec!["de", "en", "es", "fr", "ja"]);
+    }
+
+    #[test]
+    fn substitutes_formality_wording() {
+        let mut greeter = Greeter::empty();
+        greeter
+            .load_str(
+                "[de.formal]\n\
+                 template = {salutation}, {formality} {name}.\n\
+                 formality = Frau\n\
+                 default = Guten Tag\n\
+                 morning = Guten Morgen\n\
+                 afternoon = Guten Tag\n\
+                 evening = Guten Abend\n",
+            )
+            .unwrap();
+        assert_eq!(
+            greeter
+                .greet("Weber", &GreetingOptions::new("de").formal().at_hour(8))
+                .unwrap(),
+            "Guten Morgen, Frau Weber."
+        );
+
+        let casual = GreetingOptions::new("ja").at_hour(19);
+        assert_eq!(
+            greeting_in("Carol", &casual).unwrap(),
+            "Carolさん、こんばんは！"
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_template_files() {
+        let problem = |text: &str| match Greeter::empty().load_str(text) {
+            Err(LoadError::Invalid { line, problem }) => (line, problem),
+            other => panic!("expected a validation error, got {other:?}"),
+        };
+
+        assert_eq!(
+            problem("[en.casual]\ntemplate = Hi {first_name}\n"),
+            (
+                2,
+                Problem::Template(TemplateError::UnknownPlaceholder(String::from(
+                    "first_name"
+                )))
+            )
+        );
+        assert_eq!(
+            problem("[en.casual]\ntemplate = Hi!\n"),
+            (2, Problem::TemplateWithoutName)
+        );
+        assert_eq!(
+            problem("[en.casual]\ntemplate = Hi {name}\n"),
+            (1, Problem::MissingKey("default"))
+        );
+        assert_eq!(
+            problem("[en.rude]\n"),
+            (1, Problem::BadSection(String::from("en.rude")))
+        );
+        assert_eq!(problem("default = Hi\n"), (1, Problem::OutsideSection));
+        let section = "[en.casual]\n\
+                       template = {salutation}, {name}!\n\
+                       default = Hi\n\
+                       morning = Morning\n\
+                       afternoon = Hi\n\
+                       evening = Evening\n";
+        assert_eq!(
+            problem(&format!("{section}\n[EN.casual]\n")),
+            (8, Problem::DuplicateSection(String::from("EN.casual")))
+        );
+        assert_eq!(
+            problem(
+                "[en.formal]\n\
+                 template = {salutation}, {formality} {name}.\n\
+                 default = Hello\n\
+                 morning = Good morning\n\
+                 afternoon = Good afternoon\n\
+                 evening = Good evening\n"
+            ),
+            (1, Problem::MissingKey("formality"))
+        );
+    }
 }
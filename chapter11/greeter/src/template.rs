@@ -0,0 +1,151 @@
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq)]
+enum Segment {
+    Text(String),
+    Name,
+    Salutation,
+    Formality,
+}
+
+// A greeting template such as `{salutation}, {name}!`. `{formality}` stands
+// for the wording that depends on how formal the greeting is, such as a
+// Japanese honorific. `{{` and `}}` stand for literal braces.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Template {
+    segments: Vec<Segment>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum TemplateError {
+    UnknownPlaceholder(String),
+    UnclosedPlaceholder,
+    UnmatchedBrace,
+}
+
+impl fmt::Display for TemplateError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            TemplateError::UnknownPlaceholder(p) => write!(
+                f,
+                "unknown placeholder `{{{p}}}`, expected `{{name}}`, `{{salutation}}` or `{{formality}}`"
+            ),
+            TemplateError::UnclosedPlaceholder => write!(f, "placeholder is missing its `}}`"),
+            TemplateError::UnmatchedBrace => write!(f, "`}}` without a matching `{{`"),
+        }
+    }
+}
+
+impl std::error::Error for TemplateError {}
+
+impl Template {
+    pub fn parse(text: &str) -> Result<Template, TemplateError> {
+        let mut segments = Vec::new();
+        let mut literal = String::new();
+        let mut chars = text.chars().peekable();
+
+        while let Some(c) = chars.next() {
+            match c {
+                '{' if chars.peek() == Some(&'{') => {
+                    chars.next();
+                    literal.push('{');
+                }
+                '}' if chars.peek() == Some(&'}') => {
+                    chars.next();
+                    literal.push('}');
+                }
+                '}' => return Err(TemplateError::UnmatchedBrace),
+                '{' => {
+                    let mut placeholder = String::new();
+                    loop {
+                        match chars.next() {
+                            Some('}') => break,
+                            Some(c) => placeholder.push(c),
+                            None => return Err(TemplateError::UnclosedPlaceholder),
+                        }
+                    }
+                    let segment = match placeholder.trim() {
+                        "name" => Segment::Name,
+                        "salutation" => Segment::Salutation,
+                        "formality" => Segment::Formality,
+                        other => {
+                            return Err(TemplateError::UnknownPlaceholder(String::from(other)));
+                        }
+                    };
+                    if !literal.is_empty() {
+                        segments.push(Segment::Text(std::mem::take(&mut literal)));
+                    }
+                    segments.push(segment);
+                }
+                c => literal.push(c),
+            }
+        }
+        if !literal.is_empty() {
+            segments.push(Segment::Text(literal));
+        }
+        Ok(Template { segments })
+    }
+
+    pub fn mentions_name(&self) -> bool {
+        self.segments.contains(&Segment::Name)
+    }
+
+    pub fn mentions_formality(&self) -> bool {
+        self.segments.contains(&Segment::Formality)
+    }
+
+    pub fn render(&self, name: &str, salutation: &str, formality: &str) -> String {
+        let mut out = String::new();
+        for segment in &self.segments {
+            match segment {
+                Segment::Text(text) => out.push_str(text),
+                Segment::Name => out.push_str(name),
+                Segment::Salutation => out.push_str(salutation),
+                Segment::Formality => out.push_str(formality),
+            }
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn substitutes_placeholders_and_escapes() {
+        let template = Template::parse("{salutation}, {name}! {{not a placeholder}}").unwrap();
+        assert_eq!(
+            template.render("Carol", "Hello", "Ms."),
+            "Hello, Carol! {not a placeholder}"
+        );
+        assert!(!template.mentions_formality());
+
+        let template = Template::parse("{salutation}, { formality } {name}.").unwrap();
+        assert!(template.mentions_formality());
+        assert_eq!(
+            template.render("Carol", "Good morning", "Dr."),
+            "Good morning, Dr. Carol."
+        );
+    }
+
+    #[test]
+    fn rejects_unknown_and_broken_placeholders() {
+        assert_eq!(
+            Template::parse("Hi {nmae}"),
+            Err(TemplateError::UnknownPlaceholder(String::from("nmae")))
+        );
+        assert_eq!(
+            Template::parse("Hi { nmae }"),
+            Err(TemplateError::UnknownPlaceholder(String::from("nmae")))
+        );
+        assert_eq!(
+            Template::parse("Hi {name"),
+            Err(TemplateError::UnclosedPlaceholder)
+        );
+        assert_eq!(
+            Template::parse("Hi name}"),
+            Err(TemplateError::UnmatchedBrace)
+        );
+    }
+}
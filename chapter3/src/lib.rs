@@ -0,0 +1,2 @@
+pub mod numtheory;
+pub mod temperature;
@@ -1,9 +1,122 @@
+use std::process;
+
+use chapter3::numtheory::{self, Primes, Recurrence};
+use chapter3::temperature::Temperature;
+
+const USAGE: &str = "\
+usage: chapter3                            run the chapter 3 demos
+       chapter3 divisible <n> <divisor>... which divisors divide n
+       chapter3 prime <n>                  is n prime
+       chapter3 primes <count>             the first count primes
+       chapter3 factor <n>                 prime factorization of n
+       chapter3 gcd <a> <b>
+       chapter3 lcm <a> <b>
+       chapter3 fib <n>                    the nth Fibonacci number
+       chapter3 lucas <n>                  the nth Lucas number
+       chapter3 temp <value>C|F            convert a temperature";
+
 fn main() {
-    variables_and_mutability();
-    data_types();
-    functions();
-    comments();
-    control_flow();
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if args.is_empty() {
+        variables_and_mutability();
+        data_types();
+        functions();
+        comments();
+        control_flow();
+        return;
+    }
+
+    match run(&args) {
+        Ok(output) => println!("{output}"),
+        Err(message) => {
+            eprintln!("{message}");
+            process::exit(1);
+        }
+    }
+}
+
+fn number(arg: Option<&String>) -> Result<u64, String> {
+    let arg = arg.ok_or_else(|| format!("missing number\n\n{USAGE}"))?;
+    arg.parse()
+        .map_err(|_| format!("`{arg}` is not a non-negative integer"))
+}
+
+fn run(args: &[String]) -> Result<String, String> {
+    let (command, rest) = args.split_first().ok_or_else(|| String::from(USAGE))?;
+    let first = number(rest.first());
+    let second = number(rest.get(1));
+    let output = match command.as_str() {
+        "divisible" => {
+            let n = first?;
+            if rest.len() < 2 {
+                return Err(format!("missing divisor\n\n{USAGE}"));
+            }
+            let divisors = rest[1..]
+                .iter()
+                .map(|d| number(Some(d)))
+                .collect::<Result<Vec<_>, _>>()?;
+            numtheory::divisibility(n, &divisors)
+                .map_err(|e| e.to_string())?
+                .to_string()
+        }
+        "prime" => {
+            let n = first?;
+            if numtheory::is_prime(n) {
+                format!("{n} is prime")
+            } else {
+                format!("{n} is not prime")
+            }
+        }
+        "primes" => Primes::new()
+            .take(first? as usize)
+            .map(|p| p.to_string())
+            .collect::<Vec<_>>()
+            .join(" "),
+        "factor" => {
+            let n = first?;
+            let factors = numtheory::factorize(n).map_err(|e| e.to_string())?;
+            let terms: Vec<String> = factors
+                .iter()
+                .map(|&(p, k)| {
+                    if k == 1 {
+                        p.to_string()
+                    } else {
+                        format!("{p}^{k}")
+                    }
+                })
+                .collect();
+            format!(
+                "{n} = {}",
+                if terms.is_empty() {
+                    String::from("1")
+                } else {
+                    terms.join(" * ")
+                }
+            )
+        }
+        "gcd" => numtheory::gcd(first?, second?).to_string(),
+        "lcm" => numtheory::lcm(first?, second?)
+            .map_err(|e| e.to_string())?
+            .to_string(),
+        "fib" => numtheory::fibonacci(first?)
+            .map_err(|e| e.to_string())?
+            .to_string(),
+        "lucas" => Recurrence::LUCAS
+            .nth_term(first?)
+            .map_err(|e| e.to_string())?
+            .to_string(),
+        "temp" => {
+            let arg = rest
+                .first()
+                .ok_or_else(|| format!("missing temperature\n\n{USAGE}"))?;
+            let temperature = Temperature::parse(arg)
+                .ok_or_else(|| format!("`{arg}` is not a temperature like 98.6F or 37C"))?;
+            format!("{temperature} = {}", temperature.convert())
+        }
+        "help" => String::from(USAGE),
+        _ => return Err(format!("unknown command `{command}`\n\n{USAGE}")),
+    };
+    Ok(output)
 }
 
 // 3.1 Variables and Mutability
@@ -197,7 +310,6 @@ fn control_flow() {
     };
     println!("The result is {result}\n");
 
-
     // Loop labels
     println!("Loop labels");
     let mut count = 0;
@@ -0,0 +1,473 @@
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NumTheoryError {
+    ZeroDivisor,
+    ZeroHasNoFactorization,
+    Overflow,
+}
+
+impl fmt::Display for NumTheoryError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            NumTheoryError::ZeroDivisor => write!(f, "cannot divide by zero"),
+            NumTheoryError::ZeroHasNoFactorization => write!(f, "zero has no prime factorization"),
+            NumTheoryError::Overflow => write!(f, "result does not fit in a u64"),
+        }
+    }
+}
+
+impl std::error::Error for NumTheoryError {}
+
+// 3.5's `if number % 4 == 0 ... else if number % 3 == 0` chain, for any number
+// and any list of divisors.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DivisibilityReport {
+    pub number: u64,
+    pub divisors: Vec<u64>,
+    pub divisible_by: Vec<u64>,
+}
+
+impl DivisibilityReport {
+    // The first divisor in the given order that divides the number, which is
+    // the branch the original if/else chain would take.
+    pub fn first_divisor(&self) -> Option<u64> {
+        self.divisible_by.first().copied()
+    }
+}
+
+impl fmt::Display for DivisibilityReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let list = |values: &[u64]| {
+            values
+                .iter()
+                .map(|d| d.to_string())
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+        if self.divisible_by.is_empty() {
+            write!(
+                f,
+                "{} is not divisible by {}",
+                self.number,
+                list(&self.divisors)
+            )
+        } else {
+            write!(
+                f,
+                "{} is divisible by {}",
+                self.number,
+                list(&self.divisible_by)
+            )
+        }
+    }
+}
+
+pub fn divisibility(number: u64, divisors: &[u64]) -> Result<DivisibilityReport, NumTheoryError> {
+    if divisors.contains(&0) {
+        return Err(NumTheoryError::ZeroDivisor);
+    }
+    Ok(DivisibilityReport {
+        number,
+        divisors: divisors.to_vec(),
+        divisible_by: divisors
+            .iter()
+            .copied()
+            .filter(|&d| number.is_multiple_of(d))
+            .collect(),
+    })
+}
+
+pub fn gcd(mut a: u64, mut b: u64) -> u64 {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+pub fn lcm(a: u64, b: u64) -> Result<u64, NumTheoryError> {
+    if a == 0 || b == 0 {
+        return Ok(0);
+    }
+    (a / gcd(a, b))
+        .checked_mul(b)
+        .ok_or(NumTheoryError::Overflow)
+}
+
+fn mul_mod(a: u64, b: u64, m: u64) -> u64 {
+    (u128::from(a) * u128::from(b) % u128::from(m)) as u64
+}
+
+fn pow_mod(mut base: u64, mut exp: u64, m: u64) -> u64 {
+    let mut result = 1 % m;
+    base %= m;
+    while exp > 0 {
+        if exp & 1 == 1 {
+            result = mul_mod(result, base, m);
+        }
+        base = mul_mod(base, base, m);
+        exp >>= 1;
+    }
+    result
+}
+
+// Deterministic Miller-Rabin: testing against the first twelve primes as
+// witnesses is enough to classify every u64 correctly.
+pub fn is_prime(n: u64) -> bool {
+    const WITNESSES: [u64; 12] = [2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37];
+
+    if n < 2 {
+        return false;
+    }
+    for p in WITNESSES {
+        if n.is_multiple_of(p) {
+            return n == p;
+        }
+    }
+
+    let s = (n - 1).trailing_zeros();
+    let d = (n - 1) >> s;
+    'witness: for a in WITNESSES {
+        let mut x = pow_mod(a, d, n);
+        if x == 1 || x == n - 1 {
+            continue;
+        }
+        for _ in 1..s {
+            x = mul_mod(x, x, n);
+            if x == n - 1 {
+                continue 'witness;
+            }
+        }
+        return false;
+    }
+    true
+}
+
+// The sieve of Eratosthenes over 0..=limit.
+pub fn sieve(limit: u64) -> Vec<u64> {
+    let limit = limit as usize;
+    let mut composite = vec![false; limit + 1];
+    let mut primes = Vec::new();
+    for n in 2..=limit {
+        if composite[n] {
+            continue;
+        }
+        primes.push(n as u64);
+        for multiple in (n * n..=limit).step_by(n) {
+            composite[multiple] = true;
+        }
+    }
+    primes
+}
+
+// An endless iterator over the primes, produced by sieving one segment of
+// numbers at a time so memory stays bounded.
+pub struct Primes {
+    base: Vec<u64>,
+    segment: Vec<u64>,
+    next_in_segment: usize,
+    segment_start: u64,
+}
+
+const SEGMENT_SIZE: u64 = 1 << 15;
+
+impl Default for Primes {
+    fn default() -> Primes {
+        Primes::new()
+    }
+}
+
+impl Primes {
+    pub fn new() -> Primes {
+        Primes {
+            base: Vec::new(),
+            segment: Vec::new(),
+            next_in_segment: 0,
+            segment_start: 0,
+        }
+    }
+
+    fn sieve_next_segment(&mut self) {
+        let low = self.segment_start;
+        let high = low + SEGMENT_SIZE;
+        self.segment_start = high;
+
+        // Base primes up to sqrt(high) are enough to sieve this segment.
+        let root = high.isqrt() + 1;
+        if self.base.last().is_none_or(|&p| p < root) {
+            self.base = sieve(root.max(2) * 2);
+        }
+
+        let mut composite = vec![false; SEGMENT_SIZE as usize];
+        for &p in &self.base {
+            if p * p >= high {
+                break;
+            }
+            let first = (p * p).max(low.div_ceil(p) * p);
+            for multiple in (first..high).step_by(p as usize) {
+                composite[(multiple - low) as usize] = true;
+            }
+        }
+        self.segment = (low.max(2)..high)
+            .filter(|&n| !composite[(n - low) as usize])
+            .collect();
+        self.next_in_segment = 0;
+    }
+}
+
+impl Iterator for Primes {
+    type Item = u64;
+
+    fn next(&mut self) -> Option<u64> {
+        while self.next_in_segment >= self.segment.len() {
+            self.sieve_next_segment();
+        }
+        self.next_in_segment += 1;
+        Some(self.segment[self.next_in_segment - 1])
+    }
+}
+
+// Finds a nontrivial factor of an odd composite number with Pollard's rho.
+fn pollard_rho(n: u64) -> u64 {
+    let mut c = 1;
+    loop {
+        let f = |x: u64| (mul_mod(x, x, n) + c) % n;
+        let (mut x, mut y, mut d) = (2, 2, 1);
+        while d == 1 {
+            x = f(x);
+            y = f(f(y));
+            d = gcd(x.abs_diff(y), n);
+        }
+        if d != n {
+            return d;
+        }
+        c += 1;
+    }
+}
+
+// Prime factors with their exponents, smallest prime first: 360 gives
+// [(2, 3), (3, 2), (5, 1)].
+pub fn factorize(n: u64) -> Result<Vec<(u64, u32)>, NumTheoryError> {
+    if n == 0 {
+        return Err(NumTheoryError::ZeroHasNoFactorization);
+    }
+
+    let mut primes = Vec::new();
+    let mut rest = n;
+    for p in [2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37] {
+        while rest.is_multiple_of(p) {
+            primes.push(p);
+            rest /= p;
+        }
+    }
+
+    let mut pending = vec![rest];
+    while let Some(m) = pending.pop() {
+        if m == 1 {
+            continue;
+        }
+        if is_prime(m) {
+            primes.push(m);
+        } else {
+            let d = pollard_rho(m);
+            pending.push(d);
+            pending.push(m / d);
+        }
+    }
+    primes.sort_unstable();
+
+    let mut factors: Vec<(u64, u32)> = Vec::new();
+    for p in primes {
+        match factors.last_mut() {
+            Some((last, count)) if *last == p => *count += 1,
+            _ => factors.push((p, 1)),
+        }
+    }
+    Ok(factors)
+}
+
+// Sequences where each term is `p * previous + q * the one before`, such as
+// Fibonacci (p = q = 1). Iteration stops at the last term that fits in a u64.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Recurrence {
+    pub first: u64,
+    pub second: u64,
+    pub p: u64,
+    pub q: u64,
+}
+
+impl Recurrence {
+    pub const FIBONACCI: Recurrence = Recurrence {
+        first: 0,
+        second: 1,
+        p: 1,
+        q: 1,
+    };
+
+    pub const LUCAS: Recurrence = Recurrence {
+        first: 2,
+        second: 1,
+        p: 1,
+        q: 1,
+    };
+
+    pub const PELL: Recurrence = Recurrence {
+        first: 0,
+        second: 1,
+        p: 2,
+        q: 1,
+    };
+
+    fn step(&self, before: u64, previous: u64) -> Option<u64> {
+        self.p
+            .checked_mul(previous)?
+            .checked_add(self.q.checked_mul(before)?)
+    }
+
+    pub fn terms(&self) -> Terms {
+        Terms {
+            recurrence: *self,
+            state: Some((self.first, Some(self.second))),
+        }
+    }
+
+    // The nth term, counting the first as term 0.
+    pub fn nth_term(&self, n: u64) -> Result<u64, NumTheoryError> {
+        if n == 0 {
+            return Ok(self.first);
+        }
+        let (mut a, mut b) = (self.first, self.second);
+        for _ in 1..n {
+            let next = self.step(a, b).ok_or(NumTheoryError::Overflow)?;
+            (a, b) = (b, next);
+        }
+        Ok(b)
+    }
+}
+
+pub struct Terms {
+    recurrence: Recurrence,
+    state: Option<(u64, Option<u64>)>,
+}
+
+impl Iterator for Terms {
+    type Item = u64;
+
+    fn next(&mut self) -> Option<u64> {
+        let (current, next) = self.state?;
+        self.state = next.map(|n| (n, self.recurrence.step(current, n)));
+        Some(current)
+    }
+}
+
+// The nth Fibonacci number by fast doubling, in O(log n) steps. Fails once
+// the answer no longer fits in a u64, which first happens at n = 94.
+pub fn fibonacci(n: u64) -> Result<u64, NumTheoryError> {
+    // Returns (F(k), F(k + 1)), where F(k + 1) is None if only it overflows.
+    fn doubling(k: u64) -> Result<(u64, Option<u64>), NumTheoryError> {
+        if k == 0 {
+            return Ok((0, Some(1)));
+        }
+        let (a, b) = doubling(k / 2)?;
+        let b = b.ok_or(NumTheoryError::Overflow)?;
+        let overflow = NumTheoryError::Overflow;
+        // F(2m) = F(m) * (2F(m+1) - F(m)), F(2m+1) = F(m)^2 + F(m+1)^2
+        let even = b
+            .checked_mul(2)
+            .and_then(|two_b| a.checked_mul(two_b - a))
+            .ok_or(overflow);
+        let odd = a
+            .checked_mul(a)
+            .and_then(|a2| b.checked_mul(b).and_then(|b2| a2.checked_add(b2)));
+        if k.is_multiple_of(2) {
+            Ok((even?, odd))
+        } else {
+            let odd = odd.ok_or(overflow)?;
+            Ok((odd, even.ok().and_then(|e| e.checked_add(odd))))
+        }
+    }
+    doubling(n).map(|(f, _)| f)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn divisibility_matches_original_chain() {
+        let report = divisibility(6, &[4, 3, 2]).unwrap();
+        assert_eq!(report.divisible_by, vec![3, 2]);
+        assert_eq!(report.first_divisor(), Some(3));
+        assert_eq!(report.to_string(), "6 is divisible by 3, 2");
+        assert_eq!(
+            divisibility(7, &[4, 3, 2]).unwrap().to_string(),
+            "7 is not divisible by 4, 3, 2"
+        );
+        assert_eq!(divisibility(6, &[0]), Err(NumTheoryError::ZeroDivisor));
+    }
+
+    #[test]
+    fn miller_rabin_agrees_with_sieve() {
+        let primes = sieve(10_000);
+        for n in 0..=10_000 {
+            assert_eq!(is_prime(n), primes.binary_search(&n).is_ok(), "{n}");
+        }
+        assert!(is_prime(18_446_744_073_709_551_557));
+        assert!(!is_prime(3_215_031_751)); // strong pseudoprime to bases 2, 3, 5, 7
+        assert!(!is_prime(u64::MAX));
+    }
+
+    #[test]
+    fn segmented_primes_cross_segment_boundaries() {
+        let expected = sieve(100_000);
+        let actual: Vec<u64> = Primes::new().take_while(|&p| p <= 100_000).collect();
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn factorizes_large_numbers() {
+        assert_eq!(factorize(360), Ok(vec![(2, 3), (3, 2), (5, 1)]));
+        assert_eq!(factorize(1), Ok(vec![]));
+        assert_eq!(
+            factorize(600_851_475_143),
+            Ok(vec![(71, 1), (839, 1), (1471, 1), (6857, 1)])
+        );
+        assert_eq!(
+            factorize(4_294_967_291 * 4_294_967_279),
+            Ok(vec![(4_294_967_279, 1), (4_294_967_291, 1)])
+        );
+        assert_eq!(factorize(0), Err(NumTheoryError::ZeroHasNoFactorization));
+    }
+
+    #[test]
+    fn gcd_and_lcm() {
+        assert_eq!(gcd(48, 18), 6);
+        assert_eq!(gcd(0, 5), 5);
+        assert_eq!(lcm(4, 6), Ok(12));
+        assert_eq!(lcm(u64::MAX, u64::MAX - 1), Err(NumTheoryError::Overflow));
+    }
+
+    #[test]
+    fn fibonacci_detects_overflow() {
+        assert_eq!(fibonacci(0), Ok(0));
+        assert_eq!(fibonacci(10), Ok(55));
+        assert_eq!(fibonacci(93), Ok(12_200_160_415_121_876_738));
+        assert_eq!(fibonacci(94), Err(NumTheoryError::Overflow));
+        for n in 0..=93 {
+            assert_eq!(fibonacci(n), Recurrence::FIBONACCI.nth_term(n));
+        }
+        assert_eq!(Recurrence::FIBONACCI.terms().count(), 94);
+        assert_eq!(
+            Recurrence::FIBONACCI.nth_term(94),
+            Err(NumTheoryError::Overflow)
+        );
+    }
+
+    #[test]
+    fn other_recurrences() {
+        let lucas: Vec<u64> = Recurrence::LUCAS.terms().take(6).collect();
+        assert_eq!(lucas, [2, 1, 3, 4, 7, 11]);
+        let pell: Vec<u64> = Recurrence::PELL.terms().take(6).collect();
+        assert_eq!(pell, [0, 1, 2, 5, 12, 29]);
+    }
+}
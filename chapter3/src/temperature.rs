@@ -0,0 +1,67 @@
+use std::fmt;
+
+// The temperature exercise from the end of chapter 3: convert between
+// Fahrenheit and Celsius.
+
+pub fn fahrenheit_to_celsius(f: f64) -> f64 {
+    (f - 32.0) * 5.0 / 9.0
+}
+
+pub fn celsius_to_fahrenheit(c: f64) -> f64 {
+    c * 9.0 / 5.0 + 32.0
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Temperature {
+    Celsius(f64),
+    Fahrenheit(f64),
+}
+
+impl Temperature {
+    // Reads a value with a trailing unit, such as "98.6F" or "-40 c".
+    pub fn parse(s: &str) -> Option<Temperature> {
+        let s = s.trim();
+        let unit = s.chars().last()?;
+        let value: f64 = s[..s.len() - unit.len_utf8()].trim().parse().ok()?;
+        match unit.to_ascii_uppercase() {
+            'C' => Some(Temperature::Celsius(value)),
+            'F' => Some(Temperature::Fahrenheit(value)),
+            _ => None,
+        }
+    }
+
+    // The same temperature in the other unit.
+    pub fn convert(self) -> Temperature {
+        match self {
+            Temperature::Celsius(c) => Temperature::Fahrenheit(celsius_to_fahrenheit(c)),
+            Temperature::Fahrenheit(f) => Temperature::Celsius(fahrenheit_to_celsius(f)),
+        }
+    }
+}
+
+impl fmt::Display for Temperature {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Temperature::Celsius(c) => write!(f, "{c:.1}°C"),
+            Temperature::Fahrenheit(v) => write!(f, "{v:.1}°F"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn converts_both_ways() {
+        assert_eq!(fahrenheit_to_celsius(212.0), 100.0);
+        assert_eq!(celsius_to_fahrenheit(-40.0), -40.0);
+        assert_eq!(
+            Temperature::parse("98.6F").unwrap().convert().to_string(),
+            "37.0°C"
+        );
+        assert_eq!(Temperature::parse(" 0 c"), Some(Temperature::Celsius(0.0)));
+        assert_eq!(Temperature::parse("12K"), None);
+        assert_eq!(Temperature::parse(""), None);
+    }
+}
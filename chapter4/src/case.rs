@@ -0,0 +1,338 @@
+// Unicode-aware case conversion over `&str` and `String`.
+//
+// The standard library already knows most Unicode case mappings, including
+// ones that change length like `ß` -> `SS` and ones that depend on context
+// like the final sigma in `ΟΔΟΣ` -> `οδος`, so whole strings go through
+// `str::to_uppercase` and `str::to_lowercase`. What it lacks is titlecase
+// (`ß` at the start of a word becomes `Ss`, not `SS`) and the Turkish and
+// Azerbaijani dotted/dotless i, which this module adds behind `Locale`.
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Locale {
+    #[default]
+    Default,
+    // Turkish and Azerbaijani: `i` <-> `İ` and `ı` <-> `I`.
+    Turkish,
+}
+
+// Words that stay lowercase in a title unless they start or end it.
+const SMALL_WORDS: [&str; 17] = [
+    "a", "an", "and", "as", "at", "but", "by", "for", "in", "nor", "of", "on", "or", "the", "to",
+    "up", "via",
+];
+
+fn push_upper(out: &mut String, c: char, locale: Locale) {
+    match (locale, c) {
+        (Locale::Turkish, 'i') => out.push('İ'),
+        _ => out.extend(c.to_uppercase()),
+    }
+}
+
+// Titlecase differs from uppercase for `ß` and the Latin digraphs, which get
+// only their first letter capitalized.
+fn push_title(out: &mut String, c: char, locale: Locale) {
+    match c {
+        'ß' => out.push_str("Ss"),
+        'Ǆ' | 'ǅ' | 'ǆ' => out.push('ǅ'),
+        'Ǉ' | 'ǈ' | 'ǉ' => out.push('ǈ'),
+        'Ǌ' | 'ǋ' | 'ǌ' => out.push('ǋ'),
+        'Ǳ' | 'ǲ' | 'ǳ' => out.push('ǲ'),
+        _ => push_upper(out, c, locale),
+    }
+}
+
+// The Turkish letters are swapped for their cased counterparts first, so the
+// rest of the string, and any context-sensitive mapping, is left to std.
+pub fn to_upper(s: &str, locale: Locale) -> String {
+    match locale {
+        Locale::Default => s.to_uppercase(),
+        Locale::Turkish => s.replace('i', "İ").to_uppercase(),
+    }
+}
+
+pub fn to_lower(s: &str, locale: Locale) -> String {
+    match locale {
+        Locale::Default => s.to_lowercase(),
+        Locale::Turkish => s.replace('I', "ı").replace('İ', "i").to_lowercase(),
+    }
+}
+
+pub fn capitalize_first(s: &str, locale: Locale) -> String {
+    let mut chars = s.chars();
+    match chars.next() {
+        None => String::new(),
+        Some(first) => {
+            let mut out = String::with_capacity(s.len());
+            push_title(&mut out, first, locale);
+            out.push_str(chars.as_str());
+            out
+        }
+    }
+}
+
+// Capitalizes every word except the small ones in the middle of the title.
+// Whitespace between words is kept exactly as it was.
+pub fn title_case(s: &str, locale: Locale) -> String {
+    let mut words: Vec<(usize, &str)> = Vec::new();
+    let mut start = None;
+    for (i, c) in s.char_indices() {
+        if !c.is_whitespace() {
+            start.get_or_insert(i);
+        } else if let Some(begin) = start.take() {
+            words.push((begin, &s[begin..i]));
+        }
+    }
+    if let Some(begin) = start {
+        words.push((begin, &s[begin..]));
+    }
+
+    let mut out = String::with_capacity(s.len());
+    let mut copied = 0;
+    for (index, &(start, word)) in words.iter().enumerate() {
+        out.push_str(&s[copied..start]);
+        copied = start + word.len();
+
+        let inner = index != 0 && index != words.len() - 1;
+        let lower = to_lower(word, locale);
+        if inner && SMALL_WORDS.contains(&lower.as_str()) {
+            out.push_str(&lower);
+        } else {
+            out.push_str(&capitalize_first(word, locale));
+        }
+    }
+    out.push_str(&s[copied..]);
+    out
+}
+
+// Splits an identifier in any of the supported styles into its words:
+// `parseHTTPResponse2` -> ["parse", "HTTP", "Response2"]. The words borrow
+// from the input.
+pub fn split_identifier(s: &str) -> Vec<&str> {
+    let mut words = Vec::new();
+    let mut start: Option<usize> = None;
+    let chars: Vec<(usize, char)> = s.char_indices().collect();
+
+    for (n, &(i, c)) in chars.iter().enumerate() {
+        if c == '_' || c == '-' || c.is_whitespace() {
+            if let Some(begin) = start.take() {
+                words.push(&s[begin..i]);
+            }
+            continue;
+        }
+        let Some(begin) = start else {
+            start = Some(i);
+            continue;
+        };
+        let prev = chars[n - 1].1;
+        let next = chars.get(n + 1).map(|&(_, c)| c);
+        // "camelCase" breaks before the `C`; "HTTPServer" breaks before the
+        // `S` because it starts a capitalized word after an acronym.
+        let lower_to_upper = c.is_uppercase() && (prev.is_lowercase() || prev.is_numeric());
+        let acronym_end =
+            c.is_uppercase() && prev.is_uppercase() && next.is_some_and(char::is_lowercase);
+        if lower_to_upper || acronym_end {
+            words.push(&s[begin..i]);
+            start = Some(i);
+        }
+    }
+    if let Some(begin) = start {
+        words.push(&s[begin..]);
+    }
+    words
+}
+
+fn join_words(s: &str, separator: &str, mut convert: impl FnMut(usize, &str) -> String) -> String {
+    let mut out = String::with_capacity(s.len());
+    for (index, word) in split_identifier(s).into_iter().enumerate() {
+        if index > 0 {
+            out.push_str(separator);
+        }
+        out.push_str(&convert(index, word));
+    }
+    out
+}
+
+fn capitalized(word: &str) -> String {
+    capitalize_first(&to_lower(word, Locale::Default), Locale::Default)
+}
+
+pub fn to_snake_case(s: &str) -> String {
+    join_words(s, "_", |_, w| to_lower(w, Locale::Default))
+}
+
+pub fn to_kebab_case(s: &str) -> String {
+    join_words(s, "-", |_, w| to_lower(w, Locale::Default))
+}
+
+pub fn to_screaming_snake_case(s: &str) -> String {
+    join_words(s, "_", |_, w| to_upper(w, Locale::Default))
+}
+
+pub fn to_camel_case(s: &str) -> String {
+    join_words(s, "", |i, w| {
+        if i == 0 {
+            to_lower(w, Locale::Default)
+        } else {
+            capitalized(w)
+        }
+    })
+}
+
+pub fn to_pascal_case(s: &str) -> String {
+    join_words(s, "", |_, w| capitalized(w))
+}
+
+// Rewrites `s` one char at a time. When every replacement has the same UTF-8
+// length as the char it replaces, the edits happen in the existing buffer
+// with no reallocation; otherwise the converted string replaces it.
+fn map_in_place(s: &mut String, mut map: impl FnMut(&mut String, char)) {
+    let mut scratch = String::with_capacity(4);
+    let mut same_length = true;
+    for c in s.chars() {
+        scratch.clear();
+        map(&mut scratch, c);
+        if scratch.len() != c.len_utf8() {
+            same_length = false;
+            break;
+        }
+    }
+
+    if !same_length {
+        let mut out = String::with_capacity(s.len() + 8);
+        for c in s.chars() {
+            map(&mut out, c);
+        }
+        *s = out;
+        return;
+    }
+
+    let mut i = 0;
+    while let Some(c) = s[i..].chars().next() {
+        scratch.clear();
+        map(&mut scratch, c);
+        if !scratch.starts_with(c) {
+            s.replace_range(i..i + c.len_utf8(), &scratch);
+        }
+        i += c.len_utf8();
+    }
+}
+
+pub fn uppercase_in_place(s: &mut String, locale: Locale) {
+    map_in_place(s, |out, c| push_upper(out, c, locale));
+}
+
+// Lowercasing can depend on the neighbouring chars (final sigma), so it can't
+// go one char at a time. The result is still copied back into the existing
+// buffer when it fits.
+pub fn lowercase_in_place(s: &mut String, locale: Locale) {
+    let lower = to_lower(s, locale);
+    if lower.len() <= s.capacity() {
+        s.clear();
+        s.push_str(&lower);
+    } else {
+        *s = lower;
+    }
+}
+
+pub fn capitalize_first_in_place(s: &mut String, locale: Locale) {
+    let Some(first) = s.chars().next() else {
+        return;
+    };
+    let mut title = String::with_capacity(4);
+    push_title(&mut title, first, locale);
+    if title != first.to_string() {
+        s.replace_range(..first.len_utf8(), &title);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn capitalize_handles_empty_and_unicode() {
+        assert_eq!(capitalize_first("", Locale::Default), "");
+        assert_eq!(capitalize_first("élan", Locale::Default), "Élan");
+        assert_eq!(capitalize_first("ßig", Locale::Default), "Ssig");
+        assert_eq!(capitalize_first("ǆungla", Locale::Default), "ǅungla");
+        assert_eq!(capitalize_first("istanbul", Locale::Turkish), "İstanbul");
+    }
+
+    #[test]
+    fn special_case_mappings() {
+        assert_eq!(to_upper("straße", Locale::Default), "STRASSE");
+        assert_eq!(to_upper("istanbul", Locale::Default), "ISTANBUL");
+        assert_eq!(to_upper("istanbul", Locale::Turkish), "İSTANBUL");
+        assert_eq!(to_lower("DİYARBAKIR", Locale::Turkish), "diyarbakır");
+    }
+
+    #[test]
+    fn lowercases_final_sigma() {
+        assert_eq!(to_lower("ΟΔΟΣ", Locale::Default), "οδος");
+        assert_eq!(to_lower("ΟΔΟΣ", Locale::Default), "ΟΔΟΣ".to_lowercase());
+        assert_eq!(to_lower("ΣΑΣ ΟΔΟΣ.", Locale::Turkish), "σας οδος.");
+        assert_eq!(to_lower("Σ", Locale::Default), "σ");
+
+        let mut s = String::from("ΟΔΟΣ");
+        lowercase_in_place(&mut s, Locale::Default);
+        assert_eq!(s, "οδος");
+    }
+
+    #[test]
+    fn title_case_keeps_small_words_lowercase() {
+        assert_eq!(
+            title_case("the lord OF the  rings", Locale::Default),
+            "The Lord of the  Rings"
+        );
+        assert_eq!(
+            title_case("a tale to look up", Locale::Default),
+            "A Tale to Look Up"
+        );
+        assert_eq!(title_case("", Locale::Default), "");
+    }
+
+    #[test]
+    fn splits_identifiers_in_every_style() {
+        assert_eq!(
+            split_identifier("parseHTTPResponse2"),
+            ["parse", "HTTP", "Response2"]
+        );
+        assert_eq!(split_identifier("__snake_case__"), ["snake", "case"]);
+        assert_eq!(split_identifier("kebab-case id"), ["kebab", "case", "id"]);
+        assert_eq!(split_identifier("ÜberGroße"), ["Über", "Große"]);
+    }
+
+    #[test]
+    fn converts_between_styles() {
+        let s = "XMLHttpRequest";
+        assert_eq!(to_snake_case(s), "xml_http_request");
+        assert_eq!(to_kebab_case(s), "xml-http-request");
+        assert_eq!(to_screaming_snake_case(s), "XML_HTTP_REQUEST");
+        assert_eq!(to_camel_case(s), "xmlHttpRequest");
+        assert_eq!(to_pascal_case("xml_http_request"), "XmlHttpRequest");
+        assert_eq!(to_camel_case(&to_kebab_case("userId")), "userId");
+        assert_eq!(to_screaming_snake_case("straßeName"), "STRASSE_NAME");
+    }
+
+    #[test]
+    fn in_place_keeps_buffer_when_length_is_unchanged() {
+        let mut s = String::from("héllo wörld");
+        let (ptr, capacity) = (s.as_ptr(), s.capacity());
+        uppercase_in_place(&mut s, Locale::Default);
+        assert_eq!(s, "HÉLLO WÖRLD");
+        assert_eq!((s.as_ptr(), s.capacity()), (ptr, capacity));
+
+        lowercase_in_place(&mut s, Locale::Default);
+        capitalize_first_in_place(&mut s, Locale::Default);
+        assert_eq!(s, "Héllo wörld");
+        assert_eq!((s.as_ptr(), s.capacity()), (ptr, capacity));
+
+        let mut grows = String::from("straße");
+        uppercase_in_place(&mut grows, Locale::Default);
+        assert_eq!(grows, "STRASSE");
+
+        let mut empty = String::new();
+        capitalize_first_in_place(&mut empty, Locale::Default);
+        assert_eq!(empty, "");
+    }
+}
@@ -1,3 +1,6 @@
+use chapter4::case::{self, Locale};
+use chapter4::tokenizer::{self, Tokenizer};
+
 fn main() {
     ownership();
     reference_and_borrowing();
@@ -74,8 +77,9 @@ fn reference_and_borrowing() {
     // println!("Third element is {}", *num); // error!
     
 
-    let mut char_v: Vec<char> = vec!['a', 'b', 'c'];
-    ascii_capitalize(&mut char_v);
+    let mut word = String::from("ßtraße");
+    case::capitalize_first_in_place(&mut word, Locale::Default);
+    println!("Capitalized: {word}");
 
 
     let mut strs = vec![
@@ -117,6 +121,19 @@ fn slice_type() {
     // this works too, without the slice syntax!
     let _word = first_word(my_string_literal);
 
+    // The same idea generalized: every word is a slice of the original string.
+    let tokenizer = Tokenizer::new().split_on_punctuation(true);
+    let text = "Hello, wörld! Slices borrow; they never copy.";
+    for word in tokenizer.words(text) {
+        println!("{:>2}: {}", word.start, word.text);
+    }
+    println!("second word: {:?}", tokenizer.nth_word(text, 1));
+    println!("last word: {:?}", tokenizer.last_word(text));
+    println!("word at byte 8: {:?}", tokenizer.word_at(text, 8));
+    for sentence in tokenizer::sentences(text) {
+        println!("sentence: {}", sentence.text);
+    }
+
     let a = [1,2,3,4,5];
     let slice = &a[1..3];
     assert_eq!(slice, &[2,3]);
@@ -224,26 +241,14 @@ fn no_dangle() ->String{
     s
 }
 
+// The tokenizer finds word boundaries without copying, so the returned slice
+// still borrows from `s` just like the original byte loop did. Taking the word
+// at offset 0 rather than the first word keeps the original behaviour for a
+// leading space: `first_word(" hello")` is "", not "hello".
 fn first_word(s: &str) -> &str {
-    let bytes = s.as_bytes();
-    for (i, &item) in bytes.iter().enumerate() {
-        if item == b' ' {
-            return &s[0..i];
-        }
-    }
-
-    &s[..]
-}
-
-fn ascii_capitalize(v: &mut Vec<char>) {
-    let c = &v[0];
-    if c.is_ascii_lowercase() {
-        let up = c.to_ascii_uppercase();
-        v[0] = up;
-        println!("Capitalized: {:?}", v);
-    } else {
-        println!("Already capitalized: {:?}", v);
-    }
+    Tokenizer::ascii_space()
+        .word_at(s, 0)
+        .map_or(&s[..0], |word| word.text)
 }
 
 fn get_first(v: &Vec<String>) -> &String {
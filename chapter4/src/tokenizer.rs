@@ -0,0 +1,361 @@
+// Splits text into words and sentences without allocating: every word is a
+// `&str` borrowed from the input, along with the byte offset it starts at.
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Whitespace {
+    // Only `' '`, like the original `first_word`.
+    AsciiSpace,
+    // Space, tab, newline, carriage return and form feed.
+    Ascii,
+    // Everything `char::is_whitespace` accepts, such as U+00A0 NO-BREAK SPACE
+    // and U+3000 IDEOGRAPHIC SPACE.
+    Unicode,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Tokenizer {
+    whitespace: Whitespace,
+    split_on_punctuation: bool,
+}
+
+impl Default for Tokenizer {
+    fn default() -> Tokenizer {
+        Tokenizer::new()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Word<'a> {
+    pub text: &'a str,
+    pub start: usize,
+}
+
+impl Word<'_> {
+    pub fn end(&self) -> usize {
+        self.start + self.text.len()
+    }
+}
+
+impl Tokenizer {
+    pub const fn new() -> Tokenizer {
+        Tokenizer {
+            whitespace: Whitespace::Unicode,
+            split_on_punctuation: false,
+        }
+    }
+
+    pub const fn ascii_space() -> Tokenizer {
+        Tokenizer {
+            whitespace: Whitespace::AsciiSpace,
+            split_on_punctuation: false,
+        }
+    }
+
+    pub const fn whitespace(mut self, whitespace: Whitespace) -> Tokenizer {
+        self.whitespace = whitespace;
+        self
+    }
+
+    // When set, punctuation separates words and is left out of them, so
+    // "Hello, world!" yields "Hello" and "world".
+    pub const fn split_on_punctuation(mut self, split: bool) -> Tokenizer {
+        self.split_on_punctuation = split;
+        self
+    }
+
+    pub fn is_separator(&self, c: char) -> bool {
+        let space = match self.whitespace {
+            Whitespace::AsciiSpace => c == ' ',
+            Whitespace::Ascii => c.is_ascii_whitespace(),
+            Whitespace::Unicode => c.is_whitespace(),
+        };
+        space || (self.split_on_punctuation && is_punctuation(c))
+    }
+
+    pub fn words<'a>(&self, s: &'a str) -> Words<'a> {
+        Words {
+            tokenizer: *self,
+            rest: s,
+            offset: 0,
+        }
+    }
+
+    pub fn first_word<'a>(&self, s: &'a str) -> Option<Word<'a>> {
+        self.words(s).next()
+    }
+
+    pub fn nth_word<'a>(&self, s: &'a str, n: usize) -> Option<Word<'a>> {
+        self.words(s).nth(n)
+    }
+
+    pub fn last_word<'a>(&self, s: &'a str) -> Option<Word<'a>> {
+        self.words(s).next_back()
+    }
+
+    // Finds the word that covers `byte_offset`. Returns `None` when the offset
+    // is past the end, not on a char boundary, or on a separator.
+    pub fn word_at<'a>(&self, s: &'a str, byte_offset: usize) -> Option<Word<'a>> {
+        if !s.is_char_boundary(byte_offset) || byte_offset >= s.len() {
+            return None;
+        }
+        let (before, after) = s.split_at(byte_offset);
+        if after.chars().next().is_some_and(|c| self.is_separator(c)) {
+            return None;
+        }
+        let start = before
+            .char_indices()
+            .rev()
+            .find(|&(_, c)| self.is_separator(c))
+            .map_or(0, |(i, c)| i + c.len_utf8());
+        let end = after
+            .char_indices()
+            .find(|&(_, c)| self.is_separator(c))
+            .map_or(s.len(), |(i, _)| byte_offset + i);
+        Some(Word {
+            text: &s[start..end],
+            start,
+        })
+    }
+}
+
+// Covers ASCII punctuation plus the punctuation most often met in Latin and
+// CJK text. The standard library has no Unicode general category lookup, so
+// this is a fixed list rather than every `P*` code point.
+pub fn is_punctuation(c: char) -> bool {
+    c.is_ascii_punctuation()
+        || matches!(
+            c,
+            '¡' | '§' | '«' | '¶' | '·' | '»' | '¿'
+                | '\u{2010}'..='\u{2027}'
+                | '\u{2030}'..='\u{205E}'
+                | '\u{3001}'..='\u{3003}'
+                | '\u{3008}'..='\u{3011}'
+                | '\u{3014}'..='\u{301F}'
+                | '\u{FF01}'..='\u{FF0F}'
+                | '\u{FF1A}'..='\u{FF1F}'
+        )
+}
+
+pub struct Words<'a> {
+    tokenizer: Tokenizer,
+    rest: &'a str,
+    offset: usize,
+}
+
+impl<'a> Iterator for Words<'a> {
+    type Item = Word<'a>;
+
+    fn next(&mut self) -> Option<Word<'a>> {
+        let skip = self
+            .rest
+            .char_indices()
+            .find(|&(_, c)| !self.tokenizer.is_separator(c))
+            .map_or(self.rest.len(), |(i, _)| i);
+        self.rest = &self.rest[skip..];
+        self.offset += skip;
+        if self.rest.is_empty() {
+            return None;
+        }
+
+        let len = self
+            .rest
+            .char_indices()
+            .find(|&(_, c)| self.tokenizer.is_separator(c))
+            .map_or(self.rest.len(), |(i, _)| i);
+        let word = Word {
+            text: &self.rest[..len],
+            start: self.offset,
+        };
+        self.rest = &self.rest[len..];
+        self.offset += len;
+        Some(word)
+    }
+}
+
+impl<'a> DoubleEndedIterator for Words<'a> {
+    fn next_back(&mut self) -> Option<Word<'a>> {
+        let end = self
+            .rest
+            .char_indices()
+            .rev()
+            .find(|&(_, c)| !self.tokenizer.is_separator(c))
+            .map(|(i, c)| i + c.len_utf8())?;
+        let start = self.rest[..end]
+            .char_indices()
+            .rev()
+            .find(|&(_, c)| self.tokenizer.is_separator(c))
+            .map_or(0, |(i, c)| i + c.len_utf8());
+        let word = Word {
+            text: &self.rest[start..end],
+            start: self.offset + start,
+        };
+        self.rest = &self.rest[..start];
+        Some(word)
+    }
+}
+
+// Splits text into sentences. A sentence ends at a run of `.`, `!`, `?`, `…`,
+// `。`, `！` or `？`, plus any closing quotes or brackets right after it, when
+// followed by whitespace or the end of the text. Abbreviations like "Dr." are
+// not recognised, so they end a sentence too.
+pub fn sentences(s: &str) -> Sentences<'_> {
+    Sentences { rest: s, offset: 0 }
+}
+
+pub struct Sentences<'a> {
+    rest: &'a str,
+    offset: usize,
+}
+
+fn is_terminator(c: char) -> bool {
+    matches!(c, '.' | '!' | '?' | '…' | '。' | '！' | '？')
+}
+
+fn is_closing(c: char) -> bool {
+    matches!(c, '"' | '\'' | ')' | ']' | '”' | '’' | '»' | '」' | '』')
+}
+
+impl<'a> Iterator for Sentences<'a> {
+    type Item = Word<'a>;
+
+    fn next(&mut self) -> Option<Word<'a>> {
+        let skip = self.rest.len() - self.rest.trim_start().len();
+        self.rest = &self.rest[skip..];
+        self.offset += skip;
+        if self.rest.is_empty() {
+            return None;
+        }
+
+        let mut chars = self.rest.char_indices().peekable();
+        let mut end = self.rest.len();
+        while let Some((_, c)) = chars.next() {
+            if !is_terminator(c) {
+                continue;
+            }
+            while let Some(&(_, next)) = chars.peek() {
+                if is_terminator(next) || is_closing(next) {
+                    chars.next();
+                } else {
+                    break;
+                }
+            }
+            // CJK full stops don't need a space after them.
+            let cjk = matches!(c, '。' | '！' | '？');
+            match chars.peek() {
+                None => break,
+                Some(&(i, next)) if next.is_whitespace() || cjk => {
+                    end = i;
+                    break;
+                }
+                Some(_) => {}
+            }
+        }
+
+        let text = self.rest[..end].trim_end();
+        let sentence = Word {
+            text,
+            start: self.offset,
+        };
+        self.rest = &self.rest[end..];
+        self.offset += end;
+        Some(sentence)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn texts<'a>(words: impl Iterator<Item = Word<'a>>) -> Vec<&'a str> {
+        words.map(|w| w.text).collect()
+    }
+
+    #[test]
+    fn words_carry_byte_offsets() {
+        let s = "  héllo\u{3000}wörld ";
+        let words: Vec<Word> = Tokenizer::new().words(s).collect();
+        assert_eq!(texts(words.iter().copied()), ["héllo", "wörld"]);
+        assert_eq!(words[1].start, 11);
+        assert_eq!(&s[words[1].start..words[1].end()], "wörld");
+        assert_eq!(
+            texts(Tokenizer::ascii_space().words(s)),
+            ["héllo\u{3000}wörld"]
+        );
+    }
+
+    #[test]
+    fn punctuation_can_split_words() {
+        let tokenizer = Tokenizer::new().split_on_punctuation(true);
+        assert_eq!(
+            texts(tokenizer.words("Hello, world! «Ça va?»")),
+            ["Hello", "world", "Ça", "va"]
+        );
+        assert_eq!(
+            texts(Tokenizer::new().words("Hello, world!")),
+            ["Hello,", "world!"]
+        );
+    }
+
+    #[test]
+    fn nth_last_and_reverse() {
+        let tokenizer = Tokenizer::new();
+        let s = "one two  three";
+        assert_eq!(tokenizer.nth_word(s, 1).unwrap().text, "two");
+        assert_eq!(tokenizer.nth_word(s, 3), None);
+        assert_eq!(
+            tokenizer.last_word(s),
+            Some(Word {
+                text: "three",
+                start: 9
+            })
+        );
+        assert_eq!(texts(tokenizer.words(s).rev()), ["three", "two", "one"]);
+        assert_eq!(tokenizer.last_word("   "), None);
+    }
+
+    #[test]
+    fn word_at_offset() {
+        let tokenizer = Tokenizer::new();
+        let s = "hello wörld";
+        assert_eq!(
+            tokenizer.word_at(s, 9),
+            Some(Word {
+                text: "wörld",
+                start: 6
+            })
+        );
+        assert_eq!(tokenizer.word_at(s, 0).unwrap().text, "hello");
+        assert_eq!(tokenizer.word_at(s, 5), None);
+        assert_eq!(tokenizer.word_at(s, 8), None, "inside the ö");
+        assert_eq!(tokenizer.word_at(s, 99), None);
+    }
+
+    // `word_at(s, 0)` is the slice up to the first space, which is what the
+    // original byte loop in `first_word` returned.
+    #[test]
+    fn word_at_start_matches_original_first_word() {
+        let original = |s: &str| s.split(' ').next().unwrap_or("").len();
+        let tokenizer = Tokenizer::ascii_space();
+        for s in ["hello world", " hello", "hello", "", "  ", "héllo\tworld x"] {
+            let word = tokenizer.word_at(s, 0).map_or("", |w| w.text);
+            assert_eq!(word, &s[..original(s)], "{s:?}");
+        }
+    }
+
+    #[test]
+    fn splits_sentences() {
+        let s = "Hi there. \"Is it 3.5?\" she asked!  Wait... 終わり。次";
+        assert_eq!(
+            texts(sentences(s)),
+            [
+                "Hi there.",
+                "\"Is it 3.5?\"",
+                "she asked!",
+                "Wait...",
+                "終わり。",
+                "次"
+            ]
+        );
+        assert_eq!(sentences(s).nth(1).unwrap().start, 10);
+    }
+}
@@ -0,0 +1,128 @@
+use std::collections::VecDeque;
+
+use crate::front_of_house::serving::OrderId;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Season {
+    Spring,
+    Summer,
+    Autumn,
+    Winter,
+}
+
+impl Season {
+    // The first fruit is what the kitchen serves when the guest doesn't pick.
+    pub fn fruits(&self) -> &'static [&'static str] {
+        match self {
+            Season::Spring => &["strawberries", "rhubarb"],
+            Season::Summer => &["peaches", "blueberries", "cherries"],
+            Season::Autumn => &["apples", "pears", "figs"],
+            Season::Winter => &["oranges", "grapefruit"],
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Breakfast {
+    pub toast: String,
+    seasonal_fruit: String,
+}
+
+impl Breakfast {
+    pub fn summer(toast: &str) -> Breakfast {
+        Breakfast::for_season(Season::Summer, toast)
+    }
+
+    pub fn for_season(season: Season, toast: &str) -> Breakfast {
+        Breakfast {
+            toast: String::from(toast),
+            seasonal_fruit: String::from(season.fruits()[0]),
+        }
+    }
+
+    // Guests may choose their fruit, but only from what's in season.
+    pub fn with_fruit(season: Season, toast: &str, fruit: &str) -> Option<Breakfast> {
+        if !season.fruits().contains(&fruit) {
+            return None;
+        }
+        Some(Breakfast {
+            toast: String::from(toast),
+            seasonal_fruit: String::from(fruit),
+        })
+    }
+
+    pub fn seasonal_fruit(&self) -> &str {
+        &self.seasonal_fruit
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Ticket {
+    pub order: OrderId,
+    pub table: u32,
+    pub dishes: Vec<(String, u32)>,
+}
+
+// Tickets are cooked in the order they arrive.
+#[derive(Debug, Default)]
+pub struct Kitchen {
+    tickets: VecDeque<Ticket>,
+}
+
+impl Kitchen {
+    pub fn new() -> Kitchen {
+        Kitchen::default()
+    }
+
+    pub fn submit(&mut self, ticket: Ticket) {
+        self.tickets.push_back(ticket);
+    }
+
+    pub fn pending(&self) -> usize {
+        self.tickets.len()
+    }
+
+    pub fn next_ticket(&self) -> Option<&Ticket> {
+        self.tickets.front()
+    }
+
+    pub fn cook_order(&mut self) -> Option<Ticket> {
+        self.tickets.pop_front()
+    }
+
+    // Sends a ticket back to the front of the queue when a dish comes out wrong.
+    pub fn fix_incorrect_order(&mut self, ticket: Ticket) {
+        self.tickets.push_front(ticket);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn breakfast_fruit_must_be_in_season() {
+        let meal = Breakfast::with_fruit(Season::Autumn, "Rye", "figs").unwrap();
+        assert_eq!(meal.seasonal_fruit(), "figs");
+        assert!(Breakfast::with_fruit(Season::Winter, "Rye", "peaches").is_none());
+        assert_eq!(Breakfast::summer("Wheat").seasonal_fruit(), "peaches");
+    }
+
+    #[test]
+    fn kitchen_cooks_tickets_in_order() {
+        let mut kitchen = Kitchen::new();
+        let ticket = |order| Ticket {
+            order,
+            table: 1,
+            dishes: vec![(String::from("Soup"), 1)],
+        };
+        kitchen.submit(ticket(1));
+        kitchen.submit(ticket(2));
+
+        let first = kitchen.cook_order().unwrap();
+        assert_eq!(first.order, 1);
+        kitchen.fix_incorrect_order(first);
+        assert_eq!(kitchen.next_ticket().unwrap().order, 1);
+        assert_eq!(kitchen.pending(), 2);
+    }
+}
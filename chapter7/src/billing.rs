@@ -0,0 +1,129 @@
+// All amounts are kept in whole cents so totals never pick up floating point
+// rounding errors. Rates are in basis points: 825 means 8.25%.
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Bill {
+    subtotal_cents: u64,
+    tax_rate_bps: u32,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum BillingError {
+    NoPayers,
+    Overflow,
+}
+
+impl std::fmt::Display for BillingError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            BillingError::NoPayers => write!(f, "a bill can't be split between zero people"),
+            BillingError::Overflow => write!(f, "the amount is too large to bill"),
+        }
+    }
+}
+
+impl std::error::Error for BillingError {}
+
+impl Bill {
+    pub fn new(subtotal_cents: u64, tax_rate_bps: u32) -> Bill {
+        Bill {
+            subtotal_cents,
+            tax_rate_bps,
+        }
+    }
+
+    pub fn subtotal_cents(&self) -> u64 {
+        self.subtotal_cents
+    }
+
+    pub fn tax_cents(&self) -> Result<u64, BillingError> {
+        percent_of(self.subtotal_cents, self.tax_rate_bps)
+    }
+
+    pub fn total_cents(&self) -> Result<u64, BillingError> {
+        add(self.subtotal_cents, self.tax_cents()?)
+    }
+
+    // Tips are calculated on the pre-tax subtotal.
+    pub fn tip_cents(&self, tip_rate_bps: u32) -> Result<u64, BillingError> {
+        percent_of(self.subtotal_cents, tip_rate_bps)
+    }
+
+    pub fn total_with_tip_cents(&self, tip_rate_bps: u32) -> Result<u64, BillingError> {
+        add(self.total_cents()?, self.tip_cents(tip_rate_bps)?)
+    }
+
+    // Splits the total plus tip evenly. Leftover cents go to the first payers,
+    // so the shares always add back up to the full amount.
+    pub fn split(&self, payers: u32, tip_rate_bps: u32) -> Result<Vec<u64>, BillingError> {
+        if payers == 0 {
+            return Err(BillingError::NoPayers);
+        }
+        let total = self.total_with_tip_cents(tip_rate_bps)?;
+        let payers = u64::from(payers);
+        let share = total / payers;
+        let remainder = total % payers;
+
+        Ok((0..payers)
+            .map(|i| if i < remainder { share + 1 } else { share })
+            .collect())
+    }
+}
+
+pub fn format_cents(cents: u64) -> String {
+    format!("${}.{:02}", cents / 100, cents % 100)
+}
+
+// Rounds half up to the nearest cent.
+fn percent_of(cents: u64, rate_bps: u32) -> Result<u64, BillingError> {
+    cents
+        .checked_mul(u64::from(rate_bps))
+        .and_then(|x| x.checked_add(5_000))
+        .map(|x| x / 10_000)
+        .ok_or(BillingError::Overflow)
+}
+
+fn add(a: u64, b: u64) -> Result<u64, BillingError> {
+    a.checked_add(b).ok_or(BillingError::Overflow)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tax_and_tip_round_to_nearest_cent() {
+        let bill = Bill::new(1999, 825);
+        assert_eq!(bill.tax_cents(), Ok(165));
+        assert_eq!(bill.total_cents(), Ok(2164));
+        assert_eq!(bill.tip_cents(1800), Ok(360));
+        assert_eq!(bill.total_with_tip_cents(1800), Ok(2524));
+    }
+
+    #[test]
+    fn huge_amounts_are_errors() {
+        let bill = Bill::new(u64::MAX / 100, 825);
+        assert_eq!(bill.tax_cents(), Err(BillingError::Overflow));
+        assert_eq!(bill.split(2, 0), Err(BillingError::Overflow));
+        assert_eq!(Bill::new(u64::MAX, 0).total_cents(), Ok(u64::MAX));
+        assert_eq!(
+            Bill::new(u64::MAX, 0).total_with_tip_cents(1),
+            Err(BillingError::Overflow)
+        );
+    }
+
+    #[test]
+    fn split_shares_add_up_to_total() {
+        let bill = Bill::new(1000, 0);
+        let shares = bill.split(3, 0).unwrap();
+        assert_eq!(shares, vec![334, 333, 333]);
+        assert_eq!(Ok(shares.iter().sum::<u64>()), bill.total_cents());
+        assert_eq!(bill.split(0, 0), Err(BillingError::NoPayers));
+    }
+
+    #[test]
+    fn formats_dollars_and_cents() {
+        assert_eq!(format_cents(2524), "$25.24");
+        assert_eq!(format_cents(7), "$0.07");
+    }
+}
@@ -0,0 +1,20 @@
+use std::env;
+use std::error::Error;
+use std::fs;
+
+use chapter7::seating;
+
+// Replays a day of arrivals and prints where each party was seated along with
+// the day's utilization statistics.
+//
+//     cargo run --bin seating_sim -- data/friday.txt
+fn main() -> Result<(), Box<dyn Error>> {
+    let path = env::args().nth(1).ok_or("usage: seating_sim <day file>")?;
+    let input = fs::read_to_string(&path)?;
+    let (plan, arrivals) = seating::parse_day(&input)?;
+
+    let report = seating::simulate(plan, &arrivals)?;
+    println!("{report}");
+
+    Ok(())
+}
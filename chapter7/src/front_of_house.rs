@@ -1 +1,3 @@
 pub mod hosting;
+pub mod seating;
+pub mod serving;
@@ -1 +1,278 @@
-pub fn add_to_waitlist() {}
+use std::collections::VecDeque;
+
+pub type PartyId = u32;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Party {
+    pub id: PartyId,
+    pub name: String,
+    pub size: u32,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Table {
+    pub number: u32,
+    pub seats: u32,
+    occupied_by: Option<PartyId>,
+}
+
+impl Table {
+    pub fn new(number: u32, seats: u32) -> Table {
+        Table {
+            number,
+            seats,
+            occupied_by: None,
+        }
+    }
+
+    pub fn is_free(&self) -> bool {
+        self.occupied_by.is_none()
+    }
+
+    pub fn occupied_by(&self) -> Option<PartyId> {
+        self.occupied_by
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Seating {
+    pub party: Party,
+    pub table: u32,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum HostingError {
+    EmptyParty,
+    NoSuchParty(PartyId),
+    NoSuchTable(u32),
+    TableTaken(u32),
+    TableAlreadyFree(u32),
+    NoTablesGiven,
+    NotEnoughSeats { size: u32, seats: u32 },
+}
+
+impl std::fmt::Display for HostingError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            HostingError::EmptyParty => write!(f, "a party needs at least one guest"),
+            HostingError::NoSuchParty(id) => write!(f, "party {id} is not waiting"),
+            HostingError::NoSuchTable(n) => write!(f, "there is no table {n}"),
+            HostingError::TableTaken(n) => write!(f, "table {n} is already occupied"),
+            HostingError::TableAlreadyFree(n) => write!(f, "table {n} is not occupied"),
+            HostingError::NoTablesGiven => write!(f, "a party must be seated at some table"),
+            HostingError::NotEnoughSeats { size, seats } => {
+                write!(f, "{seats} seats are too few for a party of {size}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for HostingError {}
+
+// The host keeps the dining room's tables and the queue of parties waiting
+// for one of them.
+#[derive(Debug, Clone)]
+pub struct Host {
+    tables: Vec<Table>,
+    waitlist: VecDeque<Party>,
+    next_party_id: PartyId,
+}
+
+impl Host {
+    pub fn new(tables: Vec<Table>) -> Host {
+        Host {
+            tables,
+            waitlist: VecDeque::new(),
+            next_party_id: 1,
+        }
+    }
+
+    pub fn add_to_waitlist(&mut self, name: &str, size: u32) -> Result<PartyId, HostingError> {
+        if size == 0 {
+            return Err(HostingError::EmptyParty);
+        }
+        let id = self.next_party_id;
+        self.next_party_id += 1;
+        self.waitlist.push_back(Party {
+            id,
+            name: String::from(name),
+            size,
+        });
+        Ok(id)
+    }
+
+    pub fn waitlist(&self) -> impl Iterator<Item = &Party> {
+        self.waitlist.iter()
+    }
+
+    pub fn tables(&self) -> &[Table] {
+        &self.tables
+    }
+
+    // Seats the first party in line that fits at a free table, giving it the
+    // smallest such table so larger tables stay open for larger parties.
+    pub fn seat_at_table(&mut self) -> Option<Seating> {
+        let (id, table) = self.waitlist.iter().find_map(|party| {
+            self.tables
+                .iter()
+                .filter(|t| t.is_free() && t.seats >= party.size)
+                .min_by_key(|t| t.seats)
+                .map(|t| (party.id, t.number))
+        })?;
+        let party = self.seat_party(id, &[table]).ok()?;
+        Some(Seating { party, table })
+    }
+
+    // Takes a particular party off the waitlist and seats it at the given
+    // tables, which may be several pushed together. Nothing changes unless
+    // every table exists and is free and together they have enough seats.
+    pub fn seat_party(&mut self, id: PartyId, tables: &[u32]) -> Result<Party, HostingError> {
+        let position = self
+            .waitlist
+            .iter()
+            .position(|p| p.id == id)
+            .ok_or(HostingError::NoSuchParty(id))?;
+        if tables.is_empty() {
+            return Err(HostingError::NoTablesGiven);
+        }
+        for &number in tables {
+            let table = self
+                .tables
+                .iter()
+                .find(|t| t.number == number)
+                .ok_or(HostingError::NoSuchTable(number))?;
+            if !table.is_free() {
+                return Err(HostingError::TableTaken(number));
+            }
+        }
+        // Counted over the host's tables, so a table listed twice only
+        // counts once.
+        let seats = self
+            .tables
+            .iter()
+            .filter(|t| tables.contains(&t.number))
+            .fold(0u32, |sum, t| sum.saturating_add(t.seats));
+        let size = self.waitlist[position].size;
+        if seats < size {
+            return Err(HostingError::NotEnoughSeats { size, seats });
+        }
+
+        let party = self
+            .waitlist
+            .remove(position)
+            .expect("position is in range");
+        for table in self
+            .tables
+            .iter_mut()
+            .filter(|t| tables.contains(&t.number))
+        {
+            table.occupied_by = Some(party.id);
+        }
+        Ok(party)
+    }
+
+    pub fn clear_table(&mut self, number: u32) -> Result<PartyId, HostingError> {
+        let table = self
+            .tables
+            .iter_mut()
+            .find(|t| t.number == number)
+            .ok_or(HostingError::NoSuchTable(number))?;
+        table
+            .occupied_by
+            .take()
+            .ok_or(HostingError::TableAlreadyFree(number))
+    }
+}
+
+// Goes through `new` so a default host hands out the same party ids.
+impl Default for Host {
+    fn default() -> Host {
+        Host::new(Vec::new())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn seats_party_at_smallest_fitting_table() {
+        let mut host = Host::new(vec![Table::new(1, 6), Table::new(2, 2), Table::new(3, 4)]);
+        host.add_to_waitlist("Ferris", 3).unwrap();
+
+        let seating = host.seat_at_table().unwrap();
+        assert_eq!(seating.table, 3);
+        assert_eq!(seating.party.name, "Ferris");
+        assert_eq!(host.waitlist().count(), 0);
+    }
+
+    #[test]
+    fn smaller_party_can_skip_ahead_when_head_does_not_fit() {
+        let mut host = Host::new(vec![Table::new(1, 2)]);
+        host.add_to_waitlist("Large", 5).unwrap();
+        host.add_to_waitlist("Small", 2).unwrap();
+
+        let seating = host.seat_at_table().unwrap();
+        assert_eq!(seating.party.name, "Small");
+        assert!(host.seat_at_table().is_none());
+    }
+
+    #[test]
+    fn clearing_a_table_frees_it() {
+        let mut host = Host::new(vec![Table::new(1, 2)]);
+        let id = host.add_to_waitlist("Ferris", 2).unwrap();
+        host.seat_at_table().unwrap();
+
+        assert_eq!(host.clear_table(1), Ok(id));
+        assert_eq!(host.clear_table(1), Err(HostingError::TableAlreadyFree(1)));
+        assert_eq!(host.clear_table(9), Err(HostingError::NoSuchTable(9)));
+    }
+
+    #[test]
+    fn rejects_empty_party() {
+        let mut host = Host::new(vec![]);
+        assert_eq!(
+            host.add_to_waitlist("Nobody", 0),
+            Err(HostingError::EmptyParty)
+        );
+    }
+
+    #[test]
+    fn default_host_numbers_parties_like_new() {
+        let mut host = Host::default();
+        assert_eq!(host.add_to_waitlist("Ferris", 2), Ok(1));
+        assert_eq!(Host::new(vec![]).add_to_waitlist("Ferris", 2), Ok(1));
+    }
+
+    #[test]
+    fn seats_a_chosen_party_at_joined_tables() {
+        let mut host = Host::new(vec![Table::new(1, 2), Table::new(2, 2)]);
+        let first = host.add_to_waitlist("First", 2).unwrap();
+        let second = host.add_to_waitlist("Second", 4).unwrap();
+
+        assert_eq!(
+            host.seat_party(second, &[1, 3]),
+            Err(HostingError::NoSuchTable(3))
+        );
+        assert_eq!(
+            host.seat_party(second, &[]),
+            Err(HostingError::NoTablesGiven)
+        );
+        assert_eq!(
+            host.seat_party(second, &[1, 1]),
+            Err(HostingError::NotEnoughSeats { size: 4, seats: 2 })
+        );
+        assert_eq!(host.waitlist().count(), 2);
+        assert_eq!(host.seat_party(second, &[1, 2]).unwrap().name, "Second");
+        assert_eq!(host.tables()[1].occupied_by(), Some(second));
+        assert_eq!(
+            host.seat_party(first, &[2]),
+            Err(HostingError::TableTaken(2))
+        );
+        assert_eq!(
+            host.seat_party(second, &[1]),
+            Err(HostingError::NoSuchParty(second))
+        );
+        assert_eq!(host.waitlist().count(), 1);
+    }
+}
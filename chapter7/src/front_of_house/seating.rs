@@ -0,0 +1,754 @@
+use std::collections::HashMap;
+use std::fmt;
+
+use super::hosting::{Host, HostingError, PartyId, Table};
+
+// Times are minutes after midnight so a whole day fits in a u32 and the
+// simulation never depends on the wall clock.
+pub type Minute = u32;
+
+// A floor plan is the host's tables plus the pairs of tables that sit next
+// to each other and can be pushed together for a larger party.
+#[derive(Debug, Clone, Default)]
+pub struct FloorPlan {
+    tables: Vec<Table>,
+    adjacent: Vec<(u32, u32)>,
+}
+
+impl FloorPlan {
+    pub fn new() -> FloorPlan {
+        FloorPlan::default()
+    }
+
+    pub fn add_table(&mut self, number: u32, seats: u32) -> Result<(), SeatingError> {
+        if self.table(number).is_some() {
+            return Err(SeatingError::DuplicateTable(number));
+        }
+        self.tables.push(Table::new(number, seats));
+        Ok(())
+    }
+
+    pub fn join(&mut self, a: u32, b: u32) -> Result<(), SeatingError> {
+        for number in [a, b] {
+            if self.table(number).is_none() {
+                return Err(SeatingError::UnknownTable(number));
+            }
+        }
+        if a != b && !self.adjacent.contains(&(a, b)) && !self.adjacent.contains(&(b, a)) {
+            self.adjacent.push((a, b));
+        }
+        Ok(())
+    }
+
+    pub fn tables(&self) -> &[Table] {
+        &self.tables
+    }
+
+    pub fn total_seats(&self) -> u32 {
+        self.tables.iter().map(|t| t.seats).sum()
+    }
+
+    fn table(&self, number: u32) -> Option<&Table> {
+        self.tables.iter().find(|t| t.number == number)
+    }
+
+    // Every way a party could be seated: each table on its own, and each
+    // adjacent pair pushed together.
+    fn options(&self) -> Vec<(Vec<u32>, u32)> {
+        let singles = self.tables.iter().map(|t| (vec![t.number], t.seats));
+        let pairs = self.adjacent.iter().map(|&(a, b)| {
+            let seats = self.table(a).map_or(0, |t| t.seats) + self.table(b).map_or(0, |t| t.seats);
+            (vec![a, b], seats)
+        });
+        singles.chain(pairs).collect()
+    }
+
+    fn largest_option(&self) -> u32 {
+        self.options()
+            .iter()
+            .map(|(_, seats)| *seats)
+            .max()
+            .unwrap_or(0)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Arrival {
+    pub name: String,
+    pub size: u32,
+    pub arrives_at: Minute,
+    pub dining_minutes: u32,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Assignment {
+    pub party: PartyId,
+    pub name: String,
+    pub size: u32,
+    pub tables: Vec<u32>,
+    pub arrived_at: Minute,
+    pub seated_at: Minute,
+    pub leaves_at: Minute,
+    pub wasted_seats: u32,
+}
+
+impl Assignment {
+    pub fn wait(&self) -> u32 {
+        self.seated_at - self.arrived_at
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum SeatingError {
+    DuplicateTable(u32),
+    UnknownTable(u32),
+    Hosting(HostingError),
+    PartyTooLarge { name: String, size: u32 },
+    // The party would still be dining when the `Minute` clock runs out.
+    ClockOverflow { name: String },
+    Parse { line: usize, message: String },
+}
+
+impl fmt::Display for SeatingError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SeatingError::DuplicateTable(n) => write!(f, "table {n} is listed twice"),
+            SeatingError::UnknownTable(n) => write!(f, "there is no table {n}"),
+            SeatingError::Hosting(e) => write!(f, "{e}"),
+            SeatingError::PartyTooLarge { name, size } => {
+                write!(
+                    f,
+                    "no table or pair of tables can seat {name}'s party of {size}"
+                )
+            }
+            SeatingError::ClockOverflow { name } => {
+                write!(f, "{name}'s party would dine past the end of the clock")
+            }
+            SeatingError::Parse { line, message } => write!(f, "line {line}: {message}"),
+        }
+    }
+}
+
+impl std::error::Error for SeatingError {}
+
+impl From<HostingError> for SeatingError {
+    fn from(e: HostingError) -> SeatingError {
+        SeatingError::Hosting(e)
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Occupied {
+    tables: Vec<u32>,
+    free_at: Minute,
+}
+
+// Seats the host's waitlist as tables free up. The `Host` owns the tables and
+// the queue; the engine adds the clock, table combinations and the choice of
+// who sits where. Parties are considered in arrival order, and each gets the
+// free option that wastes the fewest seats, so a smaller party may be seated
+// ahead of a larger one that doesn't fit yet.
+// Once the party at the front of the line has waited `hold_after` minutes,
+// the tables that free up soonest for it are held so large parties aren't
+// starved by a stream of small ones.
+#[derive(Debug, Clone)]
+pub struct SeatingEngine {
+    host: Host,
+    options: Vec<(Vec<u32>, u32)>,
+    largest_option: u32,
+    now: Minute,
+    hold_after: u32,
+    // Arrival details of every party still on the host's waitlist.
+    arrivals: HashMap<PartyId, Arrival>,
+    occupied: Vec<Occupied>,
+    assignments: Vec<Assignment>,
+}
+
+impl SeatingEngine {
+    pub fn new(plan: FloorPlan) -> SeatingEngine {
+        SeatingEngine {
+            options: plan.options(),
+            largest_option: plan.largest_option(),
+            host: Host::new(plan.tables),
+            now: 0,
+            hold_after: 30,
+            arrivals: HashMap::new(),
+            occupied: Vec::new(),
+            assignments: Vec::new(),
+        }
+    }
+
+    pub fn with_hold_after(mut self, minutes: u32) -> SeatingEngine {
+        self.hold_after = minutes;
+        self
+    }
+
+    pub fn now(&self) -> Minute {
+        self.now
+    }
+
+    pub fn assignments(&self) -> &[Assignment] {
+        &self.assignments
+    }
+
+    pub fn host(&self) -> &Host {
+        &self.host
+    }
+
+    pub fn waiting(&self) -> impl Iterator<Item = &Arrival> {
+        self.host.waitlist().map(|party| &self.arrivals[&party.id])
+    }
+
+    // Moves the clock forward, releasing tables and seating parties at every
+    // departure along the way.
+    pub fn advance_to(&mut self, time: Minute) -> Result<(), SeatingError> {
+        while let Some(next) = self
+            .occupied
+            .iter()
+            .map(|o| o.free_at)
+            .filter(|&t| t <= time)
+            .min()
+        {
+            self.now = self.now.max(next);
+            let (leaving, staying) = std::mem::take(&mut self.occupied)
+                .into_iter()
+                .partition(|o| o.free_at <= next);
+            self.occupied = staying;
+            for table in leaving.into_iter().flat_map(|o: Occupied| o.tables) {
+                self.host
+                    .clear_table(table)
+                    .expect("seated tables are occupied");
+            }
+            self.seat_waiting()?;
+        }
+        self.now = self.now.max(time);
+        self.seat_waiting()
+    }
+
+    // The party's own checks come before the clock moves, so a party rejected
+    // for its size or stay leaves the engine untouched.
+    pub fn add_to_waitlist(&mut self, arrival: Arrival) -> Result<PartyId, SeatingError> {
+        if arrival.size == 0 {
+            return Err(HostingError::EmptyParty.into());
+        }
+        if arrival.size > self.largest_option {
+            return Err(SeatingError::PartyTooLarge {
+                name: arrival.name,
+                size: arrival.size,
+            });
+        }
+        if arrival
+            .arrives_at
+            .checked_add(arrival.dining_minutes)
+            .is_none()
+        {
+            return Err(SeatingError::ClockOverflow { name: arrival.name });
+        }
+        self.advance_to(arrival.arrives_at)?;
+        let id = self.host.add_to_waitlist(&arrival.name, arrival.size)?;
+        self.arrivals.insert(id, arrival);
+        self.seat_waiting()?;
+        Ok(id)
+    }
+
+    // Estimates when each waiting party will be seated by replaying the
+    // current state forward with no further arrivals.
+    pub fn estimated_waits(&self) -> Result<Vec<(String, u32)>, SeatingError> {
+        let mut preview = self.clone();
+        preview.finish()?;
+
+        Ok(self
+            .host
+            .waitlist()
+            .map(|party| {
+                let seated_at = preview
+                    .assignments
+                    .iter()
+                    .find(|a| a.party == party.id)
+                    .map_or(self.now, |a| a.seated_at);
+                (party.name.clone(), seated_at - self.now)
+            })
+            .collect())
+    }
+
+    // Runs the clock until every waiting party has been seated and every table
+    // has been cleared.
+    pub fn finish(&mut self) -> Result<(), SeatingError> {
+        while let Some(next) = self.occupied.iter().map(|o| o.free_at).min() {
+            self.advance_to(next)?;
+        }
+        Ok(())
+    }
+
+    fn seat_waiting(&mut self) -> Result<(), SeatingError> {
+        let mut reserved: Vec<u32> = Vec::new();
+        let mut position = 0;
+        loop {
+            let next = self.host.waitlist().nth(position).map(|p| (p.id, p.size));
+            let Some((id, size)) = next else { break };
+            if position == 1 {
+                let head = self
+                    .host
+                    .waitlist()
+                    .next()
+                    .expect("the waitlist has two parties");
+                let head = &self.arrivals[&head.id];
+                if self.now - head.arrives_at >= self.hold_after {
+                    reserved = self.soonest_option(head.size);
+                }
+            }
+            match self.best_option(size, &reserved) {
+                Some(option) => self.seat(id, option)?,
+                None => position += 1,
+            }
+        }
+        Ok(())
+    }
+
+    fn best_option(&self, size: u32, reserved: &[u32]) -> Option<usize> {
+        self.options
+            .iter()
+            .enumerate()
+            .filter(|(_, (tables, seats))| {
+                *seats >= size
+                    && tables
+                        .iter()
+                        .all(|t| self.is_free(*t) && !reserved.contains(t))
+            })
+            .min_by_key(|(_, (tables, seats))| (seats - size, tables.len(), tables.clone()))
+            .map(|(index, _)| index)
+    }
+
+    // The tables that will become free soonest for a party of this size.
+    fn soonest_option(&self, size: u32) -> Vec<u32> {
+        self.options
+            .iter()
+            .filter(|(_, seats)| *seats >= size)
+            .min_by_key(|(tables, seats)| {
+                let free_at = tables.iter().map(|t| self.free_at(*t)).max();
+                (free_at, seats - size, tables.len())
+            })
+            .map_or_else(Vec::new, |(tables, _)| tables.clone())
+    }
+
+    fn free_at(&self, table: u32) -> Minute {
+        self.occupied
+            .iter()
+            .filter(|o| o.tables.contains(&table))
+            .map(|o| o.free_at)
+            .max()
+            .unwrap_or(self.now)
+    }
+
+    fn is_free(&self, table: u32) -> bool {
+        self.host
+            .tables()
+            .iter()
+            .any(|t| t.number == table && t.is_free())
+    }
+
+    // Nothing changes if the party would leave after the clock runs out.
+    fn seat(&mut self, id: PartyId, option: usize) -> Result<(), SeatingError> {
+        let arrival = &self.arrivals[&id];
+        let leaves_at = self
+            .now
+            .checked_add(arrival.dining_minutes)
+            .ok_or_else(|| SeatingError::ClockOverflow {
+                name: arrival.name.clone(),
+            })?;
+        let (tables, seats) = self.options[option].clone();
+        self.host
+            .seat_party(id, &tables)
+            .expect("the option's tables are free");
+        let party = self
+            .arrivals
+            .remove(&id)
+            .expect("waiting parties have arrivals");
+        self.occupied.push(Occupied {
+            tables: tables.clone(),
+            free_at: leaves_at,
+        });
+        self.assignments.push(Assignment {
+            party: id,
+            wasted_seats: seats - party.size,
+            name: party.name,
+            size: party.size,
+            tables,
+            arrived_at: party.arrives_at,
+            seated_at: self.now,
+            leaves_at,
+        });
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SimulationReport {
+    pub assignments: Vec<Assignment>,
+    pub rejected: Vec<Arrival>,
+    pub opened_at: Minute,
+    pub closed_at: Minute,
+    pub total_seats: u32,
+}
+
+impl SimulationReport {
+    // Guest-minutes spent seated divided by seat-minutes available while the
+    // restaurant was open.
+    pub fn seat_utilization(&self) -> f64 {
+        let available = u64::from(self.total_seats) * u64::from(self.closed_at - self.opened_at);
+        if available == 0 {
+            return 0.0;
+        }
+        let used: u64 = self
+            .assignments
+            .iter()
+            .map(|a| u64::from(a.size) * u64::from(a.leaves_at - a.seated_at))
+            .sum();
+        used as f64 / available as f64
+    }
+
+    pub fn average_wait(&self) -> f64 {
+        if self.assignments.is_empty() {
+            return 0.0;
+        }
+        let total: u32 = self.assignments.iter().map(Assignment::wait).sum();
+        f64::from(total) / self.assignments.len() as f64
+    }
+
+    pub fn longest_wait(&self) -> u32 {
+        self.assignments
+            .iter()
+            .map(Assignment::wait)
+            .max()
+            .unwrap_or(0)
+    }
+
+    pub fn wasted_seats(&self) -> u32 {
+        self.assignments.iter().map(|a| a.wasted_seats).sum()
+    }
+}
+
+impl fmt::Display for SimulationReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for a in &self.assignments {
+            let tables: Vec<String> = a.tables.iter().map(|t| t.to_string()).collect();
+            writeln!(
+                f,
+                "{} {:<12} party of {:>2} -> table {:<5} waited {:>3} min",
+                format_minute(a.seated_at),
+                a.name,
+                a.size,
+                tables.join("+"),
+                a.wait()
+            )?;
+        }
+        for r in &self.rejected {
+            writeln!(
+                f,
+                "{} {:<12} party of {:>2} -> turned away",
+                format_minute(r.arrives_at),
+                r.name,
+                r.size
+            )?;
+        }
+        writeln!(
+            f,
+            "open {} - {}",
+            format_minute(self.opened_at),
+            format_minute(self.closed_at)
+        )?;
+        writeln!(f, "parties seated: {}", self.assignments.len())?;
+        writeln!(
+            f,
+            "seat utilization: {:.1}%",
+            self.seat_utilization() * 100.0
+        )?;
+        writeln!(f, "average wait: {:.1} min", self.average_wait())?;
+        writeln!(f, "longest wait: {} min", self.longest_wait())?;
+        write!(f, "wasted seats: {}", self.wasted_seats())
+    }
+}
+
+// Replays a day of arrivals. Arrivals are handled in time order; parties that
+// arrive at the same minute keep the order they were given in. Parties the
+// engine can't take are turned away, but a clock overflow ends the day.
+pub fn simulate(plan: FloorPlan, arrivals: &[Arrival]) -> Result<SimulationReport, SeatingError> {
+    let mut arrivals = arrivals.to_vec();
+    arrivals.sort_by_key(|a| a.arrives_at);
+
+    let total_seats = plan.total_seats();
+    let opened_at = arrivals.first().map_or(0, |a| a.arrives_at);
+    let mut engine = SeatingEngine::new(plan);
+    engine.advance_to(opened_at)?;
+
+    let mut rejected = Vec::new();
+    for arrival in arrivals {
+        match engine.add_to_waitlist(arrival.clone()) {
+            Ok(_) => {}
+            Err(e @ SeatingError::ClockOverflow { .. }) => return Err(e),
+            Err(_) => rejected.push(arrival),
+        }
+    }
+    engine.finish()?;
+
+    Ok(SimulationReport {
+        closed_at: engine.now().max(opened_at),
+        assignments: engine.assignments,
+        rejected,
+        opened_at,
+        total_seats,
+    })
+}
+
+// Reads a day description made of lines like:
+//
+//     table 1 4
+//     join 1 2
+//     arrive 18:05 Smith 4 90
+//
+// Blank lines and lines starting with `#` are ignored.
+pub fn parse_day(input: &str) -> Result<(FloorPlan, Vec<Arrival>), SeatingError> {
+    let mut plan = FloorPlan::new();
+    let mut arrivals = Vec::new();
+
+    for (index, line) in input.lines().enumerate() {
+        let line_no = index + 1;
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        let parse_error = |message: &str| SeatingError::Parse {
+            line: line_no,
+            message: String::from(message),
+        };
+        let number = |field: &str| {
+            field
+                .parse::<u32>()
+                .map_err(|_| parse_error(&format!("`{field}` is not a number")))
+        };
+
+        match fields.as_slice() {
+            ["table", n, seats] => plan.add_table(number(n)?, number(seats)?),
+            ["join", a, b] => plan.join(number(a)?, number(b)?),
+            ["arrive", time, name, size, dining] => {
+                let size = number(size)?;
+                if size == 0 {
+                    return Err(parse_error(&HostingError::EmptyParty.to_string()));
+                }
+                let arrives_at = parse_minute(time)
+                    .ok_or_else(|| parse_error(&format!("`{time}` is not a HH:MM time")))?;
+                let dining_minutes = number(dining)?;
+                let name = String::from(*name);
+                if arrives_at.checked_add(dining_minutes).is_none() {
+                    return Err(parse_error(
+                        &SeatingError::ClockOverflow { name }.to_string(),
+                    ));
+                }
+                arrivals.push(Arrival {
+                    arrives_at,
+                    name,
+                    size,
+                    dining_minutes,
+                });
+                Ok(())
+            }
+            _ => Err(parse_error(&format!("don't know what to do with `{line}`"))),
+        }
+        .map_err(|e| match e {
+            SeatingError::Parse { .. } => e,
+            other => SeatingError::Parse {
+                line: line_no,
+                message: other.to_string(),
+            },
+        })?;
+    }
+    Ok((plan, arrivals))
+}
+
+pub fn parse_minute(time: &str) -> Option<Minute> {
+    let (hours, minutes) = time.split_once(':')?;
+    let hours: u32 = hours.parse().ok()?;
+    let minutes: u32 = minutes.parse().ok()?;
+    if hours > 23 || minutes > 59 {
+        return None;
+    }
+    Some(hours * 60 + minutes)
+}
+
+pub fn format_minute(minute: Minute) -> String {
+    format!("{:02}:{:02}", minute / 60, minute % 60)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn arrival(name: &str, size: u32, arrives_at: Minute, dining_minutes: u32) -> Arrival {
+        Arrival {
+            name: String::from(name),
+            size,
+            arrives_at,
+            dining_minutes,
+        }
+    }
+
+    fn plan() -> FloorPlan {
+        let mut plan = FloorPlan::new();
+        plan.add_table(1, 2).unwrap();
+        plan.add_table(2, 4).unwrap();
+        plan.add_table(3, 4).unwrap();
+        plan.join(2, 3).unwrap();
+        plan
+    }
+
+    #[test]
+    fn picks_option_with_fewest_wasted_seats() {
+        let mut engine = SeatingEngine::new(plan());
+        engine.add_to_waitlist(arrival("Pair", 2, 0, 60)).unwrap();
+        engine.add_to_waitlist(arrival("Three", 3, 0, 60)).unwrap();
+
+        let seated = engine.assignments();
+        assert_eq!(seated[0].tables, vec![1]);
+        assert_eq!(seated[1].tables, vec![2]);
+        assert_eq!(seated[1].wasted_seats, 1);
+    }
+
+    #[test]
+    fn combines_adjacent_tables_for_large_party() {
+        let mut engine = SeatingEngine::new(plan());
+        engine.add_to_waitlist(arrival("Big", 7, 0, 60)).unwrap();
+        assert_eq!(engine.assignments()[0].tables, vec![2, 3]);
+
+        let err = engine.add_to_waitlist(arrival("Huge", 9, 0, 60));
+        assert!(matches!(err, Err(SeatingError::PartyTooLarge { .. })));
+    }
+
+    #[test]
+    fn estimates_wait_from_current_tables() {
+        let mut engine = SeatingEngine::new(plan());
+        engine.add_to_waitlist(arrival("Big", 8, 0, 90)).unwrap();
+        engine.add_to_waitlist(arrival("Late", 4, 30, 60)).unwrap();
+        engine.add_to_waitlist(arrival("Later", 4, 30, 60)).unwrap();
+
+        assert_eq!(
+            engine.estimated_waits().unwrap(),
+            vec![(String::from("Late"), 60), (String::from("Later"), 60)]
+        );
+    }
+
+    #[test]
+    fn long_wait_holds_tables_for_front_of_line() {
+        let mut engine = SeatingEngine::new(plan()).with_hold_after(30);
+        engine.add_to_waitlist(arrival("First", 4, 0, 60)).unwrap();
+        engine.add_to_waitlist(arrival("Second", 4, 0, 90)).unwrap();
+        engine.add_to_waitlist(arrival("Big", 8, 10, 60)).unwrap();
+        engine.add_to_waitlist(arrival("Small", 3, 50, 60)).unwrap();
+        engine.finish().unwrap();
+
+        // Without the hold, Small would take table 2 at minute 60 and Big
+        // would wait until minute 120.
+        let big = &engine.assignments()[2];
+        assert_eq!((big.name.as_str(), big.seated_at), ("Big", 90));
+        let small = &engine.assignments()[3];
+        assert_eq!((small.name.as_str(), small.seated_at), ("Small", 150));
+    }
+
+    #[test]
+    fn simulation_is_deterministic() {
+        let day = "\
+            table 1 2\n\
+            table 2 4\n\
+            # the four-tops can be pushed together\n\
+            table 3 4\n\
+            join 2 3\n\
+            arrive 18:00 Ana 2 60\n\
+            arrive 18:00 Ben 4 60\n\
+            arrive 18:10 Cleo 6 90\n\
+            arrive 18:20 Dev 2 30\n\
+            arrive 18:30 Eli 12 30\n";
+        let (plan, arrivals) = parse_day(day).unwrap();
+        let report = simulate(plan.clone(), &arrivals).unwrap();
+
+        assert_eq!(Ok(report.clone()), simulate(plan, &arrivals));
+        assert_eq!(report.assignments.len(), 4);
+        assert_eq!(report.rejected.len(), 1);
+        assert_eq!(report.longest_wait(), 50);
+        assert_eq!(report.closed_at, parse_minute("20:30").unwrap());
+    }
+
+    #[test]
+    fn parse_errors_report_line_numbers() {
+        let err = parse_day("table 1 4\njoin 1 5\n").unwrap_err();
+        assert_eq!(
+            err,
+            SeatingError::Parse {
+                line: 2,
+                message: String::from("there is no table 5")
+            }
+        );
+        assert!(parse_day("arrive 25:00 Ana 2 60").is_err());
+    }
+
+    #[test]
+    fn seats_through_the_host() {
+        let mut engine = SeatingEngine::new(plan());
+        let big = engine.add_to_waitlist(arrival("Big", 7, 0, 60)).unwrap();
+        let pair = engine.add_to_waitlist(arrival("Pair", 2, 0, 60)).unwrap();
+
+        let occupied: Vec<_> = engine
+            .host()
+            .tables()
+            .iter()
+            .map(|t| t.occupied_by())
+            .collect();
+        assert_eq!(occupied, vec![Some(pair), Some(big), Some(big)]);
+        assert_eq!(engine.assignments()[0].party, big);
+
+        engine.advance_to(60).unwrap();
+        assert!(engine.host().tables().iter().all(|t| t.is_free()));
+    }
+
+    #[test]
+    fn rejects_empty_parties() {
+        let mut engine = SeatingEngine::new(plan());
+        assert_eq!(
+            engine.add_to_waitlist(arrival("Nobody", 0, 10, 60)),
+            Err(SeatingError::Hosting(HostingError::EmptyParty))
+        );
+        assert_eq!(engine.now(), 0);
+        assert_eq!(
+            parse_day("table 1 2\narrive 18:00 Nobody 00 60").unwrap_err(),
+            SeatingError::Parse {
+                line: 2,
+                message: String::from("a party needs at least one guest")
+            }
+        );
+    }
+
+    #[test]
+    fn reports_clock_overflow() {
+        assert_eq!(
+            parse_day("table 1 2\narrive 18:00 Ana 2 4294967295").unwrap_err(),
+            SeatingError::Parse {
+                line: 2,
+                message: String::from("Ana's party would dine past the end of the clock")
+            }
+        );
+
+        let mut engine = SeatingEngine::new(plan());
+        engine
+            .add_to_waitlist(arrival("Forever", 8, 0, u32::MAX))
+            .unwrap();
+        engine.add_to_waitlist(arrival("Next", 7, 1, 1)).unwrap();
+        let overflow = || SeatingError::ClockOverflow {
+            name: String::from("Next"),
+        };
+        assert_eq!(engine.estimated_waits(), Err(overflow()));
+        assert_eq!(engine.finish(), Err(overflow()));
+        assert_eq!(engine.waiting().count(), 1);
+
+        let arrivals = [arrival("Forever", 8, 0, u32::MAX), arrival("Next", 7, 1, 1)];
+        assert_eq!(simulate(plan(), &arrivals).unwrap_err(), overflow());
+    }
+}
@@ -0,0 +1,359 @@
+use std::collections::HashMap;
+
+use crate::back_of_house::{Kitchen, Ticket};
+use crate::billing::Bill;
+
+pub type OrderId = u32;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct OrderItem {
+    pub dish: String,
+    pub quantity: u32,
+    pub price_cents: u64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum OrderStatus {
+    Open,
+    InKitchen,
+    Served,
+    Paid,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Order {
+    pub id: OrderId,
+    pub table: u32,
+    items: Vec<OrderItem>,
+    status: OrderStatus,
+}
+
+impl Order {
+    pub fn items(&self) -> &[OrderItem] {
+        &self.items
+    }
+
+    pub fn status(&self) -> OrderStatus {
+        self.status
+    }
+
+    pub fn subtotal_cents(&self) -> Result<u64, ServingError> {
+        self.items.iter().try_fold(0u64, |sum, item| {
+            item.price_cents
+                .checked_mul(u64::from(item.quantity))
+                .and_then(|cost| sum.checked_add(cost))
+                .ok_or(ServingError::Overflow(self.id))
+        })
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum ServingError {
+    NoSuchOrder(OrderId),
+    NoSuchItem(String),
+    EmptyOrder(OrderId),
+    ZeroQuantity(String),
+    Overflow(OrderId),
+    // The dish is already on the order at a different price.
+    PriceMismatch {
+        dish: String,
+        on_order: u64,
+        given: u64,
+    },
+    WrongStatus {
+        order: OrderId,
+        expected: OrderStatus,
+        found: OrderStatus,
+    },
+}
+
+impl std::fmt::Display for ServingError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            ServingError::NoSuchOrder(id) => write!(f, "there is no order {id}"),
+            ServingError::NoSuchItem(dish) => write!(f, "the order has no {dish}"),
+            ServingError::EmptyOrder(id) => write!(f, "order {id} has no items"),
+            ServingError::ZeroQuantity(dish) => write!(f, "can't order zero {dish}"),
+            ServingError::Overflow(id) => write!(f, "order {id} is too large to add up"),
+            ServingError::PriceMismatch {
+                dish,
+                on_order,
+                given,
+            } => write!(
+                f,
+                "{dish} is on the order at {on_order} cents, not {given} cents"
+            ),
+            ServingError::WrongStatus {
+                order,
+                expected,
+                found,
+            } => write!(f, "order {order} is {found:?}, expected {expected:?}"),
+        }
+    }
+}
+
+impl std::error::Error for ServingError {}
+
+// The server tracks every order from the moment it's taken until it's paid.
+#[derive(Debug)]
+pub struct Server {
+    orders: HashMap<OrderId, Order>,
+    next_order_id: OrderId,
+}
+
+impl Server {
+    pub fn new() -> Server {
+        Server {
+            orders: HashMap::new(),
+            next_order_id: 1,
+        }
+    }
+
+    pub fn order(&self, id: OrderId) -> Option<&Order> {
+        self.orders.get(&id)
+    }
+
+    pub fn take_order(&mut self, table: u32) -> OrderId {
+        let id = self.next_order_id;
+        self.next_order_id += 1;
+        self.orders.insert(
+            id,
+            Order {
+                id,
+                table,
+                items: Vec::new(),
+                status: OrderStatus::Open,
+            },
+        );
+        id
+    }
+
+    // Adding a dish that's already on the order bumps its quantity instead of
+    // adding a second line. The price has to match the one already on the
+    // order; to change it, remove the dish and add it again.
+    pub fn add_item(
+        &mut self,
+        id: OrderId,
+        dish: &str,
+        quantity: u32,
+        price_cents: u64,
+    ) -> Result<(), ServingError> {
+        if quantity == 0 {
+            return Err(ServingError::ZeroQuantity(String::from(dish)));
+        }
+        let order = self.order_with_status(id, OrderStatus::Open)?;
+        match order.items.iter_mut().find(|item| item.dish == dish) {
+            Some(item) if item.price_cents != price_cents => {
+                return Err(ServingError::PriceMismatch {
+                    dish: String::from(dish),
+                    on_order: item.price_cents,
+                    given: price_cents,
+                });
+            }
+            Some(item) => {
+                item.quantity = item
+                    .quantity
+                    .checked_add(quantity)
+                    .ok_or(ServingError::Overflow(id))?;
+            }
+            None => order.items.push(OrderItem {
+                dish: String::from(dish),
+                quantity,
+                price_cents,
+            }),
+        }
+        Ok(())
+    }
+
+    pub fn change_quantity(
+        &mut self,
+        id: OrderId,
+        dish: &str,
+        quantity: u32,
+    ) -> Result<(), ServingError> {
+        if quantity == 0 {
+            return self.remove_item(id, dish);
+        }
+        let order = self.order_with_status(id, OrderStatus::Open)?;
+        let item = order
+            .items
+            .iter_mut()
+            .find(|item| item.dish == dish)
+            .ok_or_else(|| ServingError::NoSuchItem(String::from(dish)))?;
+        item.quantity = quantity;
+        Ok(())
+    }
+
+    pub fn remove_item(&mut self, id: OrderId, dish: &str) -> Result<(), ServingError> {
+        let order = self.order_with_status(id, OrderStatus::Open)?;
+        let position = order
+            .items
+            .iter()
+            .position(|item| item.dish == dish)
+            .ok_or_else(|| ServingError::NoSuchItem(String::from(dish)))?;
+        order.items.remove(position);
+        Ok(())
+    }
+
+    pub fn send_to_kitchen(
+        &mut self,
+        id: OrderId,
+        kitchen: &mut Kitchen,
+    ) -> Result<(), ServingError> {
+        let order = self.order_with_status(id, OrderStatus::Open)?;
+        if order.items.is_empty() {
+            return Err(ServingError::EmptyOrder(id));
+        }
+        order.status = OrderStatus::InKitchen;
+        kitchen.submit(Ticket {
+            order: order.id,
+            table: order.table,
+            dishes: order
+                .items
+                .iter()
+                .map(|item| (item.dish.clone(), item.quantity))
+                .collect(),
+        });
+        Ok(())
+    }
+
+    pub fn serve_order(&mut self, id: OrderId) -> Result<(), ServingError> {
+        let order = self.order_with_status(id, OrderStatus::InKitchen)?;
+        order.status = OrderStatus::Served;
+        Ok(())
+    }
+
+    pub fn take_payment(&mut self, id: OrderId, tax_rate_bps: u32) -> Result<Bill, ServingError> {
+        let order = self.order_with_status(id, OrderStatus::Served)?;
+        let subtotal = order.subtotal_cents()?;
+        order.status = OrderStatus::Paid;
+        Ok(Bill::new(subtotal, tax_rate_bps))
+    }
+
+    fn order_with_status(
+        &mut self,
+        id: OrderId,
+        expected: OrderStatus,
+    ) -> Result<&mut Order, ServingError> {
+        let order = self
+            .orders
+            .get_mut(&id)
+            .ok_or(ServingError::NoSuchOrder(id))?;
+        if order.status != expected {
+            return Err(ServingError::WrongStatus {
+                order: id,
+                expected,
+                found: order.status,
+            });
+        }
+        Ok(order)
+    }
+}
+
+// Goes through `new` so a default server hands out the same order ids.
+impl Default for Server {
+    fn default() -> Server {
+        Server::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn order_can_be_modified_until_sent() {
+        let mut server = Server::new();
+        let mut kitchen = Kitchen::new();
+        let id = server.take_order(4);
+
+        server.add_item(id, "Pancakes", 1, 850).unwrap();
+        server.add_item(id, "Pancakes", 1, 850).unwrap();
+        server.add_item(id, "Coffee", 2, 300).unwrap();
+        server.change_quantity(id, "Coffee", 1).unwrap();
+        assert_eq!(server.order(id).unwrap().subtotal_cents(), Ok(2000));
+
+        server.send_to_kitchen(id, &mut kitchen).unwrap();
+        assert_eq!(
+            server.add_item(id, "Juice", 1, 400),
+            Err(ServingError::WrongStatus {
+                order: id,
+                expected: OrderStatus::Open,
+                found: OrderStatus::InKitchen,
+            })
+        );
+        assert_eq!(kitchen.pending(), 1);
+    }
+
+    #[test]
+    fn order_goes_from_kitchen_to_paid() {
+        let mut server = Server::new();
+        let mut kitchen = Kitchen::new();
+        let id = server.take_order(1);
+        server.add_item(id, "Toast", 2, 250).unwrap();
+
+        assert!(server.take_payment(id, 800).is_err());
+        server.send_to_kitchen(id, &mut kitchen).unwrap();
+        server.serve_order(id).unwrap();
+        let bill = server.take_payment(id, 800).unwrap();
+
+        assert_eq!(bill.subtotal_cents(), 500);
+        assert_eq!(server.order(id).unwrap().status(), OrderStatus::Paid);
+    }
+
+    #[test]
+    fn empty_order_is_not_sent() {
+        let mut server = Server::new();
+        let mut kitchen = Kitchen::new();
+        let id = server.take_order(1);
+
+        assert_eq!(
+            server.send_to_kitchen(id, &mut kitchen),
+            Err(ServingError::EmptyOrder(id))
+        );
+        assert_eq!(
+            server.remove_item(id, "Soup"),
+            Err(ServingError::NoSuchItem(String::from("Soup")))
+        );
+    }
+
+    #[test]
+    fn rejects_zero_quantity_and_price_changes() {
+        let mut server = Server::default();
+        let id = server.take_order(2);
+        assert_eq!(id, Server::new().take_order(2));
+
+        assert_eq!(
+            server.add_item(id, "Soup", 0, 600),
+            Err(ServingError::ZeroQuantity(String::from("Soup")))
+        );
+        server.add_item(id, "Soup", 1, 600).unwrap();
+        assert_eq!(
+            server.add_item(id, "Soup", 1, 500),
+            Err(ServingError::PriceMismatch {
+                dish: String::from("Soup"),
+                on_order: 600,
+                given: 500,
+            })
+        );
+        assert_eq!(server.order(id).unwrap().subtotal_cents(), Ok(600));
+    }
+
+    #[test]
+    fn rejects_amounts_that_overflow() {
+        let mut server = Server::new();
+        let id = server.take_order(3);
+        server.add_item(id, "Tea", u32::MAX, 200).unwrap();
+        assert_eq!(
+            server.add_item(id, "Tea", 1, 200),
+            Err(ServingError::Overflow(id))
+        );
+        assert_eq!(server.order(id).unwrap().items()[0].quantity, u32::MAX);
+
+        server.add_item(id, "Caviar", 1, u64::MAX).unwrap();
+        assert_eq!(
+            server.order(id).unwrap().subtotal_cents(),
+            Err(ServingError::Overflow(id))
+        );
+    }
+}
@@ -1,7 +1,60 @@
+mod back_of_house;
+mod billing;
 mod front_of_house;
 
+pub use crate::back_of_house::{Breakfast, Kitchen, Season, Ticket};
+pub use crate::billing::{Bill, BillingError, format_cents};
 pub use crate::front_of_house::hosting;
+pub use crate::front_of_house::seating;
+pub use crate::front_of_house::serving;
 
 pub fn eat_at_restaurant() {
-    hosting::add_to_waitlist();
+    let mut host = hosting::Host::new(vec![hosting::Table::new(1, 2), hosting::Table::new(2, 4)]);
+    let mut server = serving::Server::new();
+    let mut kitchen = Kitchen::new();
+
+    host.add_to_waitlist("Ferris", 2)
+        .expect("a party of two is a valid party");
+    let seating = host.seat_at_table().expect("table 1 is free");
+    println!("Seated {} at table {}", seating.party.name, seating.table);
+
+    // Order a breakfast in the summer with Rye toast, then change our mind
+    // about what bread we'd like.
+    let mut meal = Breakfast::summer("Rye");
+    meal.toast = String::from("Wheat");
+    println!(
+        "I'd like {} toast please, with {}",
+        meal.toast,
+        meal.seasonal_fruit()
+    );
+
+    let order = server.take_order(seating.table);
+    server
+        .add_item(order, "Breakfast", 2, 1250)
+        .and_then(|_| server.add_item(order, "Coffee", 2, 300))
+        .and_then(|_| server.send_to_kitchen(order, &mut kitchen))
+        .expect("the order is still open");
+
+    if let Some(ticket) = kitchen.cook_order() {
+        println!(
+            "Kitchen cooked {:?} for table {}",
+            ticket.dishes, ticket.table
+        );
+        server
+            .serve_order(ticket.order)
+            .expect("the order was in the kitchen");
+    }
+
+    let bill = server
+        .take_payment(order, 825)
+        .expect("the order was served");
+    let shares = bill
+        .split(2, 1800)
+        .expect("two payers can split a small bill");
+    for share in shares {
+        println!("Each guest pays {}", format_cents(share));
+    }
+
+    host.clear_table(seating.table)
+        .expect("the table was occupied");
 }
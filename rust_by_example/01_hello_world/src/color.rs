@@ -0,0 +1,716 @@
+use std::fmt;
+use std::str::FromStr;
+
+// Conversions between the color models that `Color` in 08_flow_of_control
+// lists by name. Every model converts through sRGB with each channel in
+// 0.0..=1.0, kept as `f64` so that chaining conversions between the
+// floating-point models does not lose precision. Only `Rgb`, with its `u8`
+// channels, rounds.
+pub trait ColorModel: Sized {
+    fn to_unit_rgb(&self) -> [f64; 3];
+    fn from_unit_rgb(rgb: [f64; 3]) -> Self;
+
+    fn convert<M: ColorModel>(&self) -> M {
+        M::from_unit_rgb(self.to_unit_rgb())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Rgb {
+    pub red: u8,
+    pub green: u8,
+    pub blue: u8,
+}
+
+// Hue in degrees in 0.0..360.0; the other components in 0.0..=1.0.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Hsv {
+    pub hue: f64,
+    pub saturation: f64,
+    pub value: f64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Hsl {
+    pub hue: f64,
+    pub saturation: f64,
+    pub lightness: f64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Cmy {
+    pub cyan: f64,
+    pub magenta: f64,
+    pub yellow: f64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Cmyk {
+    pub cyan: f64,
+    pub magenta: f64,
+    pub yellow: f64,
+    pub key: f64,
+}
+
+// Any of the models, like the enum in 08_flow_of_control.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Color {
+    Rgb(Rgb),
+    Hsv(Hsv),
+    Hsl(Hsl),
+    Cmy(Cmy),
+    Cmyk(Cmyk),
+}
+
+impl Rgb {
+    pub const BLACK: Rgb = Rgb::new(0, 0, 0);
+    pub const WHITE: Rgb = Rgb::new(255, 255, 255);
+
+    pub const fn new(red: u8, green: u8, blue: u8) -> Rgb {
+        Rgb { red, green, blue }
+    }
+
+    pub fn from_hex(hex: u32) -> Rgb {
+        Rgb::new((hex >> 16) as u8, (hex >> 8) as u8, hex as u8)
+    }
+
+    pub fn hex(&self) -> u32 {
+        (u32::from(self.red) << 16) | (u32::from(self.green) << 8) | u32::from(self.blue)
+    }
+
+    // The CSS form, `#rrggbb`.
+    pub fn to_css(&self) -> String {
+        format!("#{:06x}", self.hex())
+    }
+}
+
+fn to_channel(unit: f64) -> u8 {
+    (unit.clamp(0.0, 1.0) * 255.0).round() as u8
+}
+
+impl ColorModel for Rgb {
+    fn to_unit_rgb(&self) -> [f64; 3] {
+        [self.red, self.green, self.blue].map(|c| f64::from(c) / 255.0)
+    }
+
+    fn from_unit_rgb([r, g, b]: [f64; 3]) -> Rgb {
+        Rgb::new(to_channel(r), to_channel(g), to_channel(b))
+    }
+}
+
+// Hue in degrees, chroma, and the largest and smallest channel.
+fn hue_chroma([r, g, b]: [f64; 3]) -> (f64, f64, f64, f64) {
+    let max = r.max(g).max(b);
+    let min = r.min(g).min(b);
+    let chroma = max - min;
+    let hue = if chroma == 0.0 {
+        0.0
+    } else if max == r {
+        60.0 * ((g - b) / chroma).rem_euclid(6.0)
+    } else if max == g {
+        60.0 * ((b - r) / chroma + 2.0)
+    } else {
+        60.0 * ((r - g) / chroma + 4.0)
+    };
+    (hue, chroma, max, min)
+}
+
+// The inverse of `hue_chroma`: the channels for a hue and chroma, before
+// the lightest-minus-chroma offset `m` is added.
+fn from_hue_chroma(hue: f64, chroma: f64, m: f64) -> [f64; 3] {
+    let h = hue.rem_euclid(360.0) / 60.0;
+    let x = chroma * (1.0 - (h.rem_euclid(2.0) - 1.0).abs());
+    let (r, g, b) = match h as u32 {
+        0 => (chroma, x, 0.0),
+        1 => (x, chroma, 0.0),
+        2 => (0.0, chroma, x),
+        3 => (0.0, x, chroma),
+        4 => (x, 0.0, chroma),
+        _ => (chroma, 0.0, x),
+    };
+    [r + m, g + m, b + m]
+}
+
+impl ColorModel for Hsv {
+    fn to_unit_rgb(&self) -> [f64; 3] {
+        let chroma = self.value * self.saturation;
+        from_hue_chroma(self.hue, chroma, self.value - chroma)
+    }
+
+    fn from_unit_rgb(rgb: [f64; 3]) -> Hsv {
+        let (hue, chroma, max, _) = hue_chroma(rgb);
+        Hsv {
+            hue,
+            saturation: if max == 0.0 { 0.0 } else { chroma / max },
+            value: max,
+        }
+    }
+}
+
+impl ColorModel for Hsl {
+    fn to_unit_rgb(&self) -> [f64; 3] {
+        let chroma = (1.0 - (2.0 * self.lightness - 1.0).abs()) * self.saturation;
+        from_hue_chroma(self.hue, chroma, self.lightness - chroma / 2.0)
+    }
+
+    fn from_unit_rgb(rgb: [f64; 3]) -> Hsl {
+        let (hue, chroma, max, min) = hue_chroma(rgb);
+        let lightness = (max + min) / 2.0;
+        let saturation = if chroma == 0.0 {
+            0.0
+        } else {
+            chroma / (1.0 - (2.0 * lightness - 1.0).abs())
+        };
+        Hsl {
+            hue,
+            saturation,
+            lightness,
+        }
+    }
+}
+
+impl ColorModel for Cmy {
+    fn to_unit_rgb(&self) -> [f64; 3] {
+        [1.0 - self.cyan, 1.0 - self.magenta, 1.0 - self.yellow]
+    }
+
+    fn from_unit_rgb([r, g, b]: [f64; 3]) -> Cmy {
+        Cmy {
+            cyan: 1.0 - r,
+            magenta: 1.0 - g,
+            yellow: 1.0 - b,
+        }
+    }
+}
+
+impl ColorModel for Cmyk {
+    fn to_unit_rgb(&self) -> [f64; 3] {
+        let white = 1.0 - self.key;
+        [self.cyan, self.magenta, self.yellow].map(|c| (1.0 - c) * white)
+    }
+
+    fn from_unit_rgb(rgb: [f64; 3]) -> Cmyk {
+        let max = rgb[0].max(rgb[1]).max(rgb[2]);
+        if max == 0.0 {
+            return Cmyk {
+                cyan: 0.0,
+                magenta: 0.0,
+                yellow: 0.0,
+                key: 1.0,
+            };
+        }
+        let [cyan, magenta, yellow] = rgb.map(|c| (max - c) / max);
+        Cmyk {
+            cyan,
+            magenta,
+            yellow,
+            key: 1.0 - max,
+        }
+    }
+}
+
+impl ColorModel for Color {
+    fn to_unit_rgb(&self) -> [f64; 3] {
+        match self {
+            Color::Rgb(c) => c.to_unit_rgb(),
+            Color::Hsv(c) => c.to_unit_rgb(),
+            Color::Hsl(c) => c.to_unit_rgb(),
+            Color::Cmy(c) => c.to_unit_rgb(),
+            Color::Cmyk(c) => c.to_unit_rgb(),
+        }
+    }
+
+    fn from_unit_rgb(rgb: [f64; 3]) -> Color {
+        Color::Rgb(Rgb::from_unit_rgb(rgb))
+    }
+}
+
+// The same format as the `Color` exercise in main.rs.
+impl fmt::Display for Rgb {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "RGB ({}, {}, {}) {:#08X}",
+            self.red,
+            self.green,
+            self.blue,
+            self.hex()
+        )
+    }
+}
+
+fn percent(unit: f64) -> f64 {
+    unit * 100.0
+}
+
+impl fmt::Display for Hsv {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "hsv({:.0}, {:.0}%, {:.0}%)",
+            self.hue,
+            percent(self.saturation),
+            percent(self.value)
+        )
+    }
+}
+
+impl fmt::Display for Hsl {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "hsl({:.0}, {:.0}%, {:.0}%)",
+            self.hue,
+            percent(self.saturation),
+            percent(self.lightness)
+        )
+    }
+}
+
+impl fmt::Display for Cmy {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "cmy({:.0}%, {:.0}%, {:.0}%)",
+            percent(self.cyan),
+            percent(self.magenta),
+            percent(self.yellow)
+        )
+    }
+}
+
+impl fmt::Display for Cmyk {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "cmyk({:.0}%, {:.0}%, {:.0}%, {:.0}%)",
+            percent(self.cyan),
+            percent(self.magenta),
+            percent(self.yellow),
+            percent(self.key)
+        )
+    }
+}
+
+impl fmt::Display for Color {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Color::Rgb(c) => c.fmt(f),
+            Color::Hsv(c) => c.fmt(f),
+            Color::Hsl(c) => c.fmt(f),
+            Color::Cmy(c) => c.fmt(f),
+            Color::Cmyk(c) => c.fmt(f),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ColorError {
+    InvalidHex(String),
+    InvalidComponent(String),
+    WrongComponentCount { expected: usize, found: usize },
+    UnknownFormat(String),
+}
+
+impl fmt::Display for ColorError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ColorError::InvalidHex(s) => write!(f, "`{s}` is not a #rgb or #rrggbb color"),
+            ColorError::InvalidComponent(s) => write!(f, "`{s}` is not a valid color component"),
+            ColorError::WrongComponentCount { expected, found } => {
+                write!(f, "expected {expected} components, found {found}")
+            }
+            ColorError::UnknownFormat(s) => write!(
+                f,
+                "`{s}` is not a color, expected #rgb, #rrggbb, rgb(...) or hsl(...)"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ColorError {}
+
+fn parse_hex(digits: &str) -> Result<Rgb, ColorError> {
+    let invalid = || ColorError::InvalidHex(format!("#{digits}"));
+    if !digits.bytes().all(|b| b.is_ascii_hexdigit()) {
+        return Err(invalid());
+    }
+    let value = u32::from_str_radix(digits, 16).map_err(|_| invalid())?;
+    match digits.len() {
+        // Each digit of #rgb is doubled: #f80 is #ff8800.
+        3 => {
+            let expand = |d: u32| (d & 0xf) as u8 * 0x11;
+            Ok(Rgb::new(
+                expand(value >> 8),
+                expand(value >> 4),
+                expand(value),
+            ))
+        }
+        6 => Ok(Rgb::from_hex(value)),
+        _ => Err(invalid()),
+    }
+}
+
+// Splits the inside of `rgb(...)` or `hsl(...)` on commas or whitespace, and
+// drops an alpha, whether given after `/` or as a fourth component.
+fn components(args: &str, expected: usize) -> Result<Vec<&str>, ColorError> {
+    let args = args.split('/').next().unwrap_or("");
+    let parts: Vec<&str> = args
+        .split(|c: char| c == ',' || c.is_whitespace())
+        .filter(|p| !p.is_empty())
+        .collect();
+    if parts.len() != expected && parts.len() != expected + 1 {
+        return Err(ColorError::WrongComponentCount {
+            expected,
+            found: parts.len(),
+        });
+    }
+    Ok(parts[..expected].to_vec())
+}
+
+// A number, or a percentage of `full` when it ends in `%`, clamped to
+// 0..=full as CSS does.
+fn component(s: &str, full: f64) -> Result<f64, ColorError> {
+    let invalid = || ColorError::InvalidComponent(String::from(s));
+    let (number, scale) = match s.strip_suffix('%') {
+        Some(number) => (number, full / 100.0),
+        None => (s, 1.0),
+    };
+    let value: f64 = number.parse().map_err(|_| invalid())?;
+    if !value.is_finite() {
+        return Err(invalid());
+    }
+    Ok((value * scale).clamp(0.0, full))
+}
+
+fn hue(s: &str) -> Result<f64, ColorError> {
+    let invalid = || ColorError::InvalidComponent(String::from(s));
+    let number = s.strip_suffix("deg").unwrap_or(s);
+    let value: f64 = number.parse().map_err(|_| invalid())?;
+    if !value.is_finite() {
+        return Err(invalid());
+    }
+    Ok(value.rem_euclid(360.0))
+}
+
+// Accepts the CSS forms `#rgb`, `#rrggbb`, `rgb(255, 128, 0)`,
+// `rgb(100% 50% 0%)` and `hsl(30, 100%, 50%)`.
+impl FromStr for Rgb {
+    type Err = ColorError;
+
+    fn from_str(s: &str) -> Result<Rgb, ColorError> {
+        let s = s.trim();
+        if let Some(digits) = s.strip_prefix('#') {
+            return parse_hex(digits);
+        }
+
+        let lower = s.to_ascii_lowercase();
+        let function = |name: &str| {
+            lower
+                .strip_prefix(name)
+                .map(|rest| rest.strip_prefix('a').unwrap_or(rest))
+                .and_then(|rest| rest.trim_start().strip_prefix('('))
+                .and_then(|rest| rest.strip_suffix(')'))
+        };
+        if let Some(args) = function("rgb") {
+            let parts = components(args, 3)?;
+            let channel = |p: &str| component(p, 255.0).map(|c| c.round() as u8);
+            return Ok(Rgb::new(
+                channel(parts[0])?,
+                channel(parts[1])?,
+                channel(parts[2])?,
+            ));
+        }
+        if let Some(args) = function("hsl") {
+            let parts = components(args, 3)?;
+            let hsl = Hsl {
+                hue: hue(parts[0])?,
+                saturation: component(parts[1], 1.0)?,
+                lightness: component(parts[2], 1.0)?,
+            };
+            return Ok(hsl.convert());
+        }
+        Err(ColorError::UnknownFormat(String::from(s)))
+    }
+}
+
+fn linearize(channel: f64) -> f64 {
+    if channel <= 0.04045 {
+        channel / 12.92
+    } else {
+        ((channel + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+fn delinearize(linear: f64) -> f64 {
+    if linear <= 0.0031308 {
+        linear * 12.92
+    } else {
+        1.055 * linear.powf(1.0 / 2.4) - 0.055
+    }
+}
+
+// WCAG 2 relative luminance: 0.0 for black, 1.0 for white.
+pub fn relative_luminance(color: &impl ColorModel) -> f64 {
+    let [r, g, b] = color.to_unit_rgb().map(linearize);
+    0.2126 * r + 0.7152 * g + 0.0722 * b
+}
+
+// WCAG 2 contrast ratio, from 1.0 (no contrast) to 21.0 (black on white).
+// The order of the two colors does not matter.
+pub fn contrast_ratio(a: &impl ColorModel, b: &impl ColorModel) -> f64 {
+    let (la, lb) = (relative_luminance(a), relative_luminance(b));
+    (la.max(lb) + 0.05) / (la.min(lb) + 0.05)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ContrastLevel {
+    Fail,
+    // AA for large text only: at least 3:1.
+    AaLarge,
+    // AA for normal text, AAA for large text: at least 4.5:1.
+    Aa,
+    // AAA for normal text: at least 7:1.
+    Aaa,
+}
+
+impl ContrastLevel {
+    pub fn of(ratio: f64) -> ContrastLevel {
+        if ratio >= 7.0 {
+            ContrastLevel::Aaa
+        } else if ratio >= 4.5 {
+            ContrastLevel::Aa
+        } else if ratio >= 3.0 {
+            ContrastLevel::AaLarge
+        } else {
+            ContrastLevel::Fail
+        }
+    }
+}
+
+// Mixes `a` and `b`: `t = 0.0` gives `a`, `t = 1.0` gives `b`. This blends
+// the sRGB values directly, as CSS gradients do by default.
+pub fn mix(a: &impl ColorModel, b: &impl ColorModel, t: f64) -> Rgb {
+    let (a, b) = (a.to_unit_rgb(), b.to_unit_rgb());
+    let t = t.clamp(0.0, 1.0);
+    Rgb::from_unit_rgb([0, 1, 2].map(|i| a[i] + (b[i] - a[i]) * t))
+}
+
+// Like `mix`, but blends in linear light, which is how light physically
+// adds up. Halfway between red and green is a brighter yellow this way.
+pub fn mix_linear(a: &impl ColorModel, b: &impl ColorModel, t: f64) -> Rgb {
+    let (a, b) = (
+        a.to_unit_rgb().map(linearize),
+        b.to_unit_rgb().map(linearize),
+    );
+    let t = t.clamp(0.0, 1.0);
+    Rgb::from_unit_rgb([0, 1, 2].map(|i| delinearize(a[i] + (b[i] - a[i]) * t)))
+}
+
+// `steps` colors evenly spaced from `a` to `b`, both included.
+pub fn gradient(a: &impl ColorModel, b: &impl ColorModel, steps: usize) -> Vec<Rgb> {
+    match steps {
+        0 => Vec::new(),
+        1 => vec![a.convert()],
+        _ => (0..steps)
+            .map(|i| mix(a, b, i as f64 / (steps - 1) as f64))
+            .collect(),
+    }
+}
+
+fn rotated(base: Hsl, degrees: f64) -> Rgb {
+    Hsl {
+        hue: (base.hue + degrees).rem_euclid(360.0),
+        ..base
+    }
+    .convert()
+}
+
+pub fn complementary(color: &impl ColorModel) -> Rgb {
+    rotated(color.convert(), 180.0)
+}
+
+// The color and the two that split the hue circle into thirds with it.
+pub fn triadic(color: &impl ColorModel) -> [Rgb; 3] {
+    let base: Hsl = color.convert();
+    [0.0, 120.0, 240.0].map(|d| rotated(base, d))
+}
+
+// `count` colors with hues `spread` degrees apart, centred on `color`.
+pub fn analogous(color: &impl ColorModel, count: usize, spread: f64) -> Vec<Rgb> {
+    let base: Hsl = color.convert();
+    let middle = (count as f64 - 1.0) / 2.0;
+    (0..count)
+        .map(|i| rotated(base, (i as f64 - middle) * spread))
+        .collect()
+}
+
+// `count` steps from `color` toward black, not including black itself.
+pub fn shades(color: &impl ColorModel, count: usize) -> Vec<Rgb> {
+    (0..count)
+        .map(|i| mix(color, &Rgb::BLACK, i as f64 / count as f64))
+        .collect()
+}
+
+// `count` steps from `color` toward white, not including white itself.
+pub fn tints(color: &impl ColorModel, count: usize) -> Vec<Rgb> {
+    (0..count)
+        .map(|i| mix(color, &Rgb::WHITE, i as f64 / count as f64))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn every_rgb(step: usize) -> Vec<Rgb> {
+        let levels: Vec<u8> = (0..=255u8).step_by(step).chain([255]).collect();
+        let mut colors = Vec::new();
+        for &r in &levels {
+            for &g in &levels {
+                for &b in &levels {
+                    colors.push(Rgb::new(r, g, b));
+                }
+            }
+        }
+        colors
+    }
+
+    fn close(a: [f64; 3], b: [f64; 3]) -> bool {
+        a.iter().zip(b).all(|(x, y)| (x - y).abs() < 1e-9)
+    }
+
+    #[test]
+    fn rgb_round_trips_through_every_model() {
+        for rgb in every_rgb(5) {
+            assert_eq!(rgb.convert::<Hsv>().convert::<Rgb>(), rgb);
+            assert_eq!(rgb.convert::<Hsl>().convert::<Rgb>(), rgb);
+            assert_eq!(rgb.convert::<Cmy>().convert::<Rgb>(), rgb);
+            assert_eq!(rgb.convert::<Cmyk>().convert::<Rgb>(), rgb);
+        }
+    }
+
+    #[test]
+    fn floating_point_models_round_trip_without_rounding() {
+        for rgb in every_rgb(17) {
+            let start = rgb.to_unit_rgb();
+            let hsv: Hsv = rgb.convert();
+            let hsl: Hsl = hsv.convert();
+            let cmyk: Cmyk = hsl.convert();
+            let cmy: Cmy = cmyk.convert();
+            let back: Hsv = cmy.convert();
+            assert!(close(back.to_unit_rgb(), start), "{rgb}");
+        }
+    }
+
+    #[test]
+    fn known_conversions() {
+        let orange = Rgb::new(255, 128, 0);
+        let hsv: Hsv = orange.convert();
+        assert_eq!(hsv.to_string(), "hsv(30, 100%, 100%)");
+        let hsl: Hsl = Rgb::new(128, 128, 128).convert();
+        assert_eq!((hsl.saturation, hsl.lightness), (0.0, 128.0 / 255.0));
+        assert_eq!(
+            Rgb::new(255, 0, 0).convert::<Cmyk>().to_string(),
+            "cmyk(0%, 100%, 100%, 0%)"
+        );
+        assert_eq!(
+            Rgb::BLACK.convert::<Cmyk>().to_string(),
+            "cmyk(0%, 0%, 0%, 100%)"
+        );
+        assert_eq!(
+            Color::Cmy(Cmy {
+                cyan: 1.0,
+                magenta: 0.0,
+                yellow: 1.0
+            })
+            .convert::<Rgb>(),
+            Rgb::new(0, 255, 0)
+        );
+        assert_eq!(orange.to_string(), "RGB (255, 128, 0) 0xFF8000");
+    }
+
+    #[test]
+    fn parses_css_syntax() {
+        let orange = Rgb::new(255, 136, 0);
+        assert_eq!("#f80".parse(), Ok(orange));
+        assert_eq!("#FF8800".parse(), Ok(orange));
+        assert_eq!("rgb(255, 136, 0)".parse(), Ok(orange));
+        assert_eq!("RGB(100% 53.3% 0%)".parse(), Ok(orange));
+        assert_eq!("rgba(255, 136, 0, 0.5)".parse(), Ok(orange));
+        assert_eq!("rgb(255 136 0 / 50%)".parse(), Ok(orange));
+        assert_eq!("hsl(32deg, 100%, 50%)".parse(), Ok(orange));
+        assert_eq!("hsl(-328, 100%, 50%)".parse(), Ok(orange));
+        assert_eq!("rgb(300, -5, 0)".parse(), Ok(Rgb::new(255, 0, 0)));
+        assert_eq!(orange.to_css(), "#ff8800");
+    }
+
+    #[test]
+    fn rejects_malformed_colors() {
+        assert_eq!(
+            "#ff88".parse::<Rgb>(),
+            Err(ColorError::InvalidHex(String::from("#ff88")))
+        );
+        assert_eq!(
+            "#+f8".parse::<Rgb>(),
+            Err(ColorError::InvalidHex(String::from("#+f8")))
+        );
+        assert_eq!(
+            "rgb(1, 2)".parse::<Rgb>(),
+            Err(ColorError::WrongComponentCount {
+                expected: 3,
+                found: 2
+            })
+        );
+        assert_eq!(
+            "hsl(x, 1%, 1%)".parse::<Rgb>(),
+            Err(ColorError::InvalidComponent(String::from("x")))
+        );
+        for hue in ["inf", "nan", "-infdeg"] {
+            assert_eq!(
+                format!("hsl({hue}, 100%, 50%)").parse::<Rgb>(),
+                Err(ColorError::InvalidComponent(String::from(hue)))
+            );
+        }
+        assert_eq!(
+            "orange".parse::<Rgb>(),
+            Err(ColorError::UnknownFormat(String::from("orange")))
+        );
+    }
+
+    #[test]
+    fn wcag_luminance_and_contrast() {
+        assert_eq!(relative_luminance(&Rgb::WHITE), 1.0);
+        assert_eq!(relative_luminance(&Rgb::BLACK), 0.0);
+        assert_eq!(contrast_ratio(&Rgb::BLACK, &Rgb::WHITE), 21.0);
+        let gray = Rgb::from_hex(0x767676);
+        let ratio = contrast_ratio(&Rgb::WHITE, &gray);
+        assert!((ratio - 4.54).abs() < 0.01, "{ratio}");
+        assert_eq!(ContrastLevel::of(ratio), ContrastLevel::Aa);
+        assert_eq!(ContrastLevel::of(1.0), ContrastLevel::Fail);
+    }
+
+    #[test]
+    fn blends_and_palettes() {
+        let red = Rgb::new(255, 0, 0);
+        let green = Rgb::new(0, 255, 0);
+        assert_eq!(mix(&red, &green, 0.5), Rgb::new(128, 128, 0));
+        assert_eq!(mix_linear(&red, &green, 0.5), Rgb::new(188, 188, 0));
+        assert_eq!(
+            gradient(&Rgb::BLACK, &Rgb::WHITE, 3),
+            [Rgb::BLACK, Rgb::new(128, 128, 128), Rgb::WHITE]
+        );
+        assert_eq!(complementary(&red), Rgb::new(0, 255, 255));
+        assert_eq!(
+            triadic(&red),
+            [red, Rgb::new(0, 255, 0), Rgb::new(0, 0, 255)]
+        );
+        let hues: Vec<f64> = analogous(&red, 3, 30.0)
+            .iter()
+            .map(|c| c.convert::<Hsl>().hue.round())
+            .collect();
+        assert_eq!(hues, [330.0, 0.0, 30.0]);
+        assert_eq!(shades(&red, 2), [red, Rgb::new(128, 0, 0)]);
+        assert_eq!(tints(&red, 2), [red, Rgb::new(255, 128, 128)]);
+    }
+}
@@ -0,0 +1,542 @@
+use std::fmt;
+use std::ops::{Add, Div, Mul, Neg, Sub};
+use std::str::FromStr;
+
+// The numbers a `Complex` can be built from: anything with the four
+// operations, negation, and a zero and one.
+pub trait Num:
+    Copy
+    + PartialEq
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+    + Div<Output = Self>
+    + Neg<Output = Self>
+{
+    const ZERO: Self;
+    const ONE: Self;
+}
+
+// The floating-point types, which also get the transcendental functions.
+pub trait Float: Num + PartialOrd {
+    const PI: Self;
+
+    fn from_f64(x: f64) -> Self;
+    fn sqrt(self) -> Self;
+    fn exp(self) -> Self;
+    fn ln(self) -> Self;
+    fn sin(self) -> Self;
+    fn cos(self) -> Self;
+    fn atan2(self, other: Self) -> Self;
+    fn hypot(self, other: Self) -> Self;
+    fn abs(self) -> Self;
+}
+
+macro_rules! num_impls {
+    ($zero:literal, $one:literal; $($t:ty),*) => {
+        $(
+            impl Num for $t {
+                const ZERO: $t = $zero;
+                const ONE: $t = $one;
+            }
+        )*
+    };
+}
+
+num_impls!(0, 1; i8, i16, i32, i64, i128, isize);
+num_impls!(0.0, 1.0; f32, f64);
+
+macro_rules! float_impls {
+    ($($t:ident),*) => {
+        $(
+            impl Float for $t {
+                const PI: $t = std::$t::consts::PI;
+
+                fn from_f64(x: f64) -> $t {
+                    x as $t
+                }
+                fn sqrt(self) -> $t {
+                    $t::sqrt(self)
+                }
+                fn exp(self) -> $t {
+                    $t::exp(self)
+                }
+                fn ln(self) -> $t {
+                    $t::ln(self)
+                }
+                fn sin(self) -> $t {
+                    $t::sin(self)
+                }
+                fn cos(self) -> $t {
+                    $t::cos(self)
+                }
+                fn atan2(self, other: $t) -> $t {
+                    $t::atan2(self, other)
+                }
+                fn hypot(self, other: $t) -> $t {
+                    $t::hypot(self, other)
+                }
+                fn abs(self) -> $t {
+                    $t::abs(self)
+                }
+            }
+        )*
+    };
+}
+
+float_impls!(f32, f64);
+
+// `real + imag·i`. The `Complex` in main.rs, generic over the number type.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Complex<T> {
+    pub real: T,
+    pub imag: T,
+}
+
+impl<T: Num> Complex<T> {
+    pub const fn new(real: T, imag: T) -> Complex<T> {
+        Complex { real, imag }
+    }
+
+    pub fn i() -> Complex<T> {
+        Complex::new(T::ZERO, T::ONE)
+    }
+
+    pub fn conj(self) -> Complex<T> {
+        Complex::new(self.real, -self.imag)
+    }
+
+    // The squared modulus, which needs no square root.
+    pub fn norm_sqr(self) -> T {
+        self.real * self.real + self.imag * self.imag
+    }
+
+    pub fn scale(self, factor: T) -> Complex<T> {
+        Complex::new(self.real * factor, self.imag * factor)
+    }
+}
+
+impl<T: Float> Complex<T> {
+    pub fn from_polar(modulus: T, argument: T) -> Complex<T> {
+        Complex::new(modulus * argument.cos(), modulus * argument.sin())
+    }
+
+    // `e^(iθ)`, the point at angle θ on the unit circle.
+    pub fn cis(argument: T) -> Complex<T> {
+        Complex::from_polar(T::ONE, argument)
+    }
+
+    pub fn modulus(self) -> T {
+        self.real.hypot(self.imag)
+    }
+
+    // The angle from the positive real axis, in -π..=π.
+    pub fn arg(self) -> T {
+        self.imag.atan2(self.real)
+    }
+
+    pub fn to_polar(self) -> (T, T) {
+        (self.modulus(), self.arg())
+    }
+
+    pub fn exp(self) -> Complex<T> {
+        Complex::from_polar(self.real.exp(), self.imag)
+    }
+
+    // The principal natural logarithm, with imaginary part in -π..=π.
+    pub fn ln(self) -> Complex<T> {
+        Complex::new(self.modulus().ln(), self.arg())
+    }
+
+    // The principal square root, with a non-negative real part.
+    pub fn sqrt(self) -> Complex<T> {
+        if self.real == T::ZERO && self.imag == T::ZERO {
+            return self;
+        }
+        let two = T::ONE + T::ONE;
+        let m = self.modulus();
+        let real = ((m + self.real) / two).sqrt();
+        let imag = ((m - self.real) / two).sqrt();
+        let imag = if self.imag < T::ZERO { -imag } else { imag };
+        Complex::new(real, imag)
+    }
+
+    // The principal value of `self^exponent`. Zero to any power is zero.
+    pub fn pow(self, exponent: Complex<T>) -> Complex<T> {
+        if self.real == T::ZERO && self.imag == T::ZERO {
+            return self;
+        }
+        (self.ln() * exponent).exp()
+    }
+
+    pub fn powf(self, exponent: T) -> Complex<T> {
+        self.pow(Complex::new(exponent, T::ZERO))
+    }
+
+    pub fn powi(self, exponent: i32) -> Complex<T> {
+        let mut result = Complex::new(T::ONE, T::ZERO);
+        let mut base = if exponent < 0 { self.recip() } else { self };
+        let mut n = exponent.unsigned_abs();
+        while n > 0 {
+            if n & 1 == 1 {
+                result = result * base;
+            }
+            base = base * base;
+            n >>= 1;
+        }
+        result
+    }
+
+    pub fn recip(self) -> Complex<T> {
+        Complex::new(T::ONE, T::ZERO) / self
+    }
+}
+
+impl<T: Num> From<T> for Complex<T> {
+    fn from(real: T) -> Complex<T> {
+        Complex::new(real, T::ZERO)
+    }
+}
+
+impl<T: Num> Add for Complex<T> {
+    type Output = Complex<T>;
+
+    fn add(self, other: Complex<T>) -> Complex<T> {
+        Complex::new(self.real + other.real, self.imag + other.imag)
+    }
+}
+
+impl<T: Num> Sub for Complex<T> {
+    type Output = Complex<T>;
+
+    fn sub(self, other: Complex<T>) -> Complex<T> {
+        Complex::new(self.real - other.real, self.imag - other.imag)
+    }
+}
+
+impl<T: Num> Mul for Complex<T> {
+    type Output = Complex<T>;
+
+    fn mul(self, other: Complex<T>) -> Complex<T> {
+        Complex::new(
+            self.real * other.real - self.imag * other.imag,
+            self.real * other.imag + self.imag * other.real,
+        )
+    }
+}
+
+// Multiplies by the conjugate of `other` and divides by its squared
+// modulus. For integer types the parts are truncated, as integer division
+// is.
+impl<T: Num> Div for Complex<T> {
+    type Output = Complex<T>;
+
+    fn div(self, other: Complex<T>) -> Complex<T> {
+        let denominator = other.norm_sqr();
+        let numerator = self * other.conj();
+        Complex::new(numerator.real / denominator, numerator.imag / denominator)
+    }
+}
+
+impl<T: Num> Neg for Complex<T> {
+    type Output = Complex<T>;
+
+    fn neg(self) -> Complex<T> {
+        Complex::new(-self.real, -self.imag)
+    }
+}
+
+// `3+4i`, `3-4i`, `-2.5+0i`. `{:.2}` applies to both parts.
+impl<T: Num + fmt::Display> fmt::Display for Complex<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match f.precision() {
+            Some(p) => write!(f, "{:.p$}{:+.p$}i", self.real, self.imag),
+            None => write!(f, "{}{:+}i", self.real, self.imag),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseComplexError(String);
+
+impl fmt::Display for ParseComplexError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "`{}` is not a complex number like 3+4i", self.0)
+    }
+}
+
+impl std::error::Error for ParseComplexError {}
+
+// Accepts `3+4i`, `3 - 4i`, `-2.5`, `4i`, `-i` and `1e-3+2E+2i`. `j` may be
+// used in place of `i`.
+impl<T: Num + FromStr> FromStr for Complex<T> {
+    type Err = ParseComplexError;
+
+    fn from_str(s: &str) -> Result<Complex<T>, ParseComplexError> {
+        let error = || ParseComplexError(String::from(s));
+        let text: String = s.chars().filter(|c| !c.is_whitespace()).collect();
+        if text.is_empty() {
+            return Err(error());
+        }
+
+        // The imaginary part starts at the last `+` or `-` that is neither
+        // the first character nor the sign of an exponent.
+        let bytes = text.as_bytes();
+        let split = (1..bytes.len())
+            .rev()
+            .find(|&i| matches!(bytes[i], b'+' | b'-') && !matches!(bytes[i - 1], b'e' | b'E'));
+
+        let parse = |part: &str| part.parse::<T>().map_err(|_| error());
+        let parse_imag = |part: &str| {
+            let number = part.strip_suffix(['i', 'j']).ok_or_else(error)?;
+            match number {
+                "" | "+" => Ok(T::ONE),
+                "-" => Ok(-T::ONE),
+                _ => parse(number.strip_prefix('+').unwrap_or(number)),
+            }
+        };
+
+        let is_imag = |part: &str| part.ends_with(['i', 'j']);
+        match split {
+            Some(i) => {
+                let (real, imag) = text.split_at(i);
+                Ok(Complex::new(parse(real)?, parse_imag(imag)?))
+            }
+            None if is_imag(&text) => Ok(Complex::new(T::ZERO, parse_imag(&text)?)),
+            None => Ok(Complex::new(parse(&text)?, T::ZERO)),
+        }
+    }
+}
+
+// The discrete Fourier transform computed straight from its definition,
+// X[k] = Σ x[n]·e^(-2πikn/N), in O(N²). `fft` gives the same result faster.
+pub fn dft<T: Float>(input: &[Complex<T>]) -> Vec<Complex<T>> {
+    let n = input.len();
+    (0..n)
+        .map(|k| {
+            input
+                .iter()
+                .enumerate()
+                .fold(Complex::new(T::ZERO, T::ZERO), |sum, (j, &x)| {
+                    // Reducing k·j modulo n first keeps the angle small and
+                    // accurate for long inputs.
+                    let turns = T::from_f64(((k * j) % n) as f64 / n as f64);
+                    sum + x * Complex::cis(-(T::PI + T::PI) * turns)
+                })
+        })
+        .collect()
+}
+
+// Iterative radix-2 Cooley-Tukey; `data.len()` must be a power of two.
+// `sign` is -1 for the forward transform and +1 for the inverse.
+fn radix2<T: Float>(data: &mut [Complex<T>], sign: T) {
+    let n = data.len();
+    let mut j = 0;
+    for i in 1..n {
+        let mut bit = n >> 1;
+        while j & bit != 0 {
+            j ^= bit;
+            bit >>= 1;
+        }
+        j |= bit;
+        if i < j {
+            data.swap(i, j);
+        }
+    }
+
+    let mut len = 2;
+    while len <= n {
+        let step = Complex::cis(sign * (T::PI + T::PI) / T::from_f64(len as f64));
+        for chunk in data.chunks_mut(len) {
+            let mut w = Complex::new(T::ONE, T::ZERO);
+            let (low, high) = chunk.split_at_mut(len / 2);
+            for (a, b) in low.iter_mut().zip(high) {
+                let t = *b * w;
+                *b = *a - t;
+                *a = *a + t;
+                w = w * step;
+            }
+        }
+        len <<= 1;
+    }
+}
+
+// Bluestein's algorithm: rewrites a transform of any length as a
+// convolution, which is computed with power-of-two transforms.
+fn bluestein<T: Float>(data: &mut [Complex<T>], sign: T) {
+    let n = data.len();
+    let m = (2 * n - 1).next_power_of_two();
+    // w[k] = e^(sign·πik²/n), with k² reduced modulo 2n to keep it exact.
+    let chirp: Vec<Complex<T>> = (0..n)
+        .map(|k| {
+            let k2 = (k * k) % (2 * n);
+            Complex::cis(sign * T::PI * T::from_f64(k2 as f64 / n as f64))
+        })
+        .collect();
+
+    let zero = Complex::new(T::ZERO, T::ZERO);
+    let mut a = vec![zero; m];
+    for (k, &x) in data.iter().enumerate() {
+        a[k] = x * chirp[k];
+    }
+    let mut b = vec![zero; m];
+    b[0] = chirp[0].conj();
+    for k in 1..n {
+        b[k] = chirp[k].conj();
+        b[m - k] = chirp[k].conj();
+    }
+
+    radix2(&mut a, -T::ONE);
+    radix2(&mut b, -T::ONE);
+    for (x, y) in a.iter_mut().zip(&b) {
+        *x = *x * *y;
+    }
+    radix2(&mut a, T::ONE);
+
+    let scale = T::ONE / T::from_f64(m as f64);
+    for (k, x) in data.iter_mut().enumerate() {
+        *x = (a[k] * chirp[k]).scale(scale);
+    }
+}
+
+fn transform<T: Float>(data: &mut [Complex<T>], sign: T) {
+    match data.len() {
+        0 | 1 => {}
+        n if n.is_power_of_two() => radix2(data, sign),
+        _ => bluestein(data, sign),
+    }
+}
+
+// The discrete Fourier transform of `data`, in place, in O(N log N) for any
+// length. Matches `dft` up to rounding.
+pub fn fft<T: Float>(data: &mut [Complex<T>]) {
+    transform(data, -T::ONE);
+}
+
+// The inverse of `fft`, including the 1/N scaling, so `ifft` after `fft`
+// gives back the input.
+pub fn ifft<T: Float>(data: &mut [Complex<T>]) {
+    transform(data, T::ONE);
+    let scale = T::ONE / T::from_f64(data.len().max(1) as f64);
+    for x in data.iter_mut() {
+        *x = x.scale(scale);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f64::consts::{FRAC_PI_2, PI};
+
+    type C = Complex<f64>;
+
+    fn close(a: C, b: C) -> bool {
+        (a - b).modulus() < 1e-9
+    }
+
+    #[test]
+    fn arithmetic() {
+        let a = C::new(3.0, 4.0);
+        let b = C::new(1.0, -2.0);
+        assert_eq!(a + b, C::new(4.0, 2.0));
+        assert_eq!(a - b, C::new(2.0, 6.0));
+        assert_eq!(a * b, C::new(11.0, -2.0));
+        assert_eq!(a / b, C::new(-1.0, 2.0));
+        assert_eq!(-a, C::new(-3.0, -4.0));
+        assert_eq!(a.conj(), C::new(3.0, -4.0));
+        assert_eq!(C::i() * C::i(), C::from(-1.0));
+        let gaussian = Complex::new(7, 2) * Complex::new(1, -1);
+        assert_eq!(gaussian, Complex::new(9, -5));
+    }
+
+    #[test]
+    fn polar_form() {
+        let a = C::new(3.0, 4.0);
+        assert_eq!(a.modulus(), 5.0);
+        assert_eq!(a.norm_sqr(), 25.0);
+        assert_eq!(C::new(0.0, 2.0).arg(), FRAC_PI_2);
+        let (r, theta) = a.to_polar();
+        assert!(close(C::from_polar(r, theta), a));
+    }
+
+    #[test]
+    fn transcendental_functions() {
+        // Euler's identity.
+        assert!(close(C::new(0.0, PI).exp(), C::from(-1.0)));
+        assert!(close(C::from(-1.0).ln(), C::new(0.0, PI)));
+        assert_eq!(C::new(-4.0, 0.0).sqrt(), C::new(0.0, 2.0));
+        assert_eq!(C::new(3.0, -4.0).sqrt(), C::new(2.0, -1.0));
+        assert!(close(C::new(1.0, 1.0).powi(4), C::from(-4.0)));
+        assert!(close(C::new(1.0, 1.0).powi(-2), C::new(0.0, -0.5)));
+        assert!(close(C::new(1.0, 1.0).powf(2.0), C::new(0.0, 2.0)));
+        // i^i is real.
+        assert!(close(C::i().pow(C::i()), C::from((-FRAC_PI_2).exp())));
+        let z = C::new(0.3, -1.7);
+        assert!(close(z.ln().exp(), z));
+        assert!(close(z.sqrt() * z.sqrt(), z));
+    }
+
+    #[test]
+    fn parses_and_displays() {
+        assert_eq!("3+4i".parse(), Ok(C::new(3.0, 4.0)));
+        assert_eq!("3 - 4i".parse(), Ok(C::new(3.0, -4.0)));
+        assert_eq!("-2.5".parse(), Ok(C::new(-2.5, 0.0)));
+        assert_eq!("4i".parse(), Ok(C::new(0.0, 4.0)));
+        assert_eq!("-i".parse(), Ok(C::new(0.0, -1.0)));
+        assert_eq!("1+j".parse(), Ok(C::new(1.0, 1.0)));
+        assert_eq!("1e-3+2E+2i".parse(), Ok(C::new(0.001, 200.0)));
+        assert_eq!("-7-i".parse(), Ok(Complex::new(-7, -1)));
+        assert!("3+4".parse::<C>().is_err());
+        assert!("3+4k".parse::<C>().is_err());
+        assert!("".parse::<C>().is_err());
+        assert!("1.5i".parse::<Complex<i32>>().is_err());
+
+        let a = C::new(3.3, -7.2);
+        assert_eq!(a.to_string(), "3.3-7.2i");
+        assert_eq!(format!("{:.2}", C::new(1.0, 0.5)), "1.00+0.50i");
+        assert_eq!(a.to_string().parse(), Ok(a));
+    }
+
+    fn signal(n: usize) -> Vec<C> {
+        (0..n)
+            .map(|k| {
+                let t = k as f64;
+                C::new((0.3 * t).sin() + 0.5 * (1.7 * t).cos(), (0.1 * t * t).cos())
+            })
+            .collect()
+    }
+
+    #[test]
+    fn fft_matches_naive_dft() {
+        for n in (0..=33).chain([64, 100, 128, 255]) {
+            let input = signal(n);
+            let expected = dft(&input);
+            let mut actual = input.clone();
+            fft(&mut actual);
+            for (k, (a, e)) in actual.iter().zip(&expected).enumerate() {
+                assert!((*a - *e).modulus() < 1e-8, "n = {n}, k = {k}: {a} vs {e}");
+            }
+            ifft(&mut actual);
+            for (a, x) in actual.iter().zip(&input) {
+                assert!(close(*a, *x), "n = {n}");
+            }
+        }
+    }
+
+    #[test]
+    fn fft_finds_a_pure_tone() {
+        let n = 48;
+        let mut data: Vec<C> = (0..n)
+            .map(|k| C::cis(2.0 * PI * 5.0 * k as f64 / n as f64))
+            .collect();
+        fft(&mut data);
+        for (k, x) in data.iter().enumerate() {
+            let expected = if k == 5 { n as f64 } else { 0.0 };
+            assert!((x.modulus() - expected).abs() < 1e-9, "bin {k}");
+        }
+
+        let mut single: Vec<Complex<f32>> = vec![Complex::new(1.0, 0.0); 8];
+        fft(&mut single);
+        assert_eq!(single[0], Complex::new(8.0, 0.0));
+    }
+}
@@ -0,0 +1,452 @@
+use std::f64::consts::PI;
+use std::fmt;
+use std::str::FromStr;
+
+// Mean Earth radius, used by the spherical (haversine) formulas.
+pub const EARTH_RADIUS_M: f64 = 6_371_008.8;
+
+// The WGS-84 ellipsoid, used by Vincenty's formula.
+const WGS84_A: f64 = 6_378_137.0;
+const WGS84_F: f64 = 1.0 / 298.257_223_563;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum GeoError {
+    LatitudeOutOfRange(f64),
+    LongitudeOutOfRange(f64),
+    InvalidAngle(String),
+    WrongHemisphere(String),
+    MissingLongitude,
+    // Vincenty's iteration does not settle for nearly antipodal points.
+    NoConvergence,
+    MalformedRecord { line: usize },
+}
+
+impl fmt::Display for GeoError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            GeoError::LatitudeOutOfRange(v) => {
+                write!(f, "latitude {v} is outside -90..=90 degrees")
+            }
+            GeoError::LongitudeOutOfRange(v) => {
+                write!(f, "longitude {v} is outside -180..=180 degrees")
+            }
+            GeoError::InvalidAngle(s) => write!(f, "`{s}` is not an angle"),
+            GeoError::WrongHemisphere(s) => write!(f, "`{s}` has the wrong hemisphere letter"),
+            GeoError::MissingLongitude => write!(f, "expected a latitude and a longitude"),
+            GeoError::NoConvergence => {
+                write!(f, "distance did not converge for nearly antipodal points")
+            }
+            GeoError::MalformedRecord { line } => {
+                write!(f, "line {line}: expected name,country,latitude,longitude")
+            }
+        }
+    }
+}
+
+impl std::error::Error for GeoError {}
+
+// A point on Earth in degrees. Latitude is within -90..=90 (north positive)
+// and longitude within -180..=180 (east positive); `new` rejects anything
+// else, so every `Coordinate` is a real place.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Coordinate {
+    lat: f64,
+    lon: f64,
+}
+
+impl Coordinate {
+    pub fn new(lat: f64, lon: f64) -> Result<Coordinate, GeoError> {
+        if !(-90.0..=90.0).contains(&lat) {
+            return Err(GeoError::LatitudeOutOfRange(lat));
+        }
+        if !(-180.0..=180.0).contains(&lon) {
+            return Err(GeoError::LongitudeOutOfRange(lon));
+        }
+        Ok(Coordinate { lat, lon })
+    }
+
+    pub fn lat(&self) -> f64 {
+        self.lat
+    }
+
+    pub fn lon(&self) -> f64 {
+        self.lon
+    }
+
+    // Great-circle distance in metres on a spherical Earth. Within about
+    // 0.5% of the true distance.
+    pub fn haversine_distance(&self, other: &Coordinate) -> f64 {
+        let (phi1, phi2) = (self.lat.to_radians(), other.lat.to_radians());
+        let d_phi = phi2 - phi1;
+        let d_lambda = (other.lon - self.lon).to_radians();
+        let h =
+            (d_phi / 2.0).sin().powi(2) + phi1.cos() * phi2.cos() * (d_lambda / 2.0).sin().powi(2);
+        2.0 * EARTH_RADIUS_M * h.sqrt().min(1.0).asin()
+    }
+
+    // Distance in metres on the WGS-84 ellipsoid by Vincenty's inverse
+    // formula, accurate to well under a millimetre. Fails for points that
+    // are nearly antipodal, where the iteration does not converge.
+    pub fn vincenty_distance(&self, other: &Coordinate) -> Result<f64, GeoError> {
+        let (a, f) = (WGS84_A, WGS84_F);
+        let b = (1.0 - f) * a;
+
+        let l = (other.lon - self.lon).to_radians();
+        let u1 = ((1.0 - f) * self.lat.to_radians().tan()).atan();
+        let u2 = ((1.0 - f) * other.lat.to_radians().tan()).atan();
+        let (sin_u1, cos_u1) = u1.sin_cos();
+        let (sin_u2, cos_u2) = u2.sin_cos();
+
+        let mut lambda = l;
+        for _ in 0..200 {
+            let (sin_lambda, cos_lambda) = lambda.sin_cos();
+            let sin_sigma = ((cos_u2 * sin_lambda).powi(2)
+                + (cos_u1 * sin_u2 - sin_u1 * cos_u2 * cos_lambda).powi(2))
+            .sqrt();
+            if sin_sigma == 0.0 {
+                return Ok(0.0);
+            }
+            let cos_sigma = sin_u1 * sin_u2 + cos_u1 * cos_u2 * cos_lambda;
+            let sigma = sin_sigma.atan2(cos_sigma);
+            let sin_alpha = cos_u1 * cos_u2 * sin_lambda / sin_sigma;
+            let cos2_alpha = 1.0 - sin_alpha * sin_alpha;
+            // On the equator cos2_alpha is zero and this term drops out.
+            let cos_2sigma_m = if cos2_alpha == 0.0 {
+                0.0
+            } else {
+                cos_sigma - 2.0 * sin_u1 * sin_u2 / cos2_alpha
+            };
+            let c = f / 16.0 * cos2_alpha * (4.0 + f * (4.0 - 3.0 * cos2_alpha));
+            let previous = lambda;
+            lambda = l
+                + (1.0 - c)
+                    * f
+                    * sin_alpha
+                    * (sigma
+                        + c * sin_sigma
+                            * (cos_2sigma_m + c * cos_sigma * (-1.0 + 2.0 * cos_2sigma_m.powi(2))));
+            if lambda.abs() > PI {
+                return Err(GeoError::NoConvergence);
+            }
+            if (lambda - previous).abs() < 1e-12 {
+                let u_sq = cos2_alpha * (a * a - b * b) / (b * b);
+                let big_a = 1.0
+                    + u_sq / 16384.0 * (4096.0 + u_sq * (-768.0 + u_sq * (320.0 - 175.0 * u_sq)));
+                let big_b = u_sq / 1024.0 * (256.0 + u_sq * (-128.0 + u_sq * (74.0 - 47.0 * u_sq)));
+                let delta_sigma = big_b
+                    * sin_sigma
+                    * (cos_2sigma_m
+                        + big_b / 4.0
+                            * (cos_sigma * (-1.0 + 2.0 * cos_2sigma_m.powi(2))
+                                - big_b / 6.0
+                                    * cos_2sigma_m
+                                    * (-3.0 + 4.0 * sin_sigma.powi(2))
+                                    * (-3.0 + 4.0 * cos_2sigma_m.powi(2))));
+                return Ok(b * big_a * (sigma - delta_sigma));
+            }
+        }
+        Err(GeoError::NoConvergence)
+    }
+
+    // The compass bearing in degrees (0 = north, 90 = east) to set off on to
+    // follow the great circle to `other`.
+    pub fn initial_bearing(&self, other: &Coordinate) -> f64 {
+        let (phi1, phi2) = (self.lat.to_radians(), other.lat.to_radians());
+        let d_lambda = (other.lon - self.lon).to_radians();
+        let y = d_lambda.sin() * phi2.cos();
+        let x = phi1.cos() * phi2.sin() - phi1.sin() * phi2.cos() * d_lambda.cos();
+        y.atan2(x).to_degrees().rem_euclid(360.0)
+    }
+}
+
+fn write_dms(f: &mut fmt::Formatter, degrees: f64, hemisphere: char) -> fmt::Result {
+    let tenths = (degrees.abs() * 36_000.0).round() as u64;
+    let (d, m, s) = (tenths / 36_000, tenths / 600 % 60, tenths % 600);
+    write!(f, "{d}°{m:02}'{:02}.{}\"{hemisphere}", s / 10, s % 10)
+}
+
+// `52.520°N 13.405°E`, like `City` in main.rs; `{:.5}` picks the number of
+// decimals. `{:#}` shows degrees, minutes and seconds: `52°31'12.0"N`.
+impl fmt::Display for Coordinate {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let ns = if self.lat >= 0.0 { 'N' } else { 'S' };
+        let ew = if self.lon >= 0.0 { 'E' } else { 'W' };
+        if f.alternate() {
+            write_dms(f, self.lat, ns)?;
+            write!(f, " ")?;
+            return write_dms(f, self.lon, ew);
+        }
+        let p = f.precision().unwrap_or(3);
+        write!(f, "{:.p$}°{ns} {:.p$}°{ew}", self.lat.abs(), self.lon.abs())
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Axis {
+    Latitude,
+    Longitude,
+}
+
+// Parses one angle in decimal degrees (`-6.2597`, `53.35N`) or degrees,
+// minutes and seconds (`52°31'12"N`, `52°31.2'N`, `52 31 12 N`).
+fn parse_angle(s: &str, axis: Axis) -> Result<f64, GeoError> {
+    let text = s.trim();
+    let invalid = || GeoError::InvalidAngle(String::from(text));
+
+    let is_hemisphere = |c: char| matches!(c.to_ascii_uppercase(), 'N' | 'S' | 'E' | 'W');
+    let (number, hemisphere) = match (text.chars().next(), text.chars().last()) {
+        (_, Some(c)) if is_hemisphere(c) => (&text[..text.len() - 1], Some(c)),
+        (Some(c), _) if is_hemisphere(c) => (&text[1..], Some(c)),
+        _ => (text, None),
+    };
+
+    let parts: Vec<&str> = number
+        .split(|c: char| matches!(c, '°' | '\'' | '"' | '′' | '″') || c.is_whitespace())
+        .filter(|p| !p.is_empty())
+        .collect();
+    if parts.is_empty() || parts.len() > 3 {
+        return Err(invalid());
+    }
+    let mut numbers = Vec::new();
+    for part in &parts {
+        let value: f64 = part.parse().map_err(|_| invalid())?;
+        if !value.is_finite() {
+            return Err(invalid());
+        }
+        numbers.push(value);
+    }
+
+    let negative = parts[0].starts_with('-');
+    let mut degrees = numbers[0].abs();
+    for (value, unit) in numbers[1..].iter().zip([60.0, 3600.0]) {
+        if !(0.0..60.0).contains(value) {
+            return Err(invalid());
+        }
+        degrees += value / unit;
+    }
+
+    let sign = match hemisphere.map(|c| c.to_ascii_uppercase()) {
+        None => 1.0,
+        Some(_) if negative => return Err(invalid()),
+        Some('N') if axis == Axis::Latitude => 1.0,
+        Some('S') if axis == Axis::Latitude => -1.0,
+        Some('E') if axis == Axis::Longitude => 1.0,
+        Some('W') if axis == Axis::Longitude => -1.0,
+        Some(_) => return Err(GeoError::WrongHemisphere(String::from(text))),
+    };
+    Ok(if negative { -degrees } else { sign * degrees })
+}
+
+// Accepts a latitude and a longitude separated by a comma or whitespace, in
+// decimal degrees or DMS: `53.3478, -6.2597` or `52°31'12"N 13°24'18"E`.
+impl FromStr for Coordinate {
+    type Err = GeoError;
+
+    fn from_str(s: &str) -> Result<Coordinate, GeoError> {
+        let s = s.trim();
+        let (lat, lon) = if let Some(split) = s.split_once(',') {
+            split
+        } else if let Some(i) = s.find(['N', 'S', 'n', 's']).filter(|&i| i > 0) {
+            s.split_at(i + 1)
+        } else {
+            let mut halves = s.split_whitespace();
+            match (halves.next(), halves.next(), halves.next()) {
+                (Some(lat), Some(lon), None) => (lat, lon),
+                _ => return Err(GeoError::MissingLongitude),
+            }
+        };
+        if lon.trim().is_empty() {
+            return Err(GeoError::MissingLongitude);
+        }
+        Coordinate::new(
+            parse_angle(lat, Axis::Latitude)?,
+            parse_angle(lon, Axis::Longitude)?,
+        )
+    }
+}
+
+// Anything with a position, so `nearest` works on any list of places.
+pub trait Located {
+    fn position(&self) -> Coordinate;
+}
+
+impl Located for Coordinate {
+    fn position(&self) -> Coordinate {
+        *self
+    }
+}
+
+// The item closest to `to` by great-circle distance, or `None` for an empty
+// list.
+pub fn nearest<'a, T: Located>(to: &Coordinate, items: &'a [T]) -> Option<&'a T> {
+    items.iter().min_by(|a, b| {
+        let da = to.haversine_distance(&a.position());
+        let db = to.haversine_distance(&b.position());
+        da.total_cmp(&db)
+    })
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Place {
+    pub name: String,
+    pub country: String,
+    pub position: Coordinate,
+}
+
+impl Located for Place {
+    fn position(&self) -> Coordinate {
+        self.position
+    }
+}
+
+// Reads `name,country,latitude,longitude` records after a header line.
+// Fields are split on every comma; quoting is not supported.
+pub fn parse_places(csv: &str) -> Result<Vec<Place>, GeoError> {
+    let mut places = Vec::new();
+    for (index, line) in csv.lines().enumerate().skip(1) {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let malformed = GeoError::MalformedRecord { line: index + 1 };
+        let fields: Vec<&str> = line.split(',').map(str::trim).collect();
+        let [name, country, lat, lon] = fields[..] else {
+            return Err(malformed);
+        };
+        let lat = lat.parse().map_err(|_| malformed.clone())?;
+        let lon = lon.parse().map_err(|_| malformed.clone())?;
+        places.push(Place {
+            name: String::from(name),
+            country: String::from(country),
+            position: Coordinate::new(lat, lon)?,
+        });
+    }
+    Ok(places)
+}
+
+// A couple dozen world cities, compiled in from `cities.csv`.
+pub fn builtin_places() -> Vec<Place> {
+    parse_places(include_str!("cities.csv")).expect("built-in cities are valid")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn c(s: &str) -> Coordinate {
+        s.parse().unwrap()
+    }
+
+    fn place<'a>(places: &'a [Place], name: &str) -> &'a Place {
+        places.iter().find(|p| p.name == name).unwrap()
+    }
+
+    #[test]
+    fn validates_ranges() {
+        assert_eq!(
+            Coordinate::new(90.5, 0.0),
+            Err(GeoError::LatitudeOutOfRange(90.5))
+        );
+        assert_eq!(
+            Coordinate::new(0.0, -181.0),
+            Err(GeoError::LongitudeOutOfRange(-181.0))
+        );
+        assert!(Coordinate::new(f64::NAN, 0.0).is_err());
+        assert!(Coordinate::new(-90.0, 180.0).is_ok());
+    }
+
+    #[test]
+    fn parses_decimal_and_dms() {
+        let berlin = c("52°31'12\"N 13°24'18\"E");
+        assert!((berlin.lat() - 52.52).abs() < 1e-9);
+        assert!((berlin.lon() - 13.405).abs() < 1e-9);
+        assert_eq!(c("52.52, 13.405"), Coordinate::new(52.52, 13.405).unwrap());
+        assert_eq!(c("52.52N 13.405E"), Coordinate::new(52.52, 13.405).unwrap());
+        let sydney = c("33°52′S 151°12′E");
+        assert!((sydney.lat() + 33.0 + 52.0 / 60.0).abs() < 1e-9);
+        assert!((sydney.lon() - 151.2).abs() < 1e-9);
+        assert_eq!(c("S 34 36 13, W 58 22 54"), c("34°36'13\"S, 58°22'54\"W"));
+        assert_eq!(berlin.to_string(), "52.520°N 13.405°E");
+        assert_eq!(format!("{berlin:#}"), "52°31'12.0\"N 13°24'18.0\"E");
+        assert_eq!(format!("{:.1}", c("-6.26, -0.13")), "6.3°S 0.1°W");
+    }
+
+    #[test]
+    fn rejects_bad_coordinates() {
+        assert_eq!(
+            "52.52".parse::<Coordinate>(),
+            Err(GeoError::MissingLongitude)
+        );
+        assert_eq!(
+            "52°N 13°N".parse::<Coordinate>(),
+            Err(GeoError::WrongHemisphere(String::from("13°N")))
+        );
+        assert_eq!(
+            "13°E, 52°N".parse::<Coordinate>(),
+            Err(GeoError::WrongHemisphere(String::from("13°E")))
+        );
+        assert_eq!(
+            "52°61'N 13°E".parse::<Coordinate>(),
+            Err(GeoError::InvalidAngle(String::from("52°61'N")))
+        );
+        assert_eq!(
+            "-52°N, 13°E".parse::<Coordinate>(),
+            Err(GeoError::InvalidAngle(String::from("-52°N")))
+        );
+        assert_eq!(
+            "95, 0".parse::<Coordinate>(),
+            Err(GeoError::LatitudeOutOfRange(95.0))
+        );
+    }
+
+    #[test]
+    fn vincenty_matches_the_published_example() {
+        // Flinders Peak to Buninyong, from Vincenty's 1975 paper as worked
+        // by Geoscience Australia: 54,972.271 m.
+        let flinders = c("37°57'03.72030\"S 144°25'29.52440\"E");
+        let buninyong = c("37°39'10.15610\"S 143°55'35.38390\"E");
+        let distance = flinders.vincenty_distance(&buninyong).unwrap();
+        assert!((distance - 54_972.271).abs() < 0.001, "{distance}");
+        let haversine = flinders.haversine_distance(&buninyong);
+        assert!((haversine - distance).abs() / distance < 0.005);
+        // The spherical bearing is close to the ellipsoidal 306°52'05".
+        let bearing = flinders.initial_bearing(&buninyong);
+        assert!((bearing - 306.868).abs() < 0.2, "{bearing}");
+    }
+
+    #[test]
+    fn distance_edge_cases() {
+        let london = c("51.5074, -0.1278");
+        assert_eq!(london.vincenty_distance(&london), Ok(0.0));
+        assert_eq!(london.haversine_distance(&london), 0.0);
+        let equator = Coordinate::new(0.0, 0.0).unwrap();
+        let east = Coordinate::new(0.0, 1.0).unwrap();
+        assert!((equator.vincenty_distance(&east).unwrap() - 111_319.491).abs() < 0.001);
+        assert_eq!(equator.initial_bearing(&east), 90.0);
+        let antipode = Coordinate::new(0.5, 179.7).unwrap();
+        assert_eq!(
+            equator.vincenty_distance(&antipode),
+            Err(GeoError::NoConvergence)
+        );
+    }
+
+    #[test]
+    fn builtin_places_and_nearest() {
+        let places = builtin_places();
+        assert!(places.len() >= 20);
+        let london = place(&places, "London").position;
+        let paris = place(&places, "Paris").position;
+        let km = london.haversine_distance(&paris) / 1000.0;
+        assert!((343.0..345.0).contains(&km), "{km}");
+
+        let brighton = c("50.8225, -0.1372");
+        assert_eq!(nearest(&brighton, &places).unwrap().name, "London");
+        let auckland = c("36°50'S 174°46'E");
+        assert_eq!(nearest(&auckland, &places).unwrap().name, "Wellington");
+        assert_eq!(nearest::<Place>(&auckland, &[]), None);
+
+        assert_eq!(
+            parse_places("header\nOslo,Norway,59.95\n"),
+            Err(GeoError::MalformedRecord { line: 2 })
+        );
+    }
+}
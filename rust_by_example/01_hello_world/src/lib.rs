@@ -0,0 +1,4 @@
+pub mod color;
+pub mod complex;
+pub mod geo;
+pub mod table;
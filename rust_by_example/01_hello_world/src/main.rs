@@ -1,5 +1,10 @@
 use std::fmt;
 
+use hello_world::color::{self, Cmyk, ColorModel, Hsv, Rgb};
+use hello_world::complex;
+use hello_world::geo::{self, Coordinate, Located};
+use hello_world::table::{Align, Cell, Column, Style, Table, TableRow};
+
 // This structure cannot be printed either with `fmt::Display` or
 // with `fmt::Debug`.
 struct UnPrintable(i32);
@@ -113,22 +118,30 @@ impl fmt::Display for List {
 
 struct City {
     name: &'static str,
-    // Latitude
-    lat: f32,
-    // Longitude
-    lon: f32,
+    // Latitude and longitude, checked to be in range
+    position: Coordinate,
+}
+
+impl City {
+    fn new(name: &'static str, lat: f64, lon: f64) -> City {
+        let position = Coordinate::new(lat, lon).expect("city coordinates are in range");
+        City { name, position }
+    }
 }
 
 impl fmt::Display for City {
     // `f` is a buffer, and this method must write the formatted string into it.
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        let lat_c = if self.lat >= 0.0 { 'N' } else { 'S' };
-        let lon_c = if self.lon >= 0.0 { 'E' } else { 'W' };
-
         // `write!` is like `format!`, but it will write the formatted string
-        // into a buffer (the first argument).
-        write!(f, "{}: {:.3}°{} {:.3}°{}",
-               self.name, self.lat.abs(), lat_c, self.lon.abs(), lon_c)
+        // into a buffer (the first argument). `Coordinate` shows itself as
+        // `53.348°N 6.260°W`.
+        write!(f, "{}: {}", self.name, self.position)
+    }
+}
+
+impl Located for City {
+    fn position(&self) -> Coordinate {
+        self.position
     }
 }
 
@@ -147,6 +160,36 @@ impl fmt::Display for Color {
     }
 }
 
+// The same two types as rows of a table.
+impl TableRow for City {
+    fn columns() -> Vec<Column> {
+        vec![
+            Column::new("City"),
+            Column::new("Latitude").align(Align::Right).precision(3),
+            Column::new("Longitude").align(Align::Right).precision(3),
+        ]
+    }
+
+    fn cells(&self) -> Vec<Cell> {
+        vec![self.name.into(), self.position.lat().into(), self.position.lon().into()]
+    }
+}
+
+impl TableRow for Color {
+    fn columns() -> Vec<Column> {
+        vec![
+            Column::new("Red").align(Align::Right),
+            Column::new("Green").align(Align::Right),
+            Column::new("Blue").align(Align::Right),
+            Column::new("Display"),
+        ]
+    }
+
+    fn cells(&self) -> Vec<Cell> {
+        vec![self.red.into(), self.green.into(), self.blue.into(), self.to_string().into()]
+    }
+}
+
 
 
 fn main() {
@@ -276,6 +319,12 @@ fn main() {
     println!("Display: {}", comp2);
     println!("Debug: {:?}", comp2);
 
+    // The same two numbers with the generic `Complex` from the library,
+    // which can also do arithmetic.
+    let z1: complex::Complex<f64> = "3.3+7.2i".parse().unwrap();
+    let z2 = complex::Complex::new(4.7, -2.3);
+    println!("({z1}) * ({z2}) = {:.2}, |z1| = {:.3}", z1 * z2, z1.modulus());
+
 
 
 
@@ -286,20 +335,52 @@ fn main() {
 
 
 
-    for city in [
-        City { name: "Dublin", lat: 53.347778, lon: -6.259722 },
-        City { name: "Oslo", lat: 59.95, lon: 10.75 },
-        City { name: "Vancouver", lat: 49.25, lon: -123.1 },
-    ] {
+    let cities = [
+        City::new("Dublin", 53.347778, -6.259722),
+        City::new("Oslo", 59.95, 10.75),
+        City::new("Vancouver", 49.25, -123.1),
+    ];
+    for city in &cities {
         println!("{}", city);
     }
-    for color in [
+    println!("{}", Table::from_rows(&cities));
+
+    let (dublin, oslo) = (&cities[0].position, &cities[1].position);
+    println!(
+        "Dublin to Oslo: {:.1} km on a sphere, {:.1} km on the WGS-84 ellipsoid, bearing {:.0}°",
+        dublin.haversine_distance(oslo) / 1000.0,
+        dublin.vincenty_distance(oslo).unwrap() / 1000.0,
+        dublin.initial_bearing(oslo)
+    );
+    let cork: Coordinate = "51°53'49\"N 8°28'14\"W".parse().unwrap();
+    if let Some(city) = geo::nearest(&cork, &cities) {
+        println!("The closest of these to Cork ({cork:#}) is {}", city.name);
+    }
+    let seattle: Coordinate = "47.6062, -122.3321".parse().unwrap();
+    if let Some(place) = geo::nearest(&seattle, &geo::builtin_places()) {
+        println!("The closest built-in city to Seattle is {}, {}", place.name, place.country);
+    }
+
+    let colors = [
         Color { red: 128, green: 255, blue: 90 },
         Color { red: 0, green: 3, blue: 254 },
         Color { red: 0, green: 0, blue: 0 },
-    ] {
+    ];
+    for color in &colors {
         // Switch this to use {} once you've added an implementation
         // for fmt::Display.
         println!("{}", color);
     }
+    println!("{}", Table::from_rows(&colors).style(Style::Markdown));
+
+    // The same colors through the color module, converted to other models.
+    for css in ["#80ff5a", "rgb(0, 3, 254)", "hsl(0, 0%, 0%)"] {
+        let rgb: Rgb = css.parse().unwrap();
+        println!(
+            "{rgb} = {} = {}, contrast with white {:.2}:1",
+            rgb.convert::<Hsv>(),
+            rgb.convert::<Cmyk>(),
+            color::contrast_ratio(&rgb, &Rgb::WHITE)
+        );
+    }
 }
@@ -0,0 +1,438 @@
+use std::fmt;
+
+use unicode_width::UnicodeWidthStr;
+
+// Lays rows out in aligned columns, instead of every type writing its own
+// `write!` loop the way `List` and `City` do in main.rs.
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Align {
+    #[default]
+    Left,
+    Right,
+    Center,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Column {
+    header: String,
+    align: Align,
+    precision: Option<usize>,
+    max_width: Option<usize>,
+}
+
+impl Column {
+    pub fn new(header: &str) -> Column {
+        Column {
+            header: String::from(header),
+            align: Align::Left,
+            precision: None,
+            max_width: None,
+        }
+    }
+
+    pub fn align(mut self, align: Align) -> Column {
+        self.align = align;
+        self
+    }
+
+    // Decimal places for `Cell::Float` values in this column.
+    pub fn precision(mut self, precision: usize) -> Column {
+        self.precision = Some(precision);
+        self
+    }
+
+    // Longer cells are cut short and end in `…`. Applies to every style but
+    // CSV, which always keeps the full text.
+    pub fn max_width(mut self, max_width: usize) -> Column {
+        self.max_width = Some(max_width);
+        self
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Cell {
+    Text(String),
+    Integer(i64),
+    Float(f64),
+}
+
+impl Cell {
+    fn render(&self, precision: Option<usize>) -> String {
+        match (self, precision) {
+            (Cell::Text(text), _) => text.clone(),
+            (Cell::Integer(n), _) => n.to_string(),
+            (Cell::Float(x), Some(p)) => format!("{x:.p$}"),
+            (Cell::Float(x), None) => x.to_string(),
+        }
+    }
+}
+
+impl From<&str> for Cell {
+    fn from(text: &str) -> Cell {
+        Cell::Text(String::from(text))
+    }
+}
+
+impl From<String> for Cell {
+    fn from(text: String) -> Cell {
+        Cell::Text(text)
+    }
+}
+
+impl From<f64> for Cell {
+    fn from(x: f64) -> Cell {
+        Cell::Float(x)
+    }
+}
+
+impl From<f32> for Cell {
+    fn from(x: f32) -> Cell {
+        Cell::Float(f64::from(x))
+    }
+}
+
+macro_rules! integer_cells {
+    ($($t:ty),*) => {
+        $(
+            impl From<$t> for Cell {
+                fn from(n: $t) -> Cell {
+                    Cell::Integer(i64::from(n))
+                }
+            }
+        )*
+    };
+}
+
+integer_cells!(i8, i16, i32, i64, u8, u16, u32);
+
+// A type that can be shown as one row of a table.
+pub trait TableRow {
+    fn columns() -> Vec<Column>;
+    fn cells(&self) -> Vec<Cell>;
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Style {
+    // Lines drawn with box-drawing characters.
+    #[default]
+    Box,
+    // A GitHub-flavoured Markdown table.
+    Markdown,
+    // Comma-separated values with RFC 4180 quoting and no padding.
+    Csv,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Table {
+    columns: Vec<Column>,
+    rows: Vec<Vec<Cell>>,
+    style: Style,
+}
+
+impl Table {
+    pub fn new(columns: Vec<Column>) -> Table {
+        Table {
+            columns,
+            rows: Vec::new(),
+            style: Style::Box,
+        }
+    }
+
+    pub fn from_rows<'a, R: TableRow + 'a>(rows: impl IntoIterator<Item = &'a R>) -> Table {
+        let mut table = Table::new(R::columns());
+        for row in rows {
+            table.push(row.cells());
+        }
+        table
+    }
+
+    pub fn style(mut self, style: Style) -> Table {
+        self.style = style;
+        self
+    }
+
+    // Rows with fewer cells than there are columns are padded with empty
+    // ones; extra cells are dropped.
+    pub fn push(&mut self, mut cells: Vec<Cell>) {
+        cells.resize(self.columns.len(), Cell::Text(String::new()));
+        self.rows.push(cells);
+    }
+
+    pub fn render(&self) -> String {
+        self.to_string()
+    }
+
+    // The header and then every row, as display strings, before padding.
+    fn text_rows(&self) -> Vec<Vec<String>> {
+        let header = self.columns.iter().map(|c| c.header.clone()).collect();
+        let body = self.rows.iter().map(|row| {
+            row.iter()
+                .zip(&self.columns)
+                .map(|(cell, column)| cell.render(column.precision))
+                .collect()
+        });
+        std::iter::once(header).chain(body).collect()
+    }
+
+    fn fit_rows(&self) -> (Vec<Vec<String>>, Vec<usize>) {
+        let rows: Vec<Vec<String>> = self
+            .text_rows()
+            .into_iter()
+            .map(|row| {
+                row.iter()
+                    .zip(&self.columns)
+                    .map(|(text, column)| {
+                        let text = text.replace(['\n', '\r'], " ");
+                        match column.max_width {
+                            Some(max) => truncate(&text, max),
+                            None => text,
+                        }
+                    })
+                    .collect()
+            })
+            .collect();
+        let widths = (0..self.columns.len())
+            .map(|i| rows.iter().map(|row| row[i].width()).max().unwrap_or(0))
+            .collect();
+        (rows, widths)
+    }
+
+    fn write_box(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let (rows, widths) = self.fit_rows();
+        let rule = |left: &str, middle: &str, right: &str| {
+            let segments: Vec<String> = widths.iter().map(|w| "─".repeat(w + 2)).collect();
+            format!("{left}{}{right}", segments.join(middle))
+        };
+
+        writeln!(f, "{}", rule("┌", "┬", "┐"))?;
+        for (i, row) in rows.iter().enumerate() {
+            let cells: Vec<String> = row
+                .iter()
+                .zip(&self.columns)
+                .zip(&widths)
+                .map(|((text, column), &width)| pad(text, width, column.align))
+                .collect();
+            writeln!(f, "│ {} │", cells.join(" │ "))?;
+            if i == 0 {
+                writeln!(f, "{}", rule("├", "┼", "┤"))?;
+            }
+        }
+        write!(f, "{}", rule("└", "┴", "┘"))
+    }
+
+    fn write_markdown(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let (rows, widths) = self.fit_rows();
+        let rows: Vec<Vec<String>> = rows
+            .iter()
+            .map(|row| row.iter().map(|text| text.replace('|', "\\|")).collect())
+            .collect();
+        let widths: Vec<usize> = (0..widths.len())
+            .map(|i| {
+                rows.iter()
+                    .map(|row| row[i].width())
+                    .max()
+                    .unwrap_or(0)
+                    .max(3)
+            })
+            .collect();
+
+        for (i, row) in rows.iter().enumerate() {
+            let cells: Vec<String> = row
+                .iter()
+                .zip(&self.columns)
+                .zip(&widths)
+                .map(|((text, column), &width)| pad(text, width, column.align))
+                .collect();
+            write!(f, "| {} |", cells.join(" | "))?;
+            if i == 0 {
+                let rules: Vec<String> = self
+                    .columns
+                    .iter()
+                    .zip(&widths)
+                    .map(|(column, &width)| match column.align {
+                        Align::Left => format!(":{}", "-".repeat(width - 1)),
+                        Align::Right => format!("{}:", "-".repeat(width - 1)),
+                        Align::Center => format!(":{}:", "-".repeat(width - 2)),
+                    })
+                    .collect();
+                write!(f, "\n| {} |", rules.join(" | "))?;
+            }
+            if i + 1 < rows.len() {
+                writeln!(f)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn write_csv(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (i, row) in self.text_rows().iter().enumerate() {
+            if i > 0 {
+                writeln!(f)?;
+            }
+            let fields: Vec<String> = row.iter().map(|field| csv_field(field)).collect();
+            write!(f, "{}", fields.join(","))?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for Table {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.style {
+            Style::Box => self.write_box(f),
+            Style::Markdown => self.write_markdown(f),
+            Style::Csv => self.write_csv(f),
+        }
+    }
+}
+
+// Shortens `text` to at most `max` terminal columns, ending in `…` when
+// anything was cut. Wide characters are never split in half.
+pub fn truncate(text: &str, max: usize) -> String {
+    if text.width() <= max {
+        return String::from(text);
+    }
+    if max == 0 {
+        return String::new();
+    }
+    let mut out = String::new();
+    let mut used = 0;
+    for c in text.chars() {
+        let w = unicode_width::UnicodeWidthChar::width(c).unwrap_or(0);
+        if used + w > max - 1 {
+            break;
+        }
+        out.push(c);
+        used += w;
+    }
+    out.push('…');
+    out
+}
+
+// Pads by display width rather than by `char` count, so `東京` takes four
+// columns and a combining accent takes none.
+fn pad(text: &str, width: usize, align: Align) -> String {
+    let gap = width.saturating_sub(text.width());
+    let (left, right) = match align {
+        Align::Left => (0, gap),
+        Align::Right => (gap, 0),
+        Align::Center => (gap / 2, gap - gap / 2),
+    };
+    format!("{}{text}{}", " ".repeat(left), " ".repeat(right))
+}
+
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        String::from(field)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Planet {
+        name: &'static str,
+        moons: u32,
+        gravity: f64,
+    }
+
+    impl TableRow for Planet {
+        fn columns() -> Vec<Column> {
+            vec![
+                Column::new("Planet").max_width(8),
+                Column::new("Moons").align(Align::Right),
+                Column::new("g").align(Align::Center).precision(1),
+            ]
+        }
+
+        fn cells(&self) -> Vec<Cell> {
+            vec![self.name.into(), self.moons.into(), self.gravity.into()]
+        }
+    }
+
+    fn planets() -> Vec<Planet> {
+        vec![
+            Planet {
+                name: "Earth",
+                moons: 1,
+                gravity: 9.807,
+            },
+            Planet {
+                name: "Jupiter, the big one",
+                moons: 95,
+                gravity: 24.79,
+            },
+        ]
+    }
+
+    #[test]
+    fn box_style_aligns_columns() {
+        let table = Table::from_rows(&planets());
+        assert_eq!(
+            table.render(),
+            "\
+┌──────────┬───────┬──────┐
+│ Planet   │ Moons │  g   │
+├──────────┼───────┼──────┤
+│ Earth    │     1 │ 9.8  │
+│ Jupiter… │    95 │ 24.8 │
+└──────────┴───────┴──────┘"
+        );
+    }
+
+    #[test]
+    fn markdown_style() {
+        let mut table = Table::from_rows(&planets()).style(Style::Markdown);
+        table.push(vec!["a|b".into()]);
+        assert_eq!(
+            table.render(),
+            "\
+| Planet   | Moons |  g   |
+| :------- | ----: | :--: |
+| Earth    |     1 | 9.8  |
+| Jupiter… |    95 | 24.8 |
+| a\\|b     |       |      |"
+        );
+    }
+
+    #[test]
+    fn csv_style_quotes_and_keeps_full_text() {
+        let table = Table::from_rows(&planets()).style(Style::Csv);
+        assert_eq!(
+            table.render(),
+            "Planet,Moons,g\nEarth,1,9.8\n\"Jupiter, the big one\",95,24.8"
+        );
+        assert_eq!(csv_field("say \"hi\""), "\"say \"\"hi\"\"\"");
+    }
+
+    #[test]
+    fn measures_display_width() {
+        let mut table = Table::new(vec![Column::new("City"), Column::new("n")]);
+        table.push(vec!["東京".into(), 1.into()]);
+        table.push(vec!["Cafe\u{301}".into(), 2.into()]);
+        assert_eq!(
+            table.render(),
+            "\
+┌──────┬───┐
+│ City │ n │
+├──────┼───┤
+│ 東京 │ 1 │
+│ Cafe\u{301} │ 2 │
+└──────┴───┘"
+        );
+    }
+
+    #[test]
+    fn truncates_without_splitting_wide_chars() {
+        assert_eq!(truncate("hello", 5), "hello");
+        assert_eq!(truncate("hello world", 5), "hell…");
+        assert_eq!(truncate("東京タワー", 6), "東京…");
+        assert_eq!(truncate("東京タワー", 5), "東京…");
+        assert_eq!(truncate("abc", 1), "…");
+        assert_eq!(truncate("abc", 0), "");
+    }
+}
@@ -1,6 +1,9 @@
-use std::fmt;
 use std::mem;
 
+// The activity's 2x2 `Matrix(f32, f32, f32, f32)` and `transpose` grew into
+// the `matrix` module, which works for any size.
+use primitives::matrix::{self, fixed};
+
 // Tuples can be used as function arguments and as return values.
 fn reverse(pair: (i32, bool)) -> (bool, i32) {
     // `let` can be used to bind the members of a tuple to variables.
@@ -9,22 +12,6 @@ fn reverse(pair: (i32, bool)) -> (bool, i32) {
     (bool_param, int_param)
 }
 
-// The following struct is for the activity.
-#[derive(Debug)]
-struct Matrix(f32, f32, f32, f32);
-
-impl fmt::Display for Matrix {
-
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "( {} {} )\n( {} {} )", self.0, self.1, self.2, self.3)
-    }
-}
-
-fn transpose(matrix: Matrix) -> Matrix {
-    Matrix(matrix.0, matrix.2, matrix.1, matrix.3)
-}
-
-
 // This function borrows a slice.
 fn analyze_slice(slice: &[i32]) {
     println!("First element of the slice: {}", slice[0]);
@@ -131,11 +118,21 @@ fn main() {
     let (a, b, c, d) = tuple;
     println!("{:?}, {:?}, {:?}, {:?}", a, b, c, d);
 
-    let matrix = Matrix(1.1, 1.2, 2.1, 2.2);
+    let matrix = fixed::Matrix::new([[1.1f32, 1.2], [2.1, 2.2]]);
     println!("{:?}", matrix);
 
     println!("Matrix:\n{}", matrix);
-    println!("Transpose:\n{}", transpose(matrix));
+    println!("Transpose:\n{}", matrix.transpose());
+
+    let system = matrix::Matrix::from_rows(vec![
+        vec![2.0, 1.0, -1.0],
+        vec![-3.0, -1.0, 2.0],
+        vec![-2.0, 1.0, 2.0],
+    ])
+    .unwrap();
+    println!("Inverse of\n{system}\nis\n{:.3}", system.inverse().unwrap());
+    println!("Solution of the system with right-hand side (8, -11, -3): {:.3?}",
+             system.solve(&[8.0, -11.0, -3.0]).unwrap());
 
 
 
@@ -0,0 +1,641 @@
+use std::fmt;
+use std::ops::{Add, Div, Index, IndexMut, Mul, Neg, Sub};
+
+pub mod fixed;
+
+// Element types: the integers and floats, anything with a zero, a one and
+// ring arithmetic.
+pub trait Scalar:
+    Copy
+    + PartialEq
+    + fmt::Display
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+    + Neg<Output = Self>
+{
+    const ZERO: Self;
+    const ONE: Self;
+}
+
+// Element types that can also be divided, which LU decomposition needs.
+pub trait Field: Scalar + PartialOrd + Div<Output = Self> {
+    const EPSILON: Self;
+
+    fn abs(self) -> Self;
+    fn from_usize(n: usize) -> Self;
+}
+
+macro_rules! scalar_impls {
+    ($zero:literal, $one:literal; $($t:ty),*) => {
+        $(
+            impl Scalar for $t {
+                const ZERO: $t = $zero;
+                const ONE: $t = $one;
+            }
+        )*
+    };
+}
+
+scalar_impls!(0, 1; i8, i16, i32, i64, i128, isize);
+scalar_impls!(0.0, 1.0; f32, f64);
+
+macro_rules! field_impls {
+    ($($t:ident),*) => {
+        $(
+            impl Field for $t {
+                const EPSILON: $t = $t::EPSILON;
+
+                fn abs(self) -> $t {
+                    $t::abs(self)
+                }
+
+                fn from_usize(n: usize) -> $t {
+                    n as $t
+                }
+            }
+        )*
+    };
+}
+
+field_impls!(f32, f64);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MatrixError {
+    // The operands of `op` have shapes that don't fit together.
+    DimensionMismatch {
+        op: &'static str,
+        left: (usize, usize),
+        right: (usize, usize),
+    },
+    NotSquare {
+        rows: usize,
+        cols: usize,
+    },
+    Singular,
+    WrongLength {
+        expected: usize,
+        found: usize,
+    },
+    RaggedRows {
+        row: usize,
+    },
+}
+
+impl fmt::Display for MatrixError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            MatrixError::DimensionMismatch { op, left, right } => write!(
+                f,
+                "cannot {op} a {}x{} matrix and a {}x{} matrix",
+                left.0, left.1, right.0, right.1
+            ),
+            MatrixError::NotSquare { rows, cols } => {
+                write!(f, "a {rows}x{cols} matrix is not square")
+            }
+            MatrixError::Singular => write!(f, "matrix is singular"),
+            MatrixError::WrongLength { expected, found } => {
+                write!(f, "expected {expected} elements, found {found}")
+            }
+            MatrixError::RaggedRows { row } => {
+                write!(f, "row {row} has a different length from row 0")
+            }
+        }
+    }
+}
+
+impl std::error::Error for MatrixError {}
+
+// A dense matrix with its size chosen at runtime, stored row by row.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Matrix<T> {
+    rows: usize,
+    cols: usize,
+    data: Vec<T>,
+}
+
+impl<T: Scalar> Matrix<T> {
+    pub fn new(rows: usize, cols: usize, data: Vec<T>) -> Result<Matrix<T>, MatrixError> {
+        if data.len() != rows * cols {
+            return Err(MatrixError::WrongLength {
+                expected: rows * cols,
+                found: data.len(),
+            });
+        }
+        Ok(Matrix { rows, cols, data })
+    }
+
+    pub fn from_rows(rows: Vec<Vec<T>>) -> Result<Matrix<T>, MatrixError> {
+        let cols = rows.first().map_or(0, Vec::len);
+        if let Some(row) = rows.iter().position(|r| r.len() != cols) {
+            return Err(MatrixError::RaggedRows { row });
+        }
+        Ok(Matrix {
+            rows: rows.len(),
+            cols,
+            data: rows.into_iter().flatten().collect(),
+        })
+    }
+
+    pub fn zeros(rows: usize, cols: usize) -> Matrix<T> {
+        Matrix {
+            rows,
+            cols,
+            data: vec![T::ZERO; rows * cols],
+        }
+    }
+
+    pub fn identity(n: usize) -> Matrix<T> {
+        let mut m = Matrix::zeros(n, n);
+        for i in 0..n {
+            m[(i, i)] = T::ONE;
+        }
+        m
+    }
+
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+
+    pub fn shape(&self) -> (usize, usize) {
+        (self.rows, self.cols)
+    }
+
+    pub fn is_square(&self) -> bool {
+        self.rows == self.cols
+    }
+
+    pub fn get(&self, row: usize, col: usize) -> Option<&T> {
+        if row < self.rows && col < self.cols {
+            self.data.get(row * self.cols + col)
+        } else {
+            None
+        }
+    }
+
+    pub fn row(&self, row: usize) -> &[T] {
+        &self.data[row * self.cols..(row + 1) * self.cols]
+    }
+
+    pub fn transpose(&self) -> Matrix<T> {
+        let mut t = Matrix::zeros(self.cols, self.rows);
+        for r in 0..self.rows {
+            for c in 0..self.cols {
+                t[(c, r)] = self[(r, c)];
+            }
+        }
+        t
+    }
+
+    fn zip_with(
+        &self,
+        other: &Matrix<T>,
+        op: &'static str,
+        f: impl Fn(T, T) -> T,
+    ) -> Result<Matrix<T>, MatrixError> {
+        if self.shape() != other.shape() {
+            return Err(MatrixError::DimensionMismatch {
+                op,
+                left: self.shape(),
+                right: other.shape(),
+            });
+        }
+        let data = self.data.iter().zip(&other.data).map(|(&a, &b)| f(a, b));
+        Ok(Matrix {
+            rows: self.rows,
+            cols: self.cols,
+            data: data.collect(),
+        })
+    }
+
+    fn require_square(&self) -> Result<(), MatrixError> {
+        if self.is_square() {
+            Ok(())
+        } else {
+            Err(MatrixError::NotSquare {
+                rows: self.rows,
+                cols: self.cols,
+            })
+        }
+    }
+}
+
+// The factorization PA = LU, with L unit lower triangular and U upper
+// triangular packed into one matrix, and P recorded as a row permutation.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Lu<T> {
+    lu: Matrix<T>,
+    permutation: Vec<usize>,
+    swaps: usize,
+    singular: bool,
+}
+
+impl<T: Field> Lu<T> {
+    pub fn is_singular(&self) -> bool {
+        self.singular
+    }
+
+    pub fn determinant(&self) -> T {
+        if self.singular {
+            return T::ZERO;
+        }
+        let n = self.lu.rows;
+        let product = (0..n).fold(T::ONE, |p, i| p * self.lu[(i, i)]);
+        if self.swaps.is_multiple_of(2) {
+            product
+        } else {
+            -product
+        }
+    }
+
+    // Solves Ax = b by forward then back substitution.
+    pub fn solve(&self, b: &[T]) -> Result<Vec<T>, MatrixError> {
+        let n = self.lu.rows;
+        if b.len() != n {
+            return Err(MatrixError::DimensionMismatch {
+                op: "solve",
+                left: (n, n),
+                right: (b.len(), 1),
+            });
+        }
+        if self.singular {
+            return Err(MatrixError::Singular);
+        }
+
+        let mut x: Vec<T> = self.permutation.iter().map(|&p| b[p]).collect();
+        for i in 0..n {
+            for j in 0..i {
+                x[i] = x[i] - self.lu[(i, j)] * x[j];
+            }
+        }
+        for i in (0..n).rev() {
+            for j in i + 1..n {
+                x[i] = x[i] - self.lu[(i, j)] * x[j];
+            }
+            x[i] = x[i] / self.lu[(i, i)];
+        }
+        Ok(x)
+    }
+}
+
+impl<T: Field> Matrix<T> {
+    // LU decomposition with partial pivoting: each column's pivot is the
+    // entry of largest magnitude at or below the diagonal, which keeps the
+    // elimination numerically stable. A pivot that is zero relative to the
+    // size of the entries in its own row marks the matrix as singular, so a
+    // large entry elsewhere can't make a small but genuine pivot look like
+    // zero.
+    pub fn lu(&self) -> Result<Lu<T>, MatrixError> {
+        self.require_square()?;
+        let n = self.rows;
+        let row_scale: Vec<T> = (0..n)
+            .map(|r| {
+                self.row(r)
+                    .iter()
+                    .map(|x| x.abs())
+                    .fold(T::ZERO, |m, x| if x > m { x } else { m })
+            })
+            .collect();
+        let epsilon = T::EPSILON * T::from_usize(n.max(1));
+
+        let mut lu = self.clone();
+        let mut permutation: Vec<usize> = (0..n).collect();
+        let mut swaps = 0;
+        let mut singular = false;
+        for k in 0..n {
+            let pivot = (k..n)
+                .reduce(|best, r| {
+                    if lu[(r, k)].abs() > lu[(best, k)].abs() {
+                        r
+                    } else {
+                        best
+                    }
+                })
+                .unwrap_or(k);
+            if lu[(pivot, k)].abs() <= epsilon * row_scale[permutation[pivot]] {
+                singular = true;
+                continue;
+            }
+            if pivot != k {
+                for c in 0..n {
+                    lu.data.swap(k * n + c, pivot * n + c);
+                }
+                permutation.swap(k, pivot);
+                swaps += 1;
+            }
+            for r in k + 1..n {
+                let factor = lu[(r, k)] / lu[(k, k)];
+                lu[(r, k)] = factor;
+                for c in k + 1..n {
+                    let value = lu[(r, c)] - factor * lu[(k, c)];
+                    lu[(r, c)] = value;
+                }
+            }
+        }
+        Ok(Lu {
+            lu,
+            permutation,
+            swaps,
+            singular,
+        })
+    }
+
+    pub fn determinant(&self) -> Result<T, MatrixError> {
+        Ok(self.lu()?.determinant())
+    }
+
+    pub fn inverse(&self) -> Result<Matrix<T>, MatrixError> {
+        let lu = self.lu()?;
+        if lu.is_singular() {
+            return Err(MatrixError::Singular);
+        }
+        let n = self.rows;
+        let mut inverse = Matrix::zeros(n, n);
+        let mut unit = vec![T::ZERO; n];
+        for c in 0..n {
+            unit[c] = T::ONE;
+            for (r, x) in lu.solve(&unit)?.into_iter().enumerate() {
+                inverse[(r, c)] = x;
+            }
+            unit[c] = T::ZERO;
+        }
+        Ok(inverse)
+    }
+
+    // The x with `self · x = b`.
+    pub fn solve(&self, b: &[T]) -> Result<Vec<T>, MatrixError> {
+        self.lu()?.solve(b)
+    }
+}
+
+impl<T> Index<(usize, usize)> for Matrix<T> {
+    type Output = T;
+
+    fn index(&self, (row, col): (usize, usize)) -> &T {
+        assert!(row < self.rows && col < self.cols, "index out of bounds");
+        &self.data[row * self.cols + col]
+    }
+}
+
+impl<T> IndexMut<(usize, usize)> for Matrix<T> {
+    fn index_mut(&mut self, (row, col): (usize, usize)) -> &mut T {
+        assert!(row < self.rows && col < self.cols, "index out of bounds");
+        &mut self.data[row * self.cols + col]
+    }
+}
+
+impl<T: Scalar> Add for &Matrix<T> {
+    type Output = Result<Matrix<T>, MatrixError>;
+
+    fn add(self, other: &Matrix<T>) -> Result<Matrix<T>, MatrixError> {
+        self.zip_with(other, "add", |a, b| a + b)
+    }
+}
+
+impl<T: Scalar> Sub for &Matrix<T> {
+    type Output = Result<Matrix<T>, MatrixError>;
+
+    fn sub(self, other: &Matrix<T>) -> Result<Matrix<T>, MatrixError> {
+        self.zip_with(other, "subtract", |a, b| a - b)
+    }
+}
+
+impl<T: Scalar> Mul for &Matrix<T> {
+    type Output = Result<Matrix<T>, MatrixError>;
+
+    fn mul(self, other: &Matrix<T>) -> Result<Matrix<T>, MatrixError> {
+        if self.cols != other.rows {
+            return Err(MatrixError::DimensionMismatch {
+                op: "multiply",
+                left: self.shape(),
+                right: other.shape(),
+            });
+        }
+        let mut product = Matrix::zeros(self.rows, other.cols);
+        for r in 0..self.rows {
+            for k in 0..self.cols {
+                let a = self[(r, k)];
+                for c in 0..other.cols {
+                    product[(r, c)] = product[(r, c)] + a * other[(k, c)];
+                }
+            }
+        }
+        Ok(product)
+    }
+}
+
+impl<T: Scalar> Add for Matrix<T> {
+    type Output = Result<Matrix<T>, MatrixError>;
+
+    fn add(self, other: Matrix<T>) -> Result<Matrix<T>, MatrixError> {
+        &self + &other
+    }
+}
+
+impl<T: Scalar> Sub for Matrix<T> {
+    type Output = Result<Matrix<T>, MatrixError>;
+
+    fn sub(self, other: Matrix<T>) -> Result<Matrix<T>, MatrixError> {
+        &self - &other
+    }
+}
+
+impl<T: Scalar> Mul for Matrix<T> {
+    type Output = Result<Matrix<T>, MatrixError>;
+
+    fn mul(self, other: Matrix<T>) -> Result<Matrix<T>, MatrixError> {
+        &self * &other
+    }
+}
+
+// Writes rows in the `( a b )` layout of the 2x2 `Matrix` in main.rs, with
+// every column right-aligned to its widest entry. A precision such as
+// `{:.2}` is applied to each entry.
+pub(crate) fn write_rows<'a, T: fmt::Display + 'a>(
+    f: &mut fmt::Formatter,
+    cols: usize,
+    rows: impl Iterator<Item = &'a [T]>,
+) -> fmt::Result {
+    let cells: Vec<Vec<String>> = rows
+        .map(|row| {
+            row.iter()
+                .map(|x| match f.precision() {
+                    Some(p) => format!("{x:.p$}"),
+                    None => x.to_string(),
+                })
+                .collect()
+        })
+        .collect();
+    let widths: Vec<usize> = (0..cols)
+        .map(|c| {
+            cells
+                .iter()
+                .map(|row| row[c].chars().count())
+                .max()
+                .unwrap_or(0)
+        })
+        .collect();
+
+    for (i, row) in cells.iter().enumerate() {
+        if i > 0 {
+            writeln!(f)?;
+        }
+        write!(f, "(")?;
+        for (cell, width) in row.iter().zip(&widths) {
+            write!(f, " {cell:>width$}")?;
+        }
+        write!(f, " )")?;
+    }
+    Ok(())
+}
+
+impl<T: fmt::Display> fmt::Display for Matrix<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.cols == 0 {
+            return (0..self.rows).try_for_each(|r| {
+                if r > 0 {
+                    writeln!(f)?;
+                }
+                write!(f, "( )")
+            });
+        }
+        write_rows(f, self.cols, self.data.chunks(self.cols))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn m(rows: &[&[f64]]) -> Matrix<f64> {
+        Matrix::from_rows(rows.iter().map(|r| r.to_vec()).collect()).unwrap()
+    }
+
+    fn assert_close(a: &Matrix<f64>, b: &Matrix<f64>) {
+        assert_eq!(a.shape(), b.shape());
+        for (x, y) in a.data.iter().zip(&b.data) {
+            assert!((x - y).abs() < 1e-9, "\n{a}\n!=\n{b}");
+        }
+    }
+
+    #[test]
+    fn construction_checks_shapes() {
+        assert_eq!(
+            Matrix::new(2, 2, vec![1, 2, 3]),
+            Err(MatrixError::WrongLength {
+                expected: 4,
+                found: 3
+            })
+        );
+        assert_eq!(
+            Matrix::from_rows(vec![vec![1, 2], vec![3]]),
+            Err(MatrixError::RaggedRows { row: 1 })
+        );
+        let id: Matrix<i32> = Matrix::identity(2);
+        assert_eq!(id, Matrix::new(2, 2, vec![1, 0, 0, 1]).unwrap());
+        assert_eq!(id.get(1, 1), Some(&1));
+        assert_eq!(id.get(2, 0), None);
+    }
+
+    #[test]
+    fn arithmetic_checks_dimensions() {
+        let a = Matrix::from_rows(vec![vec![1, 2, 3], vec![4, 5, 6]]).unwrap();
+        let b = a.transpose();
+        assert_eq!(b.shape(), (3, 2));
+        assert_eq!(
+            (&a * &b).unwrap(),
+            Matrix::from_rows(vec![vec![14, 32], vec![32, 77]]).unwrap()
+        );
+        assert_eq!(
+            (&a + &a).unwrap(),
+            Matrix::from_rows(vec![vec![2, 4, 6], vec![8, 10, 12]]).unwrap()
+        );
+        assert_eq!(
+            &a + &b,
+            Err(MatrixError::DimensionMismatch {
+                op: "add",
+                left: (2, 3),
+                right: (3, 2)
+            })
+        );
+        assert_eq!(
+            (a.clone() * a).unwrap_err().to_string(),
+            "cannot multiply a 2x3 matrix and a 2x3 matrix"
+        );
+    }
+
+    #[test]
+    fn determinant_and_inverse() {
+        let a = m(&[&[0.0, 2.0, 1.0], &[1.0, 1.0, 0.0], &[3.0, 0.0, 1.0]]);
+        // Needs a row swap straight away: the top-left entry is zero.
+        assert!((a.determinant().unwrap() - (-5.0)).abs() < 1e-12);
+        let inverse = a.inverse().unwrap();
+        assert_close(&(&a * &inverse).unwrap(), &Matrix::identity(3));
+        assert_close(&(&inverse * &a).unwrap(), &Matrix::identity(3));
+
+        let singular = m(&[&[1.0, 2.0], &[2.0, 4.0]]);
+        assert_eq!(singular.determinant(), Ok(0.0));
+        assert_eq!(singular.inverse(), Err(MatrixError::Singular));
+        // Each pivot is only compared with its own row, so very different
+        // scales don't make a small pivot look like zero.
+        let scaled = m(&[&[1e20, 0.0], &[0.0, 1.0]]);
+        assert_eq!(scaled.determinant(), Ok(1e20));
+        let inverse = scaled.inverse().unwrap();
+        assert_close(&(&scaled * &inverse).unwrap(), &Matrix::identity(2));
+        let scaled = m(&[&[1e9, 0.0], &[0.0, 1e-9]]);
+        assert_eq!(scaled.solve(&[1e9, 1e-9]), Ok(vec![1.0, 1.0]));
+        let singular = m(&[&[1e20, 1.0], &[2e20, 2.0]]);
+        assert_eq!(singular.inverse(), Err(MatrixError::Singular));
+
+        assert_eq!(
+            m(&[&[1.0, 2.0]]).determinant(),
+            Err(MatrixError::NotSquare { rows: 1, cols: 2 })
+        );
+    }
+
+    #[test]
+    fn solves_linear_systems() {
+        // 2x + y - z = 8, -3x - y + 2z = -11, -2x + y + 2z = -3
+        let a = m(&[&[2.0, 1.0, -1.0], &[-3.0, -1.0, 2.0], &[-2.0, 1.0, 2.0]]);
+        let x = a.solve(&[8.0, -11.0, -3.0]).unwrap();
+        for (got, want) in x.iter().zip([2.0, 3.0, -1.0]) {
+            assert!((got - want).abs() < 1e-12);
+        }
+        assert!(matches!(
+            a.solve(&[1.0]),
+            Err(MatrixError::DimensionMismatch { op: "solve", .. })
+        ));
+
+        // Hilbert matrices are badly conditioned; pivoting keeps the 6x6
+        // solution accurate anyway.
+        let n = 6;
+        let hilbert = Matrix::new(
+            n,
+            n,
+            (0..n * n)
+                .map(|i| 1.0 / ((i / n + i % n + 1) as f64))
+                .collect(),
+        )
+        .unwrap();
+        let ones = vec![1.0; n];
+        let b: Vec<f64> = (0..n).map(|r| hilbert.row(r).iter().sum()).collect();
+        let x = hilbert.solve(&b).unwrap();
+        for (got, want) in x.iter().zip(&ones) {
+            assert!((got - want).abs() < 1e-6, "{x:?}");
+        }
+    }
+
+    #[test]
+    fn displays_aligned_columns() {
+        let a = Matrix::new(2, 2, vec![1.1, 1.2, 2.1, 2.2]).unwrap();
+        assert_eq!(a.to_string(), "( 1.1 1.2 )\n( 2.1 2.2 )");
+        let b = Matrix::from_rows(vec![vec![1, -20, 3], vec![400, 5, 6]]).unwrap();
+        assert_eq!(b.to_string(), "(   1 -20 3 )\n( 400   5 6 )");
+        assert_eq!(format!("{:.2}", m(&[&[1.0, 0.5]])), "( 1.00 0.50 )");
+        assert_eq!(Matrix::<i32>::zeros(0, 0).to_string(), "");
+    }
+}
@@ -0,0 +1,185 @@
+use std::fmt;
+use std::ops::{Add, Index, IndexMut, Mul, Sub};
+
+use super::{Field, MatrixError, Scalar};
+
+// A matrix whose size is part of its type, so adding a 2x3 matrix to a 3x2
+// one, or multiplying shapes that don't fit, fails to compile instead of
+// returning an error.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Matrix<T, const R: usize, const C: usize> {
+    rows: [[T; C]; R],
+}
+
+impl<T: Scalar, const R: usize, const C: usize> Matrix<T, R, C> {
+    pub const fn new(rows: [[T; C]; R]) -> Matrix<T, R, C> {
+        Matrix { rows }
+    }
+
+    pub fn zeros() -> Matrix<T, R, C> {
+        Matrix::new([[T::ZERO; C]; R])
+    }
+
+    pub fn transpose(&self) -> Matrix<T, C, R> {
+        let mut t = Matrix::<T, C, R>::zeros();
+        for r in 0..R {
+            for c in 0..C {
+                t.rows[c][r] = self.rows[r][c];
+            }
+        }
+        t
+    }
+
+    pub fn to_dynamic(&self) -> super::Matrix<T> {
+        super::Matrix::new(R, C, self.rows.iter().flatten().copied().collect())
+            .expect("R * C elements")
+    }
+}
+
+impl<T: Scalar, const N: usize> Matrix<T, N, N> {
+    pub fn identity() -> Matrix<T, N, N> {
+        let mut m = Matrix::zeros();
+        for i in 0..N {
+            m.rows[i][i] = T::ONE;
+        }
+        m
+    }
+}
+
+impl<T: Field, const N: usize> Matrix<T, N, N> {
+    pub fn determinant(&self) -> T {
+        self.to_dynamic()
+            .determinant()
+            .expect("the matrix is square")
+    }
+
+    pub fn inverse(&self) -> Result<Matrix<T, N, N>, MatrixError> {
+        Matrix::try_from(&self.to_dynamic().inverse()?)
+    }
+
+    pub fn solve(&self, b: [T; N]) -> Result<[T; N], MatrixError> {
+        let x = self.to_dynamic().solve(&b)?;
+        Ok(std::array::from_fn(|i| x[i]))
+    }
+}
+
+impl<T: Scalar, const R: usize, const C: usize> TryFrom<&super::Matrix<T>> for Matrix<T, R, C> {
+    type Error = MatrixError;
+
+    fn try_from(m: &super::Matrix<T>) -> Result<Matrix<T, R, C>, MatrixError> {
+        if m.shape() != (R, C) {
+            return Err(MatrixError::DimensionMismatch {
+                op: "convert",
+                left: m.shape(),
+                right: (R, C),
+            });
+        }
+        Ok(Matrix::new(std::array::from_fn(|r| {
+            std::array::from_fn(|c| m[(r, c)])
+        })))
+    }
+}
+
+impl<T, const R: usize, const C: usize> Index<(usize, usize)> for Matrix<T, R, C> {
+    type Output = T;
+
+    fn index(&self, (row, col): (usize, usize)) -> &T {
+        &self.rows[row][col]
+    }
+}
+
+impl<T, const R: usize, const C: usize> IndexMut<(usize, usize)> for Matrix<T, R, C> {
+    fn index_mut(&mut self, (row, col): (usize, usize)) -> &mut T {
+        &mut self.rows[row][col]
+    }
+}
+
+impl<T: Scalar, const R: usize, const C: usize> Add for Matrix<T, R, C> {
+    type Output = Matrix<T, R, C>;
+
+    fn add(self, other: Matrix<T, R, C>) -> Matrix<T, R, C> {
+        Matrix::new(std::array::from_fn(|r| {
+            std::array::from_fn(|c| self.rows[r][c] + other.rows[r][c])
+        }))
+    }
+}
+
+impl<T: Scalar, const R: usize, const C: usize> Sub for Matrix<T, R, C> {
+    type Output = Matrix<T, R, C>;
+
+    fn sub(self, other: Matrix<T, R, C>) -> Matrix<T, R, C> {
+        Matrix::new(std::array::from_fn(|r| {
+            std::array::from_fn(|c| self.rows[r][c] - other.rows[r][c])
+        }))
+    }
+}
+
+impl<T: Scalar, const R: usize, const K: usize, const C: usize> Mul<Matrix<T, K, C>>
+    for Matrix<T, R, K>
+{
+    type Output = Matrix<T, R, C>;
+
+    fn mul(self, other: Matrix<T, K, C>) -> Matrix<T, R, C> {
+        Matrix::new(std::array::from_fn(|r| {
+            std::array::from_fn(|c| {
+                (0..K).fold(T::ZERO, |sum, k| sum + self.rows[r][k] * other.rows[k][c])
+            })
+        }))
+    }
+}
+
+impl<T: fmt::Display, const R: usize, const C: usize> fmt::Display for Matrix<T, R, C> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        super::write_rows(f, C, self.rows.iter().map(|row| row.as_slice()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn shapes_are_checked_by_the_type() {
+        let a = Matrix::new([[1, 2, 3], [4, 5, 6]]);
+        let product: Matrix<i32, 2, 2> = a * a.transpose();
+        assert_eq!(product, Matrix::new([[14, 32], [32, 77]]));
+        assert_eq!(a + a - a, a);
+        assert_eq!(Matrix::<i32, 2, 2>::identity() * product, product);
+        assert_eq!(product[(1, 0)], 32);
+    }
+
+    #[test]
+    fn square_matrices_use_the_lu_routines() {
+        let a = Matrix::new([[4.0, 7.0], [2.0, 6.0]]);
+        assert!((a.determinant() - 10.0).abs() < 1e-12);
+        let inverse = a.inverse().unwrap();
+        let id = a * inverse;
+        for r in 0..2 {
+            for c in 0..2 {
+                let want = if r == c { 1.0 } else { 0.0 };
+                assert!((id[(r, c)] - want).abs() < 1e-12);
+            }
+        }
+        let x = a.solve([1.0, 2.0]).unwrap();
+        assert!((x[0] + 0.8).abs() < 1e-12 && (x[1] - 0.6).abs() < 1e-12);
+        assert_eq!(
+            Matrix::new([[1.0, 2.0], [2.0, 4.0]]).inverse(),
+            Err(MatrixError::Singular)
+        );
+
+        let scaled = Matrix::new([[1e20, 0.0], [0.0, 1.0]]);
+        assert_eq!(scaled.determinant(), 1e20);
+        assert!(scaled.inverse().is_ok());
+        let scaled = Matrix::new([[1e9, 0.0], [0.0, 1e-9]]);
+        assert_eq!(scaled.solve([1e9, 1e-9]), Ok([1.0, 1.0]));
+    }
+
+    #[test]
+    fn converts_to_and_from_dynamic() {
+        let a = Matrix::new([[1, 2, 3], [4, 5, 6]]);
+        let dynamic = a.to_dynamic();
+        assert_eq!(Matrix::<i32, 2, 3>::try_from(&dynamic), Ok(a));
+        assert!(Matrix::<i32, 3, 2>::try_from(&dynamic).is_err());
+        assert_eq!(a.to_string(), dynamic.to_string());
+    }
+}
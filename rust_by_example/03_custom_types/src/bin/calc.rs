@@ -0,0 +1,63 @@
+// An integer calculator that remembers variables between lines:
+//
+//     $ cargo run --bin calc
+//     > r = 12
+//     12
+//     > 3 * r ^ 2
+//     432
+//     > ans / 0
+//     error: division by zero
+//
+// `:vars` lists the variables, `:help` the operators and functions, and
+// `:quit` (or end of input) leaves.
+use std::io::{self, BufRead, IsTerminal, Write};
+use std::process;
+
+use custom_types::calc::{Env, FUNCTIONS};
+
+fn prompt(interactive: bool) {
+    if interactive {
+        print!("> ");
+        io::stdout().flush().ok();
+    }
+}
+
+fn main() {
+    let interactive = io::stdin().is_terminal();
+    let mut env = Env::new();
+    let mut failed = false;
+
+    prompt(interactive);
+    for line in io::stdin().lock().lines() {
+        let line = line.unwrap_or_else(|e| {
+            eprintln!("failed to read stdin: {e}");
+            process::exit(1);
+        });
+        match line.trim() {
+            "" => {}
+            ":quit" | ":q" => break,
+            ":vars" => {
+                for (name, value) in env.vars() {
+                    println!("{name} = {value}");
+                }
+            }
+            ":help" => {
+                println!("operators: + - * / % ^ and parentheses; `name = expr` assigns");
+                println!("functions: {}", FUNCTIONS.join(", "));
+            }
+            input => match env.eval(input) {
+                Ok(value) => println!("{value}"),
+                Err(e) => {
+                    eprintln!("error: {e}");
+                    failed = true;
+                }
+            },
+        }
+        prompt(interactive);
+    }
+
+    // Piped input that had errors should be noticed by scripts.
+    if failed && !interactive {
+        process::exit(1);
+    }
+}
@@ -0,0 +1,623 @@
+use std::collections::BTreeMap;
+use std::fmt;
+
+// An integer calculator: `2 * (x + 3) ^ 2 % 7`, `max(a, -b, 4)`, `y = x / 2`.
+// Everything is `i64` and every step is checked, so overflow and division by
+// zero are errors rather than panics or wrapped results.
+
+// The binary operators. This began as the lesson's
+// `VeryVerboseEnumOfThingsToDoWithNumbers` with only `Add` and `Subtract`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Operation {
+    Add,
+    Subtract,
+    Multiply,
+    Divide,
+    Remainder,
+    Power,
+}
+
+impl Operation {
+    pub fn run(&self, x: i64, y: i64) -> Result<i64, CalcError> {
+        match self {
+            Self::Add => x.checked_add(y).ok_or(CalcError::Overflow),
+            Self::Subtract => x.checked_sub(y).ok_or(CalcError::Overflow),
+            Self::Multiply => x.checked_mul(y).ok_or(CalcError::Overflow),
+            Self::Divide if y == 0 => Err(CalcError::DivisionByZero),
+            Self::Divide => x.checked_div(y).ok_or(CalcError::Overflow),
+            Self::Remainder if y == 0 => Err(CalcError::DivisionByZero),
+            Self::Remainder => x.checked_rem(y).ok_or(CalcError::Overflow),
+            Self::Power if y < 0 => Err(CalcError::NegativeExponent),
+            // These bases never grow, so any exponent fits, even one too big
+            // for `checked_pow`.
+            Self::Power if x == 0 || x == 1 => Ok(if y == 0 { 1 } else { x }),
+            Self::Power if x == -1 => Ok(if y % 2 == 0 { 1 } else { -1 }),
+            Self::Power => {
+                let exp = u32::try_from(y).map_err(|_| CalcError::Overflow)?;
+                x.checked_pow(exp).ok_or(CalcError::Overflow)
+            }
+        }
+    }
+
+    fn from_symbol(c: char) -> Option<Operation> {
+        match c {
+            '+' => Some(Self::Add),
+            '-' => Some(Self::Subtract),
+            '*' => Some(Self::Multiply),
+            '/' => Some(Self::Divide),
+            '%' => Some(Self::Remainder),
+            '^' => Some(Self::Power),
+            _ => None,
+        }
+    }
+
+    fn symbol(&self) -> char {
+        match self {
+            Self::Add => '+',
+            Self::Subtract => '-',
+            Self::Multiply => '*',
+            Self::Divide => '/',
+            Self::Remainder => '%',
+            Self::Power => '^',
+        }
+    }
+
+    // Higher binds tighter. Unary minus sits between `*` and `^`, so `-2 ^ 2`
+    // is `-(2 ^ 2)` and `-2 * 3` is `(-2) * 3`.
+    fn precedence(&self) -> u8 {
+        match self {
+            Self::Add | Self::Subtract => 1,
+            Self::Multiply | Self::Divide | Self::Remainder => 2,
+            Self::Power => 4,
+        }
+    }
+
+    fn right_associative(&self) -> bool {
+        *self == Self::Power
+    }
+}
+
+const NEGATION_PRECEDENCE: u8 = 3;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CalcError {
+    // `column` counts characters from 1.
+    UnexpectedChar { column: usize, found: char },
+    UnexpectedToken { column: usize, found: String },
+    UnexpectedEnd,
+    NumberTooLarge { column: usize },
+    Overflow,
+    DivisionByZero,
+    NegativeExponent,
+    UnknownVariable(String),
+    UnknownFunction(String),
+    WrongArgumentCount { function: String, found: usize },
+    NegativeSquareRoot,
+    TooDeep,
+}
+
+impl fmt::Display for CalcError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CalcError::UnexpectedChar { column, found } => {
+                write!(f, "unexpected character '{}' at column {}", found, column)
+            }
+            CalcError::UnexpectedToken { column, found } => {
+                write!(f, "unexpected `{}` at column {}", found, column)
+            }
+            CalcError::UnexpectedEnd => write!(f, "unexpected end of expression"),
+            CalcError::NumberTooLarge { column } => {
+                write!(f, "number at column {} is too large", column)
+            }
+            CalcError::Overflow => write!(f, "arithmetic overflow"),
+            CalcError::DivisionByZero => write!(f, "division by zero"),
+            CalcError::NegativeExponent => write!(f, "negative exponent"),
+            CalcError::UnknownVariable(name) => write!(f, "unknown variable `{}`", name),
+            CalcError::UnknownFunction(name) => write!(f, "unknown function `{}`", name),
+            CalcError::WrongArgumentCount { function, found } => {
+                write!(f, "`{}` can't take {} argument(s)", function, found)
+            }
+            CalcError::NegativeSquareRoot => write!(f, "square root of a negative number"),
+            CalcError::TooDeep => write!(f, "expression is nested too deeply"),
+        }
+    }
+}
+
+impl std::error::Error for CalcError {}
+
+// Names `call` knows, for listing in help text.
+pub const FUNCTIONS: [&str; 6] = ["abs", "sign", "sqrt", "gcd", "min", "max"];
+
+fn call(function: &str, args: &[i64]) -> Result<i64, CalcError> {
+    let wrong_count = || CalcError::WrongArgumentCount {
+        function: String::from(function),
+        found: args.len(),
+    };
+    match (function, args) {
+        ("abs", [x]) => x.checked_abs().ok_or(CalcError::Overflow),
+        ("sign", [x]) => Ok(x.signum()),
+        ("sqrt", [x]) if *x < 0 => Err(CalcError::NegativeSquareRoot),
+        ("sqrt", [x]) => Ok(x.isqrt()),
+        ("gcd", [a, b]) => {
+            let (mut a, mut b) = (a.unsigned_abs(), b.unsigned_abs());
+            while b != 0 {
+                (a, b) = (b, a % b);
+            }
+            i64::try_from(a).map_err(|_| CalcError::Overflow)
+        }
+        ("min", _) => args.iter().copied().min().ok_or_else(wrong_count),
+        ("max", _) => args.iter().copied().max().ok_or_else(wrong_count),
+        _ if FUNCTIONS.contains(&function) => Err(wrong_count()),
+        _ => Err(CalcError::UnknownFunction(String::from(function))),
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Expr {
+    Number(i64),
+    Variable(String),
+    Negate(Box<Expr>),
+    Binary(Operation, Box<Expr>, Box<Expr>),
+    Call(String, Vec<Expr>),
+}
+
+impl Expr {
+    pub fn eval(&self, env: &Env) -> Result<i64, CalcError> {
+        match self {
+            Expr::Number(n) => Ok(*n),
+            Expr::Variable(name) => env
+                .get(name)
+                .ok_or_else(|| CalcError::UnknownVariable(name.clone())),
+            Expr::Negate(e) => e.eval(env)?.checked_neg().ok_or(CalcError::Overflow),
+            Expr::Binary(op, left, right) => op.run(left.eval(env)?, right.eval(env)?),
+            Expr::Call(function, args) => {
+                let args = args
+                    .iter()
+                    .map(|arg| arg.eval(env))
+                    .collect::<Result<Vec<i64>, CalcError>>()?;
+                call(function, &args)
+            }
+        }
+    }
+}
+
+// Fully parenthesised, so the structure the parser chose is visible.
+impl fmt::Display for Expr {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Expr::Number(n) => write!(f, "{}", n),
+            Expr::Variable(name) => write!(f, "{}", name),
+            Expr::Negate(e) => write!(f, "(-{})", e),
+            Expr::Binary(op, left, right) => write!(f, "({} {} {})", left, op.symbol(), right),
+            Expr::Call(function, args) => {
+                let args: Vec<String> = args.iter().map(|arg| arg.to_string()).collect();
+                write!(f, "{}({})", function, args.join(", "))
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Token {
+    // Literals are kept as text until parsing so that `-9223372036854775808`
+    // can be told apart from an overflowing positive number.
+    Number(String),
+    Ident(String),
+    Op(Operation),
+    LeftParen,
+    RightParen,
+    Comma,
+    Assign,
+}
+
+impl fmt::Display for Token {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Token::Number(digits) => write!(f, "{}", digits),
+            Token::Ident(name) => write!(f, "{}", name),
+            Token::Op(op) => write!(f, "{}", op.symbol()),
+            Token::LeftParen => write!(f, "("),
+            Token::RightParen => write!(f, ")"),
+            Token::Comma => write!(f, ","),
+            Token::Assign => write!(f, "="),
+        }
+    }
+}
+
+// Each token with the column it starts at.
+fn tokenize(input: &str) -> Result<Vec<(usize, Token)>, CalcError> {
+    let chars: Vec<char> = input.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        let column = i + 1;
+        let start = i;
+        i += 1;
+        let token = if c.is_whitespace() {
+            continue;
+        } else if c.is_ascii_digit() {
+            while i < chars.len() && (chars[i].is_ascii_digit() || chars[i] == '_') {
+                i += 1;
+            }
+            Token::Number(chars[start..i].iter().filter(|&&c| c != '_').collect())
+        } else if c.is_alphabetic() || c == '_' {
+            while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_') {
+                i += 1;
+            }
+            Token::Ident(chars[start..i].iter().collect())
+        } else if let Some(op) = Operation::from_symbol(c) {
+            Token::Op(op)
+        } else {
+            match c {
+                '(' => Token::LeftParen,
+                ')' => Token::RightParen,
+                ',' => Token::Comma,
+                '=' => Token::Assign,
+                _ => return Err(CalcError::UnexpectedChar { column, found: c }),
+            }
+        };
+        tokens.push((column, token));
+    }
+    Ok(tokens)
+}
+
+// Parsing, evaluating, printing and dropping an `Expr` all recurse over the
+// tree, so its depth is capped to keep deeply nested input from overflowing
+// the stack. Both nesting and each operator in a chain like `1 + 1 + 1` count
+// towards the limit.
+const MAX_DEPTH: usize = 256;
+
+struct Parser {
+    tokens: Vec<(usize, Token)>,
+    pos: usize,
+    depth: usize,
+}
+
+impl Parser {
+    fn new(tokens: Vec<(usize, Token)>) -> Parser {
+        Parser {
+            tokens,
+            pos: 0,
+            depth: 0,
+        }
+    }
+
+    fn enter(&mut self) -> Result<(), CalcError> {
+        self.depth += 1;
+        if self.depth > MAX_DEPTH {
+            return Err(CalcError::TooDeep);
+        }
+        Ok(())
+    }
+
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos).map(|(_, token)| token)
+    }
+
+    fn next(&mut self) -> Result<(usize, Token), CalcError> {
+        let next = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        next.ok_or(CalcError::UnexpectedEnd)
+    }
+
+    fn expect(&mut self, want: Token) -> Result<(), CalcError> {
+        let (column, token) = self.next()?;
+        if token == want {
+            Ok(())
+        } else {
+            Err(unexpected(column, &token))
+        }
+    }
+
+    fn finish(&self) -> Result<(), CalcError> {
+        match self.tokens.get(self.pos) {
+            Some((column, token)) => Err(unexpected(*column, token)),
+            None => Ok(()),
+        }
+    }
+
+    // Precedence climbing: parse one operand, then keep absorbing operators
+    // that bind at least as tightly as `min_precedence`.
+    fn expression(&mut self, min_precedence: u8) -> Result<Expr, CalcError> {
+        let depth = self.depth;
+        self.enter()?;
+        let mut left = self.unary()?;
+        while let Some(&Token::Op(op)) = self.peek() {
+            if op.precedence() < min_precedence {
+                break;
+            }
+            self.pos += 1;
+            self.enter()?;
+            let next_min = if op.right_associative() {
+                op.precedence()
+            } else {
+                op.precedence() + 1
+            };
+            let right = self.expression(next_min)?;
+            left = Expr::Binary(op, Box::new(left), Box::new(right));
+        }
+        self.depth = depth;
+        Ok(left)
+    }
+
+    fn unary(&mut self) -> Result<Expr, CalcError> {
+        if self.peek() != Some(&Token::Op(Operation::Subtract)) {
+            return self.primary();
+        }
+        self.pos += 1;
+        // `-9223372036854775808` is in range even though its digits alone
+        // are not.
+        if let Some((column, Token::Number(digits))) = self.tokens.get(self.pos).cloned() {
+            let follows_tighter = matches!(
+                self.tokens.get(self.pos + 1),
+                Some((_, Token::Op(op))) if op.precedence() > NEGATION_PRECEDENCE
+            );
+            if !follows_tighter {
+                self.pos += 1;
+                return format!("-{}", digits)
+                    .parse()
+                    .map(Expr::Number)
+                    .map_err(|_| CalcError::NumberTooLarge { column });
+            }
+        }
+        Ok(Expr::Negate(Box::new(
+            self.expression(NEGATION_PRECEDENCE)?,
+        )))
+    }
+
+    fn primary(&mut self) -> Result<Expr, CalcError> {
+        let (column, token) = self.next()?;
+        match token {
+            Token::Number(digits) => digits
+                .parse()
+                .map(Expr::Number)
+                .map_err(|_| CalcError::NumberTooLarge { column }),
+            Token::Ident(name) if self.peek() == Some(&Token::LeftParen) => {
+                self.pos += 1;
+                let mut args = Vec::new();
+                if self.peek() == Some(&Token::RightParen) {
+                    self.pos += 1;
+                    return Ok(Expr::Call(name, args));
+                }
+                loop {
+                    args.push(self.expression(1)?);
+                    let (column, token) = self.next()?;
+                    match token {
+                        Token::Comma => continue,
+                        Token::RightParen => return Ok(Expr::Call(name, args)),
+                        _ => return Err(unexpected(column, &token)),
+                    }
+                }
+            }
+            Token::Ident(name) => Ok(Expr::Variable(name)),
+            Token::LeftParen => {
+                let inner = self.expression(1)?;
+                self.expect(Token::RightParen)?;
+                Ok(inner)
+            }
+            _ => Err(unexpected(column, &token)),
+        }
+    }
+}
+
+fn unexpected(column: usize, token: &Token) -> CalcError {
+    CalcError::UnexpectedToken {
+        column,
+        found: token.to_string(),
+    }
+}
+
+pub fn parse(input: &str) -> Result<Expr, CalcError> {
+    let mut parser = Parser::new(tokenize(input)?);
+    let expr = parser.expression(1)?;
+    parser.finish()?;
+    Ok(expr)
+}
+
+// Variables that outlive a single expression. `ans` always holds the last
+// result.
+#[derive(Debug, Clone, Default)]
+pub struct Env {
+    vars: BTreeMap<String, i64>,
+}
+
+impl Env {
+    pub fn new() -> Env {
+        Env::default()
+    }
+
+    pub fn get(&self, name: &str) -> Option<i64> {
+        self.vars.get(name).copied()
+    }
+
+    pub fn set(&mut self, name: &str, value: i64) {
+        self.vars.insert(String::from(name), value);
+    }
+
+    pub fn vars(&self) -> impl Iterator<Item = (&str, i64)> {
+        self.vars
+            .iter()
+            .map(|(name, &value)| (name.as_str(), value))
+    }
+
+    // Evaluates an expression, or an assignment like `x = 2 * y`, which also
+    // evaluates to the assigned value. Nothing changes if evaluation fails.
+    pub fn eval(&mut self, line: &str) -> Result<i64, CalcError> {
+        let tokens = tokenize(line)?;
+        let (target, tokens) = match tokens.as_slice() {
+            [(_, Token::Ident(name)), (_, Token::Assign), ..] => {
+                (Some(name.clone()), tokens[2..].to_vec())
+            }
+            _ => (None, tokens),
+        };
+        let mut parser = Parser::new(tokens);
+        let expr = parser.expression(1)?;
+        parser.finish()?;
+
+        let value = expr.eval(self)?;
+        if let Some(name) = target {
+            self.set(&name, value);
+        }
+        self.set("ans", value);
+        Ok(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn eval(input: &str) -> Result<i64, CalcError> {
+        Env::new().eval(input)
+    }
+
+    #[test]
+    fn operations_are_checked() {
+        assert_eq!(Operation::Add.run(3, 4), Ok(7));
+        assert_eq!(Operation::Subtract.run(2, 5), Ok(-3));
+        assert_eq!(Operation::Add.run(i64::MAX, 1), Err(CalcError::Overflow));
+        assert_eq!(
+            Operation::Divide.run(i64::MIN, -1),
+            Err(CalcError::Overflow)
+        );
+        assert_eq!(
+            Operation::Remainder.run(1, 0),
+            Err(CalcError::DivisionByZero)
+        );
+        assert_eq!(
+            Operation::Power.run(2, -1),
+            Err(CalcError::NegativeExponent)
+        );
+        assert_eq!(Operation::Power.run(2, 62), Ok(1 << 62));
+        assert_eq!(Operation::Power.run(2, 63), Err(CalcError::Overflow));
+        assert_eq!(Operation::Power.run(0, 0), Ok(1));
+    }
+
+    #[test]
+    fn deep_nesting_is_an_error() {
+        let nested = |n| format!("{}1{}", "(".repeat(n), ")".repeat(n));
+        assert_eq!(eval(&nested(100)), Ok(1));
+        assert_eq!(eval(&nested(200_000)), Err(CalcError::TooDeep));
+        assert_eq!(eval(&vec!["1"; 100].join(" + ")), Ok(100));
+        assert_eq!(
+            eval(&vec!["1"; 200_000].join(" + ")),
+            Err(CalcError::TooDeep)
+        );
+        assert_eq!(
+            eval(&vec!["1"; 200_000].join(" ^ ")),
+            Err(CalcError::TooDeep)
+        );
+        assert_eq!(eval(&"-".repeat(200_000)), Err(CalcError::TooDeep));
+    }
+
+    #[test]
+    fn huge_exponents_of_small_bases() {
+        assert_eq!(eval("1 ^ 5000000000"), Ok(1));
+        assert_eq!(eval("0 ^ 5000000000"), Ok(0));
+        assert_eq!(eval("(-1) ^ 5000000000"), Ok(1));
+        assert_eq!(eval("(-1) ^ 5000000001"), Ok(-1));
+        assert_eq!(eval("2 ^ 5000000000"), Err(CalcError::Overflow));
+        assert_eq!(eval("1 ^ -5000000000"), Err(CalcError::NegativeExponent));
+    }
+
+    #[test]
+    fn precedence_and_associativity() {
+        let shape = |input: &str| parse(input).unwrap().to_string();
+        assert_eq!(shape("1 + 2 * 3"), "(1 + (2 * 3))");
+        assert_eq!(shape("10 - 4 - 3"), "((10 - 4) - 3)");
+        assert_eq!(shape("2 ^ 3 ^ 2"), "(2 ^ (3 ^ 2))");
+        assert_eq!(shape("-x ^ 2"), "(-(x ^ 2))");
+        assert_eq!(shape("-x * 3"), "((-x) * 3)");
+        assert_eq!(shape("2 ^ -1"), "(2 ^ -1)");
+        assert_eq!(shape("(1 + 2) % max(a, 3)"), "((1 + 2) % max(a, 3))");
+
+        assert_eq!(eval("1 + 2 * 3"), Ok(7));
+        assert_eq!(eval("10 - 4 - 3"), Ok(3));
+        assert_eq!(eval("2 ^ 3 ^ 2"), Ok(512));
+        assert_eq!(eval("-2 ^ 2"), Ok(-4));
+        assert_eq!(eval("(-2) ^ 2"), Ok(4));
+        assert_eq!(eval("--3"), Ok(3));
+        assert_eq!(eval("-7 / 2"), Ok(-3));
+        assert_eq!(eval("-7 % 3"), Ok(-1));
+        assert_eq!(eval("1_000 * (2 + 3)"), Ok(5000));
+    }
+
+    #[test]
+    fn extreme_literals() {
+        assert_eq!(eval("-9223372036854775808"), Ok(i64::MIN));
+        assert_eq!(
+            eval("9223372036854775808"),
+            Err(CalcError::NumberTooLarge { column: 1 })
+        );
+        assert_eq!(eval("-9223372036854775808 - 1"), Err(CalcError::Overflow));
+        assert_eq!(eval("abs(-9223372036854775808)"), Err(CalcError::Overflow));
+    }
+
+    #[test]
+    fn functions() {
+        assert_eq!(eval("abs(-5) + sign(-9)"), Ok(4));
+        assert_eq!(eval("sqrt(99)"), Ok(9));
+        assert_eq!(eval("gcd(-12, 18)"), Ok(6));
+        assert_eq!(eval("min(3, -1, 2) * max(4)"), Ok(-4));
+        assert_eq!(eval("sqrt(-1)"), Err(CalcError::NegativeSquareRoot));
+        assert_eq!(
+            eval("max()"),
+            Err(CalcError::WrongArgumentCount {
+                function: String::from("max"),
+                found: 0
+            })
+        );
+        assert_eq!(
+            eval("gcd(1)").unwrap_err().to_string(),
+            "`gcd` can't take 1 argument(s)"
+        );
+        assert_eq!(
+            eval("cube(2)"),
+            Err(CalcError::UnknownFunction(String::from("cube")))
+        );
+    }
+
+    #[test]
+    fn syntax_errors() {
+        assert_eq!(
+            eval("2 $ 3"),
+            Err(CalcError::UnexpectedChar {
+                column: 3,
+                found: '$'
+            })
+        );
+        assert_eq!(eval("(1 + 2"), Err(CalcError::UnexpectedEnd));
+        assert_eq!(eval("1 +"), Err(CalcError::UnexpectedEnd));
+        assert_eq!(
+            eval("1 2").unwrap_err().to_string(),
+            "unexpected `2` at column 3"
+        );
+        assert_eq!(
+            eval("max(1,)"),
+            Err(CalcError::UnexpectedToken {
+                column: 7,
+                found: String::from(")")
+            })
+        );
+        assert!(eval("x = = 1").is_err());
+        assert!(eval("1 = 2").is_err());
+    }
+
+    #[test]
+    fn variables_persist_in_the_environment() {
+        let mut env = Env::new();
+        assert_eq!(env.eval("x = 6"), Ok(6));
+        assert_eq!(env.eval("y = x * 7"), Ok(42));
+        assert_eq!(env.eval("ans - x"), Ok(36));
+        assert_eq!(
+            env.eval("z + 1"),
+            Err(CalcError::UnknownVariable(String::from("z")))
+        );
+        // A failed assignment leaves everything as it was.
+        assert_eq!(env.eval("x = x / 0"), Err(CalcError::DivisionByZero));
+        assert_eq!(env.get("x"), Some(6));
+        let vars: Vec<(&str, i64)> = env.vars().collect();
+        assert_eq!(vars, vec![("ans", 36), ("x", 6), ("y", 42)]);
+    }
+}
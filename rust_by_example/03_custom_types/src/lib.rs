@@ -0,0 +1,3 @@
+pub mod calc;
+pub mod list;
+pub mod session;
@@ -0,0 +1,273 @@
+// A persistent (immutable) singly linked list. Prepending and taking the tail
+// never copy: the new list points at the old nodes, so several lists can share
+// the same suffix. Everything that walks the list does so with a loop rather
+// than recursion, so a list of a million elements is as safe to measure,
+// print and drop as a list of three.
+//
+// The same list is generated twice: `list::List` shares nodes through `Rc`,
+// and `list::sync::List` through `Arc` so it can be sent between threads.
+macro_rules! persistent_list {
+    ($Ptr:ident) => {
+        pub struct List<T> {
+            head: Option<$Ptr<Node<T>>>,
+        }
+
+        struct Node<T> {
+            elem: T,
+            next: List<T>,
+        }
+
+        impl<T> List<T> {
+            pub fn new() -> List<T> {
+                List { head: None }
+            }
+
+            // A new list with `elem` in front of this one, which is left
+            // untouched and shared.
+            pub fn prepend(&self, elem: T) -> List<T> {
+                List {
+                    head: Some($Ptr::new(Node {
+                        elem,
+                        next: self.clone(),
+                    })),
+                }
+            }
+
+            pub fn head(&self) -> Option<&T> {
+                self.head.as_ref().map(|node| &node.elem)
+            }
+
+            // Everything after the first element, shared with this list. The
+            // tail of an empty list is empty.
+            pub fn tail(&self) -> List<T> {
+                match &self.head {
+                    Some(node) => node.next.clone(),
+                    None => List::new(),
+                }
+            }
+
+            pub fn is_empty(&self) -> bool {
+                self.head.is_none()
+            }
+
+            pub fn len(&self) -> usize {
+                self.iter().count()
+            }
+
+            pub fn iter(&self) -> Iter<'_, T> {
+                Iter {
+                    next: self.head.as_deref(),
+                }
+            }
+
+            // Whether both lists start at the same node, i.e. one is a shared
+            // copy of the other rather than just equal to it.
+            pub fn ptr_eq(&self, other: &List<T>) -> bool {
+                match (&self.head, &other.head) {
+                    (Some(a), Some(b)) => $Ptr::ptr_eq(a, b),
+                    (None, None) => true,
+                    _ => false,
+                }
+            }
+        }
+
+        impl<T: Clone> List<T> {
+            // Reversing has to build every node again; nothing can be shared.
+            pub fn reverse(&self) -> List<T> {
+                self.iter()
+                    .fold(List::new(), |reversed, elem| reversed.prepend(elem.clone()))
+            }
+
+            // This list followed by `other`. The elements of `self` are copied
+            // and `other` is shared as the tail of the result.
+            pub fn append(&self, other: &List<T>) -> List<T> {
+                let front: Vec<&T> = self.iter().collect();
+                front
+                    .into_iter()
+                    .rev()
+                    .fold(other.clone(), |list, elem| list.prepend(elem.clone()))
+            }
+        }
+
+        // Cloning a list only copies the pointer to its first node.
+        impl<T> Clone for List<T> {
+            fn clone(&self) -> List<T> {
+                List {
+                    head: self.head.clone(),
+                }
+            }
+        }
+
+        impl<T> Default for List<T> {
+            fn default() -> List<T> {
+                List::new()
+            }
+        }
+
+        // The default drop would recurse once per node. Instead, unlink nodes
+        // one at a time until reaching one that another list still uses.
+        impl<T> Drop for List<T> {
+            fn drop(&mut self) {
+                let mut next = self.head.take();
+                while let Some(node) = next {
+                    match $Ptr::try_unwrap(node) {
+                        Ok(mut node) => next = node.next.head.take(),
+                        Err(_) => break,
+                    }
+                }
+            }
+        }
+
+        impl<T> FromIterator<T> for List<T> {
+            fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> List<T> {
+                let elems: Vec<T> = iter.into_iter().collect();
+                elems
+                    .into_iter()
+                    .rev()
+                    .fold(List::new(), |list, elem| list.prepend(elem))
+            }
+        }
+
+        impl<T: PartialEq> PartialEq for List<T> {
+            fn eq(&self, other: &List<T>) -> bool {
+                self.iter().eq(other.iter())
+            }
+        }
+
+        impl<T: Eq> Eq for List<T> {}
+
+        impl<T: std::fmt::Debug> std::fmt::Debug for List<T> {
+            fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+                f.debug_list().entries(self.iter()).finish()
+            }
+        }
+
+        // Written the way `stringify` in main.rs prints a list: `3, 2, 1, Nil`.
+        impl<T: std::fmt::Display> std::fmt::Display for List<T> {
+            fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+                for elem in self.iter() {
+                    write!(f, "{}, ", elem)?;
+                }
+                write!(f, "Nil")
+            }
+        }
+
+        pub struct Iter<'a, T> {
+            next: Option<&'a Node<T>>,
+        }
+
+        impl<'a, T> Iterator for Iter<'a, T> {
+            type Item = &'a T;
+
+            fn next(&mut self) -> Option<&'a T> {
+                self.next.map(|node| {
+                    self.next = node.next.head.as_deref();
+                    &node.elem
+                })
+            }
+        }
+
+        impl<'a, T> IntoIterator for &'a List<T> {
+            type Item = &'a T;
+            type IntoIter = Iter<'a, T>;
+
+            fn into_iter(self) -> Iter<'a, T> {
+                self.iter()
+            }
+        }
+    };
+}
+
+use std::rc::Rc;
+
+persistent_list!(Rc);
+
+pub mod sync {
+    use std::sync::Arc;
+
+    persistent_list!(Arc);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LONG: usize = 1_000_000;
+
+    #[test]
+    fn prepend_and_tail_share_nodes() {
+        let base: List<u32> = List::new().prepend(1).prepend(2);
+        let a = base.prepend(3);
+        let b = base.prepend(4);
+
+        assert!(a.tail().ptr_eq(&base));
+        assert!(b.tail().ptr_eq(&base));
+        assert!(!a.ptr_eq(&b));
+        assert_eq!(a.to_string(), "3, 2, 1, Nil");
+        assert_eq!(b.to_string(), "4, 2, 1, Nil");
+        assert_eq!(base.to_string(), "2, 1, Nil");
+        assert_eq!(a.head(), Some(&3));
+        assert_eq!(List::<u32>::new().tail(), List::new());
+    }
+
+    #[test]
+    fn collects_and_iterates_in_order() {
+        let list: List<char> = "abc".chars().collect();
+        assert_eq!(list.iter().collect::<String>(), "abc");
+        assert_eq!(list.len(), 3);
+        assert!(!list.is_empty());
+        assert_eq!(format!("{:?}", list), "['a', 'b', 'c']");
+        assert_eq!(List::<char>::new().to_string(), "Nil");
+        assert!(List::<char>::default().is_empty());
+    }
+
+    #[test]
+    fn reverse_and_append() {
+        let front: List<i32> = (1..=3).collect();
+        let back: List<i32> = (4..=5).collect();
+
+        assert_eq!(front.reverse(), (1..=3).rev().collect());
+        let both = front.append(&back);
+        assert_eq!(both, (1..=5).collect());
+        // The appended list is reused as is.
+        let mut rest = both.clone();
+        for _ in 0..3 {
+            rest = rest.tail();
+        }
+        assert!(rest.ptr_eq(&back));
+        assert_eq!(front.len(), 3);
+    }
+
+    #[test]
+    fn long_lists_do_not_overflow_the_stack() {
+        let list: List<usize> = (0..LONG).collect();
+        assert_eq!(list.len(), LONG);
+        assert_eq!(list.iter().sum::<usize>(), LONG * (LONG - 1) / 2);
+        assert!(list.to_string().ends_with("999999, Nil"));
+
+        let reversed = list.reverse();
+        assert_eq!(reversed.head(), Some(&(LONG - 1)));
+        assert!(list != reversed);
+
+        // Dropping a list whose tail is still shared stops at the shared part.
+        let shared = list.tail();
+        drop(list);
+        assert_eq!(shared.len(), LONG - 1);
+    }
+
+    #[test]
+    fn sync_lists_cross_threads() {
+        let list: sync::List<u64> = (1..=100).collect();
+        let handles: Vec<_> = (0..4)
+            .map(|i| {
+                let list = list.prepend(i);
+                std::thread::spawn(move || list.iter().sum::<u64>())
+            })
+            .collect();
+        let sums: Vec<u64> = handles.into_iter().map(|h| h.join().unwrap()).collect();
+        assert_eq!(sums, vec![5050, 5051, 5052, 5053]);
+
+        let long: sync::List<usize> = (0..LONG).collect();
+        assert_eq!(long.len(), LONG);
+    }
+}
@@ -44,20 +44,12 @@ fn square(point: &Point, i: f32) -> Rectangle {
 
 }
 
-// Create an `enum` to classify a web event. Note how both
-// names and type information together specify the variant:
-// `PageLoad != PageUnload` and `KeyPress(char) != Paste(String)`.
-// Each is different and independent.
-enum WebEvent {
-    // An `enum` variant may either be `unit-like`,
-    PageLoad,
-    PageUnload,
-    // like tuple structs,
-    KeyPress(char),
-    Paste(String),
-    // or c-like structures.
-    Click { x: i64, y: i64 },
-}
+// `WebEvent` classifies a web event. Note how both names and type
+// information together specify the variant: `PageLoad != PageUnload` and
+// `KeyPress(char) != Paste(String)`. Each is different and independent.
+// It lives in the `session` module, which can also record, save and replay
+// a stream of them.
+use custom_types::session::{ManualClock, Recorder, Session, WebEvent};
 
 // A function which takes a `WebEvent` enum as an argument and
 // returns nothing.
@@ -75,23 +67,14 @@ fn inspect(event: WebEvent) {
     }
 }
 
-enum VeryVerboseEnumOfThingsToDoWithNumbers {
-    Add,
-    Subtract,
-}
+// The lesson's `VeryVerboseEnumOfThingsToDoWithNumbers` only had `Add` and
+// `Subtract`, with unchecked `i32` arithmetic. It grew into `calc::Operation`,
+// part of an expression calculator; the long name is kept here for the alias.
+use custom_types::calc::{self, Operation as VeryVerboseEnumOfThingsToDoWithNumbers};
 
 // Creates a type alias
 type Operations = VeryVerboseEnumOfThingsToDoWithNumbers;
 
-impl VeryVerboseEnumOfThingsToDoWithNumbers {
-    fn run(&self, x: i32, y: i32) -> i32 {
-        match self {
-            Self::Add => x + y,
-            Self::Subtract => x - y,
-        }
-    }
-}
-
 enum Stage {
     Beginner,
     Advanced,
@@ -120,62 +103,10 @@ enum Color {
 
 
 
-use crate::List::*;
-
-enum List {
-    // Cons: Tuple struct that wraps an element and a pointer to the next node
-    Cons(u32, Box<List>),
-    // Nil: A node that signifies the end of the linked list
-    Nil,
-}
-
-
-impl List {
-    // Create an empty list
-    fn new() -> List {
-        // `Nil` has type `List`
-        Nil
-    }
-
-    // Consume a list, and return the same list with a new element at its front
-    fn prepend(self, elem: u32) -> List {
-        // `Cons` also has type List
-        Cons(elem, Box::new(self))
-    }
-
-    // Return the length of the list
-    fn len(&self) -> u32 {
-        // `self` has to be matched, because the behavior of this method
-        // depends on the variant of `self`
-        // `self` has type `&List`, and `*self` has type `List`, matching on a
-        // concrete type `T` is preferred over a match on a reference `&T`
-        // after Rust 2018 you can use self here and tail (with no ref) below as well,
-        // rust will infer &s and ref tail.
-        // See https://doc.rust-lang.org/edition-guide/rust-2018/ownership-and-lifetimes/default-match-bindings.html
-        match *self {
-            // Can't take ownership of the tail, because `self` is borrowed;
-            // instead take a reference to the tail
-            // And it'a a non-tail recursive call which may cause stack overflow for long lists.
-            Cons(_, ref tail) => 1 + tail.len(),
-            // Base Case: An empty list has zero length
-            Nil => 0
-        }
-    }
-
-    // Return representation of the list as a (heap allocated) string
-    fn stringify(&self) -> String {
-        match *self {
-            Cons(head, ref tail) => {
-                // `format!` is similar to `print!`, but returns a heap
-                // allocated string instead of printing to the console
-                format!("{}, {}", head, tail.stringify())
-            },
-            Nil => {
-                format!("Nil")
-            },
-        }
-    }
-}
+// The lesson's `List { Cons(u32, Box<List>), Nil }` recursed in `len` and
+// `stringify`, so a long enough list would overflow the stack. It grew into
+// the generic, persistent `list` module.
+use custom_types::list::List;
 
 
 // Globals are declared outside all other scopes.
@@ -258,14 +189,54 @@ fn main() {
     inspect(load);
     inspect(unload);
 
+    // Record a session, with a clock we move by hand so the times are fixed.
+    let clock = ManualClock::new();
+    let mut recorder = Recorder::new(&clock);
+    recorder.record(WebEvent::PageLoad);
+    for c in "hi!".chars() {
+        clock.advance(150);
+        recorder.record(WebEvent::KeyPress(c));
+    }
+    clock.advance(900);
+    recorder.record(WebEvent::Paste("my text".to_owned()));
+    clock.advance(400);
+    recorder.record(WebEvent::Click { x: 20, y: 80 });
+    recorder.record(WebEvent::PageUnload);
+    let session = recorder.finish();
+
+    print!("recorded session:\n{}", session);
+    println!("as JSON: {}", session.to_json());
+    let reread: Session = session.to_string().parse().unwrap();
+    println!("reads back the same: {}", reread == session);
+    println!("clicks per 100px region: {:?}", session.clicks_per_region(100));
+    if let Some(speed) = session.typing_speed() {
+        println!("typing speed: {:.0} keys per minute", speed);
+    }
+    println!("pastes: {}", session.paste_count());
+    println!("replaying:");
+    session.replay(&mut |at_ms, event: &WebEvent| {
+        print!("  {:>5}ms ", at_ms);
+        inspect(event.clone());
+    });
+
 
     // We can refer to each variant via its alias, not its long and inconvenient
     // name.
     let x = Operations::Add;
-    println!("Add operation x.run(3, 4): {}", x.run(3, 4));
+    println!("Add operation x.run(3, 4): {:?}", x.run(3, 4));
 
     let y = Operations::Subtract;
-    println!("Subtract operation y.run(2, 5): {}", y.run(2, 5));
+    println!("Subtract operation y.run(2, 5): {:?}", y.run(2, 5));
+    println!("Overflow is caught: {:?}", x.run(i64::MAX, 1));
+
+    // The full calculator, with variables kept in an environment.
+    let mut env = calc::Env::new();
+    for line in ["r = 12", "3 * r ^ 2 - -4", "max(r, ans % 7, sqrt(200))", "r / (r - 12)", "r + s"] {
+        match env.eval(line) {
+            Ok(value) => println!("{} => {}", line, value),
+            Err(e) => println!("{} => error: {}", line, e),
+        }
+    }
 
 
 
@@ -326,7 +297,16 @@ fn main() {
 
     // Show the final state of the list
     println!("linked list has length: {}", list.len());
-    println!("{}", list.stringify());
+    println!("{}", list);
+
+    // Prepending leaves the old list intact, so both share its nodes.
+    let other = list.tail().prepend(4);
+    println!("{} and {} share {}", list, other, list.tail());
+    println!("reversed: {}", list.reverse());
+    println!("appended: {}", list.append(&other));
+
+    let long: List<u32> = (0..1_000_000).collect();
+    println!("a long list has length: {}", long.len());
 
 
 
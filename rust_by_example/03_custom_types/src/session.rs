@@ -0,0 +1,522 @@
+use std::cell::Cell;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::str::FromStr;
+use std::time::Instant;
+
+// Records a stream of `WebEvent`s with the time each one happened, writes the
+// recording out as text or JSON, reads the text back, and answers a few
+// questions about it.
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum WebEvent {
+    PageLoad,
+    PageUnload,
+    KeyPress(char),
+    Paste(String),
+    Click { x: i64, y: i64 },
+}
+
+// Where the recorder gets the time from, in milliseconds. Tests use a
+// `ManualClock` so timings are exact.
+pub trait Clock {
+    fn now_ms(&self) -> u64;
+}
+
+impl<C: Clock + ?Sized> Clock for &C {
+    fn now_ms(&self) -> u64 {
+        (**self).now_ms()
+    }
+}
+
+// Milliseconds since the clock was created.
+#[derive(Debug, Clone, Copy)]
+pub struct SystemClock {
+    start: Instant,
+}
+
+impl SystemClock {
+    pub fn new() -> SystemClock {
+        SystemClock {
+            start: Instant::now(),
+        }
+    }
+}
+
+impl Default for SystemClock {
+    fn default() -> SystemClock {
+        SystemClock::new()
+    }
+}
+
+impl Clock for SystemClock {
+    fn now_ms(&self) -> u64 {
+        self.start.elapsed().as_millis() as u64
+    }
+}
+
+// A clock that only moves when told to.
+#[derive(Debug, Default)]
+pub struct ManualClock {
+    now: Cell<u64>,
+}
+
+impl ManualClock {
+    pub fn new() -> ManualClock {
+        ManualClock::default()
+    }
+
+    pub fn advance(&self, ms: u64) {
+        self.now.set(self.now.get() + ms);
+    }
+}
+
+impl Clock for ManualClock {
+    fn now_ms(&self) -> u64 {
+        self.now.get()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TimedEvent {
+    // Milliseconds since recording started.
+    pub at_ms: u64,
+    pub event: WebEvent,
+}
+
+// One line of the text format: the time, the kind of event and its data, e.g.
+// `1500 click 20 80` or `1720 paste my text`. Backslashes and line breaks in
+// keys and pasted text are escaped so every event stays on one line.
+impl fmt::Display for TimedEvent {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} ", self.at_ms)?;
+        match &self.event {
+            WebEvent::PageLoad => write!(f, "load"),
+            WebEvent::PageUnload => write!(f, "unload"),
+            WebEvent::KeyPress(c) => write!(f, "key {}", escape(&c.to_string())),
+            WebEvent::Paste(text) => write!(f, "paste {}", escape(text)),
+            WebEvent::Click { x, y } => write!(f, "click {} {}", x, y),
+        }
+    }
+}
+
+pub struct Recorder<C: Clock> {
+    clock: C,
+    start: u64,
+    events: Vec<TimedEvent>,
+}
+
+impl<C: Clock> Recorder<C> {
+    pub fn new(clock: C) -> Recorder<C> {
+        let start = clock.now_ms();
+        Recorder {
+            clock,
+            start,
+            events: Vec::new(),
+        }
+    }
+
+    pub fn record(&mut self, event: WebEvent) {
+        // A clock that goes backwards must not make the recording unreadable.
+        let last = self.events.last().map_or(0, |e| e.at_ms);
+        let at_ms = self.clock.now_ms().saturating_sub(self.start).max(last);
+        self.events.push(TimedEvent { at_ms, event });
+    }
+
+    pub fn finish(self) -> Session {
+        Session {
+            events: self.events,
+        }
+    }
+}
+
+// Receives events during `Session::replay`, in recorded order.
+pub trait EventHandler {
+    fn handle(&mut self, at_ms: u64, event: &WebEvent);
+}
+
+impl<F: FnMut(u64, &WebEvent)> EventHandler for F {
+    fn handle(&mut self, at_ms: u64, event: &WebEvent) {
+        self(at_ms, event)
+    }
+}
+
+// Keypresses further apart than this are separate bursts of typing; the pause
+// between them doesn't count towards typing speed.
+pub const TYPING_PAUSE_MS: u64 = 2000;
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Session {
+    events: Vec<TimedEvent>,
+}
+
+impl Session {
+    pub fn events(&self) -> &[TimedEvent] {
+        &self.events
+    }
+
+    pub fn duration_ms(&self) -> u64 {
+        self.events.last().map_or(0, |e| e.at_ms)
+    }
+
+    pub fn replay<H: EventHandler>(&self, handler: &mut H) {
+        for e in &self.events {
+            handler.handle(e.at_ms, &e.event);
+        }
+    }
+
+    // Clicks counted per square of `cell` by `cell` pixels, keyed by the
+    // square's (column, row). Negative coordinates land in negative squares.
+    // Panics if `cell` is zero.
+    pub fn clicks_per_region(&self, cell: u32) -> BTreeMap<(i64, i64), usize> {
+        assert!(cell > 0, "region size must be positive");
+        let cell = i64::from(cell);
+        let mut regions = BTreeMap::new();
+        for e in &self.events {
+            if let WebEvent::Click { x, y } = e.event {
+                *regions
+                    .entry((x.div_euclid(cell), y.div_euclid(cell)))
+                    .or_insert(0) += 1;
+            }
+        }
+        regions
+    }
+
+    // Keys per minute while typing, or `None` if there were never two
+    // keypresses within `TYPING_PAUSE_MS` of each other.
+    pub fn typing_speed(&self) -> Option<f64> {
+        let mut keys = 0;
+        let mut typing_ms = 0;
+        let mut last = None;
+        for e in &self.events {
+            if let WebEvent::KeyPress(_) = e.event {
+                if let Some(last) = last {
+                    let gap = e.at_ms - last;
+                    if gap <= TYPING_PAUSE_MS {
+                        keys += 1;
+                        typing_ms += gap;
+                    }
+                }
+                last = Some(e.at_ms);
+            }
+        }
+        if typing_ms == 0 {
+            return None;
+        }
+        Some(keys as f64 * 60_000.0 / typing_ms as f64)
+    }
+
+    pub fn paste_count(&self) -> usize {
+        self.events
+            .iter()
+            .filter(|e| matches!(e.event, WebEvent::Paste(_)))
+            .count()
+    }
+
+    pub fn to_json(&self) -> String {
+        let events: Vec<String> = self
+            .events
+            .iter()
+            .map(|e| {
+                let fields = match &e.event {
+                    WebEvent::PageLoad => String::from(r#""type":"load""#),
+                    WebEvent::PageUnload => String::from(r#""type":"unload""#),
+                    WebEvent::KeyPress(c) => {
+                        format!(r#""type":"key","key":{}"#, json_string(&c.to_string()))
+                    }
+                    WebEvent::Paste(text) => {
+                        format!(r#""type":"paste","text":{}"#, json_string(text))
+                    }
+                    WebEvent::Click { x, y } => format!(r#""type":"click","x":{},"y":{}"#, x, y),
+                };
+                format!(r#"{{"at_ms":{},{}}}"#, e.at_ms, fields)
+            })
+            .collect();
+        format!("[{}]", events.join(","))
+    }
+}
+
+impl fmt::Display for Session {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for e in &self.events {
+            writeln!(f, "{}", e)?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseSessionError {
+    InvalidTime { line: usize },
+    // Times must never decrease from one line to the next.
+    OutOfOrder { line: usize },
+    UnknownEvent { line: usize, name: String },
+    InvalidData { line: usize, event: &'static str },
+}
+
+impl fmt::Display for ParseSessionError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseSessionError::InvalidTime { line } => {
+                write!(f, "line {}: expected a time in milliseconds", line)
+            }
+            ParseSessionError::OutOfOrder { line } => {
+                write!(f, "line {}: time is earlier than the line before", line)
+            }
+            ParseSessionError::UnknownEvent { line, name } => {
+                write!(f, "line {}: unknown event \"{}\"", line, name)
+            }
+            ParseSessionError::InvalidData { line, event } => {
+                write!(f, "line {}: invalid data for a {} event", line, event)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ParseSessionError {}
+
+// Reads the text written by `Display`. Blank lines are skipped.
+impl FromStr for Session {
+    type Err = ParseSessionError;
+
+    fn from_str(text: &str) -> Result<Session, ParseSessionError> {
+        let mut events: Vec<TimedEvent> = Vec::new();
+        for (i, line) in text.lines().enumerate() {
+            let line_no = i + 1;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let event = parse_line(line_no, line)?;
+            if events.last().is_some_and(|last| last.at_ms > event.at_ms) {
+                return Err(ParseSessionError::OutOfOrder { line: line_no });
+            }
+            events.push(event);
+        }
+        Ok(Session { events })
+    }
+}
+
+fn parse_line(line_no: usize, line: &str) -> Result<TimedEvent, ParseSessionError> {
+    let (time, rest) = line.split_once(' ').unwrap_or((line, ""));
+    let at_ms = time
+        .parse()
+        .map_err(|_| ParseSessionError::InvalidTime { line: line_no })?;
+    // The data may itself contain spaces, so only the name is split off.
+    let (name, data) = rest.split_once(' ').unwrap_or((rest, ""));
+    let invalid = |event| ParseSessionError::InvalidData {
+        line: line_no,
+        event,
+    };
+
+    let event = match name {
+        "load" if !data.is_empty() => return Err(invalid("load")),
+        "unload" if !data.is_empty() => return Err(invalid("unload")),
+        "load" => WebEvent::PageLoad,
+        "unload" => WebEvent::PageUnload,
+        "key" => {
+            let key = unescape(data).ok_or_else(|| invalid("key"))?;
+            let mut chars = key.chars();
+            match (chars.next(), chars.next()) {
+                (Some(c), None) => WebEvent::KeyPress(c),
+                _ => return Err(invalid("key")),
+            }
+        }
+        "paste" => WebEvent::Paste(unescape(data).ok_or_else(|| invalid("paste"))?),
+        "click" => {
+            let coords: Vec<&str> = data.split(' ').collect();
+            match coords.as_slice() {
+                [x, y] => WebEvent::Click {
+                    x: x.parse().map_err(|_| invalid("click"))?,
+                    y: y.parse().map_err(|_| invalid("click"))?,
+                },
+                _ => return Err(invalid("click")),
+            }
+        }
+        _ => {
+            return Err(ParseSessionError::UnknownEvent {
+                line: line_no,
+                name: String::from(name),
+            });
+        }
+    };
+    Ok(TimedEvent { at_ms, event })
+}
+
+fn escape(text: &str) -> String {
+    let mut out = String::new();
+    for c in text.chars() {
+        match c {
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+fn unescape(text: &str) -> Option<String> {
+    let mut out = String::new();
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+        match chars.next()? {
+            '\\' => out.push('\\'),
+            'n' => out.push('\n'),
+            'r' => out.push('\r'),
+            _ => return None,
+        }
+    }
+    Some(out)
+}
+
+fn json_string(text: &str) -> String {
+    let mut out = String::from("\"");
+    for c in text.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> Session {
+        let clock = ManualClock::new();
+        clock.advance(5000);
+        let mut recorder = Recorder::new(&clock);
+        let steps = [
+            (0, WebEvent::PageLoad),
+            (1000, WebEvent::KeyPress('h')),
+            (200, WebEvent::KeyPress('i')),
+            (300, WebEvent::KeyPress(' ')),
+            (5000, WebEvent::KeyPress('\\')),
+            (100, WebEvent::Paste(String::from("two\nlines"))),
+            (400, WebEvent::Click { x: 20, y: 80 }),
+            (10, WebEvent::Click { x: 99, y: 5 }),
+            (10, WebEvent::Click { x: -1, y: 150 }),
+            (20, WebEvent::Paste(String::new())),
+            (0, WebEvent::PageUnload),
+        ];
+        for (delay, event) in steps {
+            clock.advance(delay);
+            recorder.record(event);
+        }
+        recorder.finish()
+    }
+
+    #[test]
+    fn records_times_from_the_clock() {
+        let session = sample();
+        let times: Vec<u64> = session.events().iter().map(|e| e.at_ms).collect();
+        assert_eq!(
+            times,
+            vec![
+                0, 1000, 1200, 1500, 6500, 6600, 7000, 7010, 7020, 7040, 7040
+            ]
+        );
+        assert_eq!(session.duration_ms(), 7040);
+    }
+
+    #[test]
+    fn line_format_round_trips() {
+        let session = sample();
+        let text = session.to_string();
+        assert!(text.starts_with("0 load\n1000 key h\n1200 key i\n1500 key  \n"));
+        assert!(text.contains("6500 key \\\\\n6600 paste two\\nlines\n7000 click 20 80\n"));
+        assert!(text.ends_with("7040 paste \n7040 unload\n"));
+        assert_eq!(text.parse::<Session>(), Ok(session));
+    }
+
+    #[test]
+    fn rejects_malformed_lines() {
+        let parse = |text: &str| text.parse::<Session>().unwrap_err();
+        assert_eq!(parse("x load"), ParseSessionError::InvalidTime { line: 1 });
+        assert_eq!(
+            parse("10 load\n\n5 unload"),
+            ParseSessionError::OutOfOrder { line: 3 }
+        );
+        assert_eq!(
+            parse("1 scroll 4"),
+            ParseSessionError::UnknownEvent {
+                line: 1,
+                name: String::from("scroll")
+            }
+        );
+        assert_eq!(
+            parse("1 key ab"),
+            ParseSessionError::InvalidData {
+                line: 1,
+                event: "key"
+            }
+        );
+        assert_eq!(
+            parse("1 click 3"),
+            ParseSessionError::InvalidData {
+                line: 1,
+                event: "click"
+            }
+        );
+        assert_eq!(
+            parse("1 paste bad\\q").to_string(),
+            "line 1: invalid data for a paste event"
+        );
+    }
+
+    #[test]
+    fn serializes_to_json() {
+        let mut session = sample();
+        session.events.truncate(7);
+        assert_eq!(
+            session.to_json(),
+            concat!(
+                r#"[{"at_ms":0,"type":"load"},"#,
+                r#"{"at_ms":1000,"type":"key","key":"h"},"#,
+                r#"{"at_ms":1200,"type":"key","key":"i"},"#,
+                r#"{"at_ms":1500,"type":"key","key":" "},"#,
+                r#"{"at_ms":6500,"type":"key","key":"\\"},"#,
+                r#"{"at_ms":6600,"type":"paste","text":"two\nlines"},"#,
+                r#"{"at_ms":7000,"type":"click","x":20,"y":80}]"#,
+            )
+        );
+        assert_eq!(Session::default().to_json(), "[]");
+    }
+
+    #[test]
+    fn analytics() {
+        let session = sample();
+        let regions: Vec<((i64, i64), usize)> =
+            session.clicks_per_region(100).into_iter().collect();
+        assert_eq!(regions, vec![((-1, 1), 1), ((0, 0), 2)]);
+        // Two keys in 500ms of typing; the five second pause is left out.
+        assert_eq!(session.typing_speed(), Some(240.0));
+        assert_eq!(session.paste_count(), 2);
+        assert_eq!(Session::default().typing_speed(), None);
+    }
+
+    #[test]
+    fn replays_in_order() {
+        let session = sample();
+        let mut seen = Vec::new();
+        session.replay(&mut |at_ms, event: &WebEvent| seen.push((at_ms, event.clone())));
+        let recorded: Vec<(u64, WebEvent)> = session
+            .events()
+            .iter()
+            .map(|e| (e.at_ms, e.event.clone()))
+            .collect();
+        assert_eq!(seen, recorded);
+    }
+}
@@ -0,0 +1,91 @@
+// Converts temperatures read from stdin, one per line:
+//
+//     $ printf '%s\n' '-40°F' '300K to F' | cargo run --bin temperature
+//     -40°C = -40°F = 233.15K = 419.67°R
+//     80.33°F
+//
+// `temperature F` converts every line to Fahrenheit, and `--precision 2`
+// shows exactly two decimal places.
+use std::io::{self, BufRead};
+use std::process;
+
+use flow_of_control::temperature::{Temperature, TemperatureError, UNITS, Unit};
+
+struct Options {
+    target: Option<Unit>,
+    precision: Option<usize>,
+}
+
+fn parse_args() -> Result<Options, String> {
+    let mut options = Options {
+        target: None,
+        precision: None,
+    };
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        if arg == "--precision" || arg == "-p" {
+            let value = args.next().ok_or("--precision needs a number")?;
+            let precision = value
+                .parse()
+                .map_err(|_| format!("`{value}` is not a precision"))?;
+            options.precision = Some(precision);
+        } else {
+            options.target = Some(arg.parse().map_err(|e: TemperatureError| e.to_string())?);
+        }
+    }
+    Ok(options)
+}
+
+fn show(temperature: Temperature, precision: Option<usize>) -> String {
+    match precision {
+        Some(p) => format!("{temperature:.p$}"),
+        None => temperature.to_string(),
+    }
+}
+
+fn convert(line: &str, options: &Options) -> Result<String, TemperatureError> {
+    let (value, target) = match line.split_once(" to ") {
+        Some((value, unit)) => (value, Some(unit.parse()?)),
+        None => (line, options.target),
+    };
+    let temperature: Temperature = value.parse()?;
+    Ok(match target {
+        Some(unit) => show(temperature.to(unit), options.precision),
+        None => {
+            let all: Vec<String> = UNITS
+                .iter()
+                .map(|&unit| show(temperature.to(unit), options.precision))
+                .collect();
+            all.join(" = ")
+        }
+    })
+}
+
+fn main() {
+    let options = parse_args().unwrap_or_else(|message| {
+        eprintln!("{message}");
+        eprintln!("usage: temperature [C|F|K|R] [--precision N] < values");
+        process::exit(2);
+    });
+
+    let mut failed = false;
+    for (number, line) in io::stdin().lock().lines().enumerate() {
+        let line = line.unwrap_or_else(|e| {
+            eprintln!("failed to read stdin: {e}");
+            process::exit(1);
+        });
+        if line.trim().is_empty() {
+            continue;
+        }
+        match convert(&line, &options) {
+            Ok(output) => println!("{output}"),
+            Err(e) => {
+                eprintln!("line {}: {e}", number + 1);
+                failed = true;
+            }
+        }
+    }
+    if failed {
+        process::exit(1);
+    }
+}
@@ -493,6 +493,13 @@ fn main() {
         Temperature::Fahrenheit(t) => println!("{}F is equal to or below 86 Fahrenheit", t),
     }
 
+    // The same check with the full temperature type, which compares across units.
+    let threshold: flow_of_control::temperature::Temperature = "30 C".parse().unwrap();
+    let reading: flow_of_control::temperature::Temperature = "87 F".parse().unwrap();
+    if reading > threshold {
+        println!("{reading} is above {threshold} ({:.1})", reading.to(threshold.unit()));
+    }
+
 
 
 
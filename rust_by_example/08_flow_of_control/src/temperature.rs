@@ -0,0 +1,428 @@
+use std::cmp::Ordering;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::str::FromStr;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Unit {
+    Celsius,
+    Fahrenheit,
+    Kelvin,
+    Rankine,
+}
+
+pub const UNITS: [Unit; 4] = [Unit::Celsius, Unit::Fahrenheit, Unit::Kelvin, Unit::Rankine];
+
+impl Unit {
+    // Ticks per thousandth of a degree, and where the scale's zero sits in
+    // thousandths of a degree above absolute zero.
+    fn scale(self) -> i64 {
+        match self {
+            Unit::Celsius | Unit::Kelvin => 9,
+            Unit::Fahrenheit | Unit::Rankine => 5,
+        }
+    }
+
+    fn offset(self) -> i64 {
+        match self {
+            Unit::Celsius => 273_150,
+            Unit::Fahrenheit => 459_670,
+            Unit::Kelvin | Unit::Rankine => 0,
+        }
+    }
+
+    pub fn symbol(self) -> &'static str {
+        match self {
+            Unit::Celsius => "°C",
+            Unit::Fahrenheit => "°F",
+            Unit::Kelvin => "K",
+            Unit::Rankine => "°R",
+        }
+    }
+}
+
+impl FromStr for Unit {
+    type Err = TemperatureError;
+
+    fn from_str(s: &str) -> Result<Unit, TemperatureError> {
+        let name = s.trim();
+        let name = name.strip_prefix('°').unwrap_or(name).trim_start();
+        match name.to_ascii_lowercase().as_str() {
+            "c" | "celsius" => Ok(Unit::Celsius),
+            "f" | "fahrenheit" => Ok(Unit::Fahrenheit),
+            "k" | "kelvin" => Ok(Unit::Kelvin),
+            "r" | "ra" | "rankine" => Ok(Unit::Rankine),
+            "" => Err(TemperatureError::MissingUnit),
+            _ => Err(TemperatureError::UnknownUnit(String::from(s.trim()))),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum TemperatureError {
+    BelowAbsoluteZero { value: f64, unit: Unit },
+    InvalidNumber(String),
+    TooPrecise(String),
+    MissingUnit,
+    UnknownUnit(String),
+    OutOfRange,
+}
+
+impl fmt::Display for TemperatureError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            TemperatureError::BelowAbsoluteZero { value, unit } => {
+                write!(f, "{value}{} is below absolute zero", unit.symbol())
+            }
+            TemperatureError::InvalidNumber(s) => write!(f, "`{s}` is not a number"),
+            TemperatureError::TooPrecise(s) => {
+                write!(f, "`{s}` has more than three decimal places")
+            }
+            TemperatureError::MissingUnit => write!(f, "missing unit, expected C, F, K or R"),
+            TemperatureError::UnknownUnit(s) => {
+                write!(f, "unknown unit `{s}`, expected C, F, K or R")
+            }
+            TemperatureError::OutOfRange => write!(f, "temperature is too large"),
+        }
+    }
+}
+
+impl std::error::Error for TemperatureError {}
+
+// A temperature stored exactly, as a whole number of 1/9000 kelvin above
+// absolute zero. At that resolution every value given to a thousandth of a
+// degree in any of the four units is represented without error, so
+// converting between units and back never drifts, and `-40°C == -40°F`
+// holds exactly. The unit only affects how the temperature is shown.
+#[derive(Debug, Clone, Copy)]
+pub struct Temperature {
+    ticks: i64,
+    unit: Unit,
+}
+
+impl Temperature {
+    // Exact: `millidegrees` is the temperature in thousandths of a degree.
+    pub fn from_millidegrees(
+        millidegrees: i64,
+        unit: Unit,
+    ) -> Result<Temperature, TemperatureError> {
+        let ticks = millidegrees
+            .checked_add(unit.offset())
+            .and_then(|m| m.checked_mul(unit.scale()))
+            .ok_or(TemperatureError::OutOfRange)?;
+        if ticks < 0 {
+            return Err(TemperatureError::BelowAbsoluteZero {
+                value: millidegrees as f64 / 1000.0,
+                unit,
+            });
+        }
+        Ok(Temperature { ticks, unit })
+    }
+
+    // Rounds `value` to the nearest 1/9000 kelvin, which keeps any input
+    // with three or fewer decimal places exact.
+    pub fn new(value: f64, unit: Unit) -> Result<Temperature, TemperatureError> {
+        if !value.is_finite() {
+            return Err(TemperatureError::OutOfRange);
+        }
+        let ticks = ((value * 1000.0 + unit.offset() as f64) * unit.scale() as f64).round();
+        if ticks < 0.0 {
+            return Err(TemperatureError::BelowAbsoluteZero { value, unit });
+        }
+        if ticks >= i64::MAX as f64 {
+            return Err(TemperatureError::OutOfRange);
+        }
+        Ok(Temperature {
+            ticks: ticks as i64,
+            unit,
+        })
+    }
+
+    pub fn celsius(value: f64) -> Result<Temperature, TemperatureError> {
+        Temperature::new(value, Unit::Celsius)
+    }
+
+    pub fn fahrenheit(value: f64) -> Result<Temperature, TemperatureError> {
+        Temperature::new(value, Unit::Fahrenheit)
+    }
+
+    pub fn kelvin(value: f64) -> Result<Temperature, TemperatureError> {
+        Temperature::new(value, Unit::Kelvin)
+    }
+
+    pub fn rankine(value: f64) -> Result<Temperature, TemperatureError> {
+        Temperature::new(value, Unit::Rankine)
+    }
+
+    pub fn unit(&self) -> Unit {
+        self.unit
+    }
+
+    // The same temperature, shown in `unit`. This never loses precision.
+    pub fn to(self, unit: Unit) -> Temperature {
+        Temperature { unit, ..self }
+    }
+
+    pub fn value(&self) -> f64 {
+        self.value_in(self.unit)
+    }
+
+    pub fn value_in(&self, unit: Unit) -> f64 {
+        let (numerator, denominator) = self.ratio_in(unit);
+        numerator as f64 / denominator as f64
+    }
+
+    // The temperature in `unit` as numerator / denominator, exactly.
+    fn ratio_in(&self, unit: Unit) -> (i128, i128) {
+        let numerator = i128::from(self.ticks) - i128::from(unit.offset() * unit.scale());
+        (numerator, i128::from(unit.scale()) * 1000)
+    }
+
+    // The value in its unit times 10^decimals, rounded half away from zero.
+    fn scaled(&self, decimals: u32) -> i128 {
+        let (numerator, denominator) = self.ratio_in(self.unit);
+        let numerator = numerator * 10i128.pow(decimals);
+        let rounded = (numerator.abs() * 2 + denominator) / (denominator * 2);
+        rounded * numerator.signum()
+    }
+
+    // Rounds to `decimals` places in the current unit, such as 1 for 37.0°C.
+    // Finer than three places is below the stored resolution, so it leaves
+    // the temperature as it is.
+    pub fn rounded(self, decimals: u32) -> Temperature {
+        if decimals > 3 {
+            return self;
+        }
+        let millidegrees = self.scaled(decimals) * 10i128.pow(3 - decimals);
+        // Rounding never moves a value far enough to leave the i64 range,
+        // but it can nudge a value just above absolute zero below it.
+        Temperature::from_millidegrees(millidegrees as i64, self.unit).unwrap_or(Temperature {
+            ticks: 0,
+            unit: self.unit,
+        })
+    }
+
+    pub fn is_absolute_zero(&self) -> bool {
+        self.ticks == 0
+    }
+}
+
+impl PartialEq for Temperature {
+    fn eq(&self, other: &Temperature) -> bool {
+        self.ticks == other.ticks
+    }
+}
+
+impl Eq for Temperature {}
+
+impl PartialOrd for Temperature {
+    fn partial_cmp(&self, other: &Temperature) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Temperature {
+    fn cmp(&self, other: &Temperature) -> Ordering {
+        self.ticks.cmp(&other.ticks)
+    }
+}
+
+impl Hash for Temperature {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.ticks.hash(state);
+    }
+}
+
+// Without a precision, shows up to three decimal places with trailing zeros
+// dropped: `-40°F`, `21.5°C`, `-17.222°C`. `{:.1}` shows exactly one.
+impl fmt::Display for Temperature {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let decimals = f.precision().map_or(3, |p| p.min(18) as u32);
+        let scaled = self.scaled(decimals);
+        let digits = scaled.unsigned_abs().to_string();
+        let digits = format!("{digits:0>width$}", width = decimals as usize + 1);
+        let (whole, fraction) = digits.split_at(digits.len() - decimals as usize);
+        let fraction = if f.precision().is_some() {
+            fraction
+        } else {
+            fraction.trim_end_matches('0')
+        };
+
+        let sign = if scaled < 0 { "-" } else { "" };
+        let text = if fraction.is_empty() {
+            format!("{sign}{whole}{}", self.unit.symbol())
+        } else {
+            format!("{sign}{whole}.{fraction}{}", self.unit.symbol())
+        };
+        // `Formatter::pad` would treat the precision as a maximum length, so
+        // the width and alignment are applied by hand.
+        let padding = f.width().unwrap_or(0).saturating_sub(text.chars().count());
+        let (before, after) = match f.align() {
+            Some(fmt::Alignment::Left) => (0, padding),
+            Some(fmt::Alignment::Center) => (padding / 2, padding - padding / 2),
+            Some(fmt::Alignment::Right) | None => (padding, 0),
+        };
+        let fill = f.fill();
+        for _ in 0..before {
+            write!(f, "{fill}")?;
+        }
+        f.write_str(&text)?;
+        for _ in 0..after {
+            write!(f, "{fill}")?;
+        }
+        Ok(())
+    }
+}
+
+// Parses a number followed by a unit: "-40°F", "300K", "21.5 C",
+// "451 fahrenheit". Up to three decimal places are accepted, which keeps
+// parsing exact.
+impl FromStr for Temperature {
+    type Err = TemperatureError;
+
+    fn from_str(s: &str) -> Result<Temperature, TemperatureError> {
+        let s = s.trim();
+        let split = s
+            .find(|c: char| !(c.is_ascii_digit() || matches!(c, '+' | '-' | '.')))
+            .unwrap_or(s.len());
+        let (number, unit) = s.split_at(split);
+        if number.is_empty() {
+            return Err(TemperatureError::InvalidNumber(String::from(s)));
+        }
+        let unit: Unit = unit.parse()?;
+
+        let invalid = || TemperatureError::InvalidNumber(String::from(number));
+        let (negative, digits) = match number.strip_prefix('-') {
+            Some(rest) => (true, rest),
+            None => (false, number.strip_prefix('+').unwrap_or(number)),
+        };
+        let (whole, fraction) = digits.split_once('.').unwrap_or((digits, ""));
+        let all_digits = |part: &str| part.bytes().all(|b| b.is_ascii_digit());
+        if whole.is_empty() && fraction.is_empty() || !all_digits(whole) || !all_digits(fraction) {
+            return Err(invalid());
+        }
+        if fraction.len() > 3 {
+            return Err(TemperatureError::TooPrecise(String::from(number)));
+        }
+
+        let whole: i64 = if whole.is_empty() {
+            0
+        } else {
+            whole.parse().map_err(|_| TemperatureError::OutOfRange)?
+        };
+        let fraction: i64 = format!("{fraction:0<3}").parse().map_err(|_| invalid())?;
+        let millidegrees = whole
+            .checked_mul(1000)
+            .and_then(|w| w.checked_add(fraction))
+            .ok_or(TemperatureError::OutOfRange)?;
+        let millidegrees = if negative {
+            -millidegrees
+        } else {
+            millidegrees
+        };
+        Temperature::from_millidegrees(millidegrees, unit)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn t(s: &str) -> Temperature {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn parses_common_spellings() {
+        assert_eq!(t("-40°F").unit(), Unit::Fahrenheit);
+        assert_eq!(t("300K").value(), 300.0);
+        assert_eq!(t("21.5 C").value(), 21.5);
+        assert_eq!(t(" 451 fahrenheit ").value(), 451.0);
+        assert_eq!(t("0 °R"), t("0K"));
+        assert_eq!(t(".5C").value(), 0.5);
+    }
+
+    #[test]
+    fn rejects_bad_input() {
+        assert_eq!(
+            "-1K".parse::<Temperature>(),
+            Err(TemperatureError::BelowAbsoluteZero {
+                value: -1.0,
+                unit: Unit::Kelvin
+            })
+        );
+        assert_eq!(
+            "-273.151C".parse::<Temperature>(),
+            Err(TemperatureError::BelowAbsoluteZero {
+                value: -273.151,
+                unit: Unit::Celsius
+            })
+        );
+        assert!(
+            "-273.15C"
+                .parse::<Temperature>()
+                .unwrap()
+                .is_absolute_zero()
+        );
+        assert_eq!(
+            "12".parse::<Temperature>(),
+            Err(TemperatureError::MissingUnit)
+        );
+        assert_eq!(
+            "12 X".parse::<Temperature>(),
+            Err(TemperatureError::UnknownUnit(String::from("X")))
+        );
+        assert_eq!(
+            "1.2.3C".parse::<Temperature>(),
+            Err(TemperatureError::InvalidNumber(String::from("1.2.3")))
+        );
+        assert_eq!(
+            "1.2345C".parse::<Temperature>(),
+            Err(TemperatureError::TooPrecise(String::from("1.2345")))
+        );
+        assert!(Temperature::celsius(f64::NAN).is_err());
+    }
+
+    #[test]
+    fn compares_across_units() {
+        assert_eq!(t("-40C"), t("-40F"));
+        assert_eq!(t("0C"), t("273.15K"));
+        assert_eq!(t("32F"), t("491.67R"));
+        assert!(t("100C") > t("211.99F"));
+        assert!(t("1K") < t("2R"));
+    }
+
+    #[test]
+    fn conversions_round_trip_exactly() {
+        for input in ["98.6F", "-17.778C", "0.001K", "1000000R"] {
+            let start = t(input);
+            let mut current = start;
+            for _ in 0..100 {
+                for unit in UNITS {
+                    current = current.to(unit);
+                }
+            }
+            assert_eq!(current.to(start.unit()).to_string(), start.to_string());
+        }
+    }
+
+    #[test]
+    fn displays_with_precision() {
+        assert_eq!(t("98.6F").to(Unit::Celsius).to_string(), "37°C");
+        assert_eq!(format!("{:.1}", t("98.6F").to(Unit::Celsius)), "37.0°C");
+        assert_eq!(t("1F").to(Unit::Celsius).to_string(), "-17.222°C");
+        assert_eq!(format!("{:.5}", t("1F").to(Unit::Celsius)), "-17.22222°C");
+        assert_eq!(format!("{:.0}", t("-0.4C")), "0°C");
+        assert_eq!(format!("{:>8.1}", t("300K")), "  300.0K");
+    }
+
+    #[test]
+    fn explicit_rounding() {
+        let body = t("1F").to(Unit::Celsius).rounded(1);
+        assert_eq!(body.to_string(), "-17.2°C");
+        assert_eq!(Temperature::kelvin(0.0004).unwrap().rounded(0), t("0K"));
+        assert_eq!(t("20.25C").rounded(1).value(), 20.3);
+        assert_eq!(t("-20.25C").rounded(1).value(), -20.3);
+        assert_eq!(t("1F").to(Unit::Celsius).rounded(3), t("-17.222C"));
+    }
+}
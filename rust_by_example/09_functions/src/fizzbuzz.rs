@@ -0,0 +1,433 @@
+use std::fmt;
+use std::fs;
+use std::io::{self, Write};
+use std::ops::RangeInclusive;
+use std::path::Path;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::thread;
+
+// FizzBuzz with the rules taken out of the code. Every rule that matches a
+// number adds its word, in the order the rules were given; a number no rule
+// matches is written as itself. The classic game is `3 => fizz, 5 => buzz`,
+// which makes 15 `fizzbuzz` without a rule of its own.
+
+type Predicate = Arc<dyn Fn(i64) -> bool + Send + Sync>;
+
+#[derive(Clone)]
+enum Matcher {
+    Divisor(i64),
+    Predicate(Predicate),
+}
+
+#[derive(Clone)]
+pub struct Rule {
+    matcher: Matcher,
+    word: String,
+}
+
+impl Rule {
+    // Matches multiples of `divisor`. A zero divisor matches nothing, so it
+    // is refused rather than silently doing nothing.
+    pub fn divisor(divisor: i64, word: &str) -> Result<Rule, RuleError> {
+        if divisor == 0 {
+            return Err(RuleError::ZeroDivisor {
+                word: String::from(word),
+            });
+        }
+        Ok(Rule {
+            matcher: Matcher::Divisor(divisor),
+            word: String::from(word),
+        })
+    }
+
+    // Matches whatever numbers `predicate` accepts.
+    pub fn when<F>(word: &str, predicate: F) -> Rule
+    where
+        F: Fn(i64) -> bool + Send + Sync + 'static,
+    {
+        Rule {
+            matcher: Matcher::Predicate(Arc::new(predicate)),
+            word: String::from(word),
+        }
+    }
+
+    pub fn matches(&self, n: i64) -> bool {
+        match &self.matcher {
+            // `checked_rem` only fails for `i64::MIN % -1`, which divides.
+            Matcher::Divisor(d) => n.checked_rem(*d).is_none_or(|r| r == 0),
+            Matcher::Predicate(predicate) => predicate(n),
+        }
+    }
+
+    pub fn word(&self) -> &str {
+        &self.word
+    }
+}
+
+impl fmt::Debug for Rule {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match &self.matcher {
+            Matcher::Divisor(d) => write!(f, "Rule({} => {:?})", d, self.word),
+            Matcher::Predicate(_) => write!(f, "Rule(<predicate> => {:?})", self.word),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RuleError {
+    ZeroDivisor { word: String },
+}
+
+impl fmt::Display for RuleError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RuleError::ZeroDivisor { word } => {
+                write!(f, "the rule for \"{}\" divides by zero", word)
+            }
+        }
+    }
+}
+
+impl std::error::Error for RuleError {}
+
+#[derive(Debug, Clone, Default)]
+pub struct FizzBuzz {
+    rules: Vec<Rule>,
+}
+
+impl FizzBuzz {
+    pub fn new() -> FizzBuzz {
+        FizzBuzz::default()
+    }
+
+    pub fn classic() -> FizzBuzz {
+        FizzBuzz::new()
+            .rule(Rule::divisor(3, "fizz").unwrap())
+            .rule(Rule::divisor(5, "buzz").unwrap())
+    }
+
+    pub fn rule(mut self, rule: Rule) -> FizzBuzz {
+        self.rules.push(rule);
+        self
+    }
+
+    pub fn rules(&self) -> &[Rule] {
+        &self.rules
+    }
+
+    pub fn say(&self, n: i64) -> String {
+        let mut out = String::new();
+        self.write_one(n, &mut out);
+        out
+    }
+
+    // What to say for each number, computed only as the iterator is advanced,
+    // so `outputs(1..)` is fine as long as you stop taking.
+    pub fn outputs<I: IntoIterator<Item = i64>>(&self, numbers: I) -> Outputs<'_, I::IntoIter> {
+        Outputs {
+            game: self,
+            numbers: numbers.into_iter(),
+        }
+    }
+
+    // One output per line.
+    pub fn write_to<W: Write>(&self, numbers: RangeInclusive<i64>, out: &mut W) -> io::Result<()> {
+        let mut buf = io::BufWriter::new(out);
+        for n in numbers {
+            let mut line = self.say(n);
+            line.push('\n');
+            buf.write_all(line.as_bytes())?;
+        }
+        buf.flush()
+    }
+
+    // Writes exactly what `write_to` would, but works out `threads` chunks of
+    // the range at a time on separate threads. Chunks are written in order,
+    // and at most `threads` of them are held in memory at once.
+    pub fn write_parallel<W: Write>(
+        &self,
+        numbers: RangeInclusive<i64>,
+        threads: usize,
+        out: &mut W,
+    ) -> io::Result<()> {
+        let threads = threads.max(1);
+        let mut chunks = Chunks::new(numbers, CHUNK_SIZE);
+        loop {
+            let round: Vec<RangeInclusive<i64>> = chunks.by_ref().take(threads).collect();
+            if round.is_empty() {
+                return out.flush();
+            }
+            let rendered: Vec<String> = thread::scope(|scope| {
+                let handles: Vec<_> = round
+                    .into_iter()
+                    .map(|chunk| scope.spawn(move || self.render(chunk)))
+                    .collect();
+                handles
+                    .into_iter()
+                    .map(|h| h.join().expect("fizzbuzz worker panicked"))
+                    .collect()
+            });
+            for text in rendered {
+                out.write_all(text.as_bytes())?;
+            }
+        }
+    }
+
+    fn render(&self, numbers: RangeInclusive<i64>) -> String {
+        let mut text = String::new();
+        for n in numbers {
+            self.write_one(n, &mut text);
+            text.push('\n');
+        }
+        text
+    }
+
+    fn write_one(&self, n: i64, out: &mut String) {
+        let start = out.len();
+        for rule in self.rules.iter().filter(|rule| rule.matches(n)) {
+            out.push_str(&rule.word);
+        }
+        if out.len() == start {
+            out.push_str(&n.to_string());
+        }
+    }
+
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<FizzBuzz, ConfigError> {
+        fs::read_to_string(path)
+            .map_err(|e| ConfigError::Io(e.to_string()))?
+            .parse()
+    }
+}
+
+// Numbers per chunk in `write_parallel`.
+const CHUNK_SIZE: i64 = 1 << 16;
+
+// Splits a range into consecutive pieces of at most `size` numbers, without
+// overflowing at the ends of `i64`.
+struct Chunks {
+    next: Option<i64>,
+    end: i64,
+    size: i64,
+}
+
+impl Chunks {
+    fn new(range: RangeInclusive<i64>, size: i64) -> Chunks {
+        let (start, end) = range.into_inner();
+        Chunks {
+            next: (start <= end).then_some(start),
+            end,
+            size,
+        }
+    }
+}
+
+impl Iterator for Chunks {
+    type Item = RangeInclusive<i64>;
+
+    fn next(&mut self) -> Option<RangeInclusive<i64>> {
+        let start = self.next?;
+        let last = start.saturating_add(self.size - 1).min(self.end);
+        self.next = if last == self.end {
+            None
+        } else {
+            Some(last + 1)
+        };
+        Some(start..=last)
+    }
+}
+
+pub struct Outputs<'a, I> {
+    game: &'a FizzBuzz,
+    numbers: I,
+}
+
+impl<I: Iterator<Item = i64>> Iterator for Outputs<'_, I> {
+    type Item = String;
+
+    fn next(&mut self) -> Option<String> {
+        self.numbers.next().map(|n| self.game.say(n))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.numbers.size_hint()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConfigError {
+    Io(String),
+    // A line that isn't `<divisor> = <word>`.
+    Syntax { line: usize },
+    InvalidDivisor { line: usize },
+    InvalidRule { line: usize, error: RuleError },
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ConfigError::Io(message) => write!(f, "can't read rules: {}", message),
+            ConfigError::Syntax { line } => {
+                write!(f, "line {}: expected `<divisor> = <word>`", line)
+            }
+            ConfigError::InvalidDivisor { line } => {
+                write!(f, "line {}: the divisor is not a whole number", line)
+            }
+            ConfigError::InvalidRule { line, error } => write!(f, "line {}: {}", line, error),
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+// The config format is one `<divisor> = <word>` rule per line, in order.
+// Blank lines and lines starting with `#` are ignored:
+//
+//     # classic
+//     3 = fizz
+//     5 = buzz
+impl FromStr for FizzBuzz {
+    type Err = ConfigError;
+
+    fn from_str(text: &str) -> Result<FizzBuzz, ConfigError> {
+        let mut game = FizzBuzz::new();
+        for (i, line) in text.lines().enumerate() {
+            let line_no = i + 1;
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let (divisor, word) = line
+                .split_once('=')
+                .ok_or(ConfigError::Syntax { line: line_no })?;
+            let (divisor, word) = (divisor.trim(), word.trim());
+            if divisor.is_empty() || word.is_empty() {
+                return Err(ConfigError::Syntax { line: line_no });
+            }
+            let divisor = divisor
+                .parse()
+                .map_err(|_| ConfigError::InvalidDivisor { line: line_no })?;
+            let rule = Rule::divisor(divisor, word).map_err(|error| ConfigError::InvalidRule {
+                line: line_no,
+                error,
+            })?;
+            game = game.rule(rule);
+        }
+        Ok(game)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn classic_game() {
+        let game = FizzBuzz::classic();
+        let first: Vec<String> = game.outputs(1..=15).collect();
+        assert_eq!(
+            first.join(" "),
+            "1 2 fizz 4 buzz fizz 7 8 fizz buzz 11 fizz 13 14 fizzbuzz"
+        );
+        assert_eq!(game.say(0), "fizzbuzz");
+        assert_eq!(game.say(-9), "fizz");
+        assert_eq!(game.say(i64::MIN), i64::MIN.to_string());
+    }
+
+    #[test]
+    fn closures_and_divisors_mix() {
+        let game = FizzBuzz::classic()
+            .rule(Rule::when("bazz", |n| n.to_string().contains('7')))
+            .rule(Rule::divisor(-2, "!").unwrap());
+        let said: Vec<String> = game.outputs((5..=21).step_by(4)).collect();
+        assert_eq!(said, vec!["buzz", "fizz", "13", "bazz", "fizz"]);
+        assert_eq!(game.say(70), "buzzbazz!");
+        assert_eq!(game.say(i64::MIN), "bazz!");
+        assert_eq!(
+            format!("{:?}", game.rules()[2..].to_vec()),
+            "[Rule(<predicate> => \"bazz\"), Rule(-2 => \"!\")]"
+        );
+    }
+
+    #[test]
+    fn outputs_are_lazy() {
+        let game = FizzBuzz::new().rule(Rule::when("big", |n| {
+            assert!(n < 100, "evaluated too far");
+            false
+        }));
+        let taken: Vec<String> = game.outputs(1..).take(3).collect();
+        assert_eq!(taken, vec!["1", "2", "3"]);
+    }
+
+    #[test]
+    fn zero_divisors_are_rejected() {
+        assert_eq!(
+            Rule::divisor(0, "never").unwrap_err(),
+            RuleError::ZeroDivisor {
+                word: String::from("never")
+            }
+        );
+        assert_eq!(
+            "3 = fizz\n0 = nope"
+                .parse::<FizzBuzz>()
+                .unwrap_err()
+                .to_string(),
+            "line 2: the rule for \"nope\" divides by zero"
+        );
+    }
+
+    #[test]
+    fn parses_config() {
+        let game: FizzBuzz = "# game\n\n 2 = fizz \n3=buzz\n4 = pop pop\n"
+            .parse()
+            .unwrap();
+        let said: Vec<String> = game.outputs(1..=6).collect();
+        assert_eq!(
+            said,
+            vec!["1", "fizz", "buzz", "fizzpop pop", "5", "fizzbuzz"]
+        );
+
+        let error = |text: &str| text.parse::<FizzBuzz>().unwrap_err();
+        assert_eq!(error("3 fizz"), ConfigError::Syntax { line: 1 });
+        assert_eq!(error("3 ="), ConfigError::Syntax { line: 1 });
+        assert_eq!(
+            error("\nthree = fizz"),
+            ConfigError::InvalidDivisor { line: 2 }
+        );
+        assert!(matches!(
+            FizzBuzz::load("/no/such/rules"),
+            Err(ConfigError::Io(_))
+        ));
+    }
+
+    #[test]
+    fn parallel_output_is_identical() {
+        let game = FizzBuzz::classic().rule(Rule::when("seven", |n| n % 7 == 0));
+        let ranges = [
+            1..=1,
+            RangeInclusive::new(5, 4),
+            -300_000..=300_000,
+            i64::MAX - 100_000..=i64::MAX,
+            i64::MIN..=i64::MIN + 70_000,
+        ];
+        for range in ranges {
+            let mut sequential = Vec::new();
+            game.write_to(range.clone(), &mut sequential).unwrap();
+            for threads in [1, 3, 8] {
+                let mut parallel = Vec::new();
+                game.write_parallel(range.clone(), threads, &mut parallel)
+                    .unwrap();
+                assert!(parallel == sequential, "{range:?} with {threads} threads");
+            }
+        }
+    }
+
+    #[test]
+    fn chunks_cover_the_range() {
+        let chunks: Vec<_> = Chunks::new(1..=10, 4).collect();
+        assert_eq!(chunks, vec![1..=4, 5..=8, 9..=10]);
+        let ends: Vec<_> = Chunks::new(i64::MAX - 2..=i64::MAX, 2).collect();
+        assert_eq!(ends, vec![i64::MAX - 2..=i64::MAX - 1, i64::MAX..=i64::MAX]);
+        assert_eq!(Chunks::new(RangeInclusive::new(3, 2), 4).count(), 0);
+    }
+}
@@ -0,0 +1,652 @@
+use std::cell::Cell;
+use std::collections::{BTreeMap, HashMap};
+use std::hash::Hash;
+use std::rc::Rc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+// Small helpers that take closures and give back new ones, in the spirit of
+// `apply`, `create_fn` and friends in main.rs.
+
+// `f(g(x))`: `g` runs first, as in mathematics.
+pub fn compose<A, B, C, F, G>(f: F, g: G) -> impl Fn(A) -> C
+where
+    F: Fn(B) -> C,
+    G: Fn(A) -> B,
+{
+    move |x| f(g(x))
+}
+
+// `g(f(x))`: reads left to right, in the order the steps happen.
+pub fn pipe<A, B, C, F, G>(f: F, g: G) -> impl Fn(A) -> C
+where
+    F: Fn(A) -> B,
+    G: Fn(B) -> C,
+{
+    move |x| g(f(x))
+}
+
+// `pipe` for closures that change their captured state.
+pub fn pipe_mut<A, B, C, F, G>(mut f: F, mut g: G) -> impl FnMut(A) -> C
+where
+    F: FnMut(A) -> B,
+    G: FnMut(B) -> C,
+{
+    move |x| g(f(x))
+}
+
+// `pipe` for closures that can only be called once.
+pub fn pipe_once<A, B, C, F, G>(f: F, g: G) -> impl FnOnce(A) -> C
+where
+    F: FnOnce(A) -> B,
+    G: FnOnce(B) -> C,
+{
+    move |x| g(f(x))
+}
+
+// A partly applied function, as returned by `curry2` and `curry3`.
+pub type Curried<A, R> = Box<dyn Fn(A) -> R>;
+
+// Turns `f(a, b)` into `f(a)(b)`. Each partial application can be called
+// any number of times.
+pub fn curry2<A, B, R, F>(f: F) -> impl Fn(A) -> Curried<B, R>
+where
+    A: Clone + 'static,
+    B: 'static,
+    R: 'static,
+    F: Fn(A, B) -> R + 'static,
+{
+    let f = Rc::new(f);
+    move |a| {
+        let f = Rc::clone(&f);
+        Box::new(move |b| f(a.clone(), b))
+    }
+}
+
+// Turns `f(a, b, c)` into `f(a)(b)(c)`.
+pub fn curry3<A, B, C, R, F>(f: F) -> impl Fn(A) -> Curried<B, Curried<C, R>>
+where
+    A: Clone + 'static,
+    B: Clone + 'static,
+    C: 'static,
+    R: 'static,
+    F: Fn(A, B, C) -> R + 'static,
+{
+    let f = Rc::new(f);
+    move |a| {
+        let f = Rc::clone(&f);
+        Box::new(move |b| {
+            let f = Rc::clone(&f);
+            let a = a.clone();
+            Box::new(move |c| f(a.clone(), b.clone(), c))
+        })
+    }
+}
+
+// Remembers the results of up to `capacity` calls, forgetting the least
+// recently used one when full. Functions of several arguments can take a
+// tuple.
+pub struct Memoize<A, R, F> {
+    f: F,
+    capacity: usize,
+    // Each argument's result and when it was last used.
+    cache: HashMap<A, (R, u64)>,
+    // The same entries ordered by last use, oldest first.
+    by_use: BTreeMap<u64, A>,
+    tick: u64,
+    hits: u64,
+    misses: u64,
+}
+
+pub fn memoize<A, R, F>(f: F, capacity: usize) -> Memoize<A, R, F>
+where
+    A: Hash + Eq + Clone,
+    R: Clone,
+    F: FnMut(A) -> R,
+{
+    Memoize {
+        f,
+        capacity,
+        cache: HashMap::new(),
+        by_use: BTreeMap::new(),
+        tick: 0,
+        hits: 0,
+        misses: 0,
+    }
+}
+
+impl<A, R, F> Memoize<A, R, F>
+where
+    A: Hash + Eq + Clone,
+    R: Clone,
+    F: FnMut(A) -> R,
+{
+    pub fn call(&mut self, arg: A) -> R {
+        self.tick += 1;
+        if let Some((result, last_used)) = self.cache.get_mut(&arg) {
+            self.hits += 1;
+            self.by_use.remove(last_used);
+            *last_used = self.tick;
+            self.by_use.insert(self.tick, arg);
+            return result.clone();
+        }
+
+        self.misses += 1;
+        let result = (self.f)(arg.clone());
+        if self.capacity == 0 {
+            return result;
+        }
+        if self.cache.len() == self.capacity
+            && let Some((_, oldest)) = self.by_use.pop_first()
+        {
+            self.cache.remove(&oldest);
+        }
+        self.cache.insert(arg.clone(), (result.clone(), self.tick));
+        self.by_use.insert(self.tick, arg);
+        result
+    }
+
+    pub fn len(&self) -> usize {
+        self.cache.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cache.is_empty()
+    }
+
+    // Calls answered from the cache, and calls that ran the function.
+    pub fn stats(&self) -> (u64, u64) {
+        (self.hits, self.misses)
+    }
+}
+
+// Runs `f` the first time it is called and hands back the same result every
+// time after that.
+pub struct Once<R, F> {
+    f: Option<F>,
+    result: Option<R>,
+}
+
+pub fn once<R, F: FnOnce() -> R>(f: F) -> Once<R, F> {
+    Once {
+        f: Some(f),
+        result: None,
+    }
+}
+
+impl<R, F: FnOnce() -> R> Once<R, F> {
+    pub fn call(&mut self) -> &R {
+        if let Some(f) = self.f.take() {
+            self.result = Some(f());
+        }
+        self.result.as_ref().expect("set on the first call")
+    }
+
+    pub fn has_run(&self) -> bool {
+        self.f.is_none()
+    }
+}
+
+// Where the time-based helpers get the time from. `ManualClock` lets tests
+// step through time exactly, and makes `sleep` return at once.
+pub trait Clock {
+    fn now(&self) -> Duration;
+    fn sleep(&self, duration: Duration);
+}
+
+impl<C: Clock + ?Sized> Clock for &C {
+    fn now(&self) -> Duration {
+        (**self).now()
+    }
+
+    fn sleep(&self, duration: Duration) {
+        (**self).sleep(duration)
+    }
+}
+
+// Real time, measured from when the clock was created.
+#[derive(Debug, Clone, Copy)]
+pub struct SystemClock {
+    start: Instant,
+}
+
+impl SystemClock {
+    pub fn new() -> SystemClock {
+        SystemClock {
+            start: Instant::now(),
+        }
+    }
+}
+
+impl Default for SystemClock {
+    fn default() -> SystemClock {
+        SystemClock::new()
+    }
+}
+
+impl Clock for SystemClock {
+    fn now(&self) -> Duration {
+        self.start.elapsed()
+    }
+
+    fn sleep(&self, duration: Duration) {
+        thread::sleep(duration)
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct ManualClock {
+    now: Cell<Duration>,
+    slept: Cell<Duration>,
+}
+
+impl ManualClock {
+    pub fn new() -> ManualClock {
+        ManualClock::default()
+    }
+
+    pub fn advance(&self, duration: Duration) {
+        self.now.set(self.now.get() + duration);
+    }
+
+    // The total time asked for through `sleep`.
+    pub fn slept(&self) -> Duration {
+        self.slept.get()
+    }
+}
+
+impl Clock for ManualClock {
+    fn now(&self) -> Duration {
+        self.now.get()
+    }
+
+    fn sleep(&self, duration: Duration) {
+        self.slept.set(self.slept.get() + duration);
+        self.advance(duration);
+    }
+}
+
+// Runs `f` with the latest argument once calls have stopped for `wait`.
+// Nothing runs in the background: the pending call fires from `poll`, or from
+// the next `call` if it comes after the quiet period.
+pub struct Debounce<A, F, C> {
+    f: F,
+    wait: Duration,
+    clock: C,
+    pending: Option<(A, Duration)>,
+}
+
+pub fn debounce<A, R, F, C>(f: F, wait: Duration, clock: C) -> Debounce<A, F, C>
+where
+    F: FnMut(A) -> R,
+    C: Clock,
+{
+    Debounce {
+        f,
+        wait,
+        clock,
+        pending: None,
+    }
+}
+
+impl<A, R, F, C> Debounce<A, F, C>
+where
+    F: FnMut(A) -> R,
+    C: Clock,
+{
+    // Replaces any pending call with this one. Returns the result of the
+    // previous pending call if its quiet period had already passed.
+    pub fn call(&mut self, arg: A) -> Option<R> {
+        let fired = self.poll();
+        self.pending = Some((arg, self.clock.now()));
+        fired
+    }
+
+    // A quiet period that would end past `Duration::MAX` never ends, so the
+    // call stays pending until `flush`.
+    pub fn poll(&mut self) -> Option<R> {
+        let now = self.clock.now();
+        match &self.pending {
+            Some((_, at)) if at.checked_add(self.wait).is_some_and(|end| now >= end) => {
+                let (arg, _) = self.pending.take()?;
+                Some((self.f)(arg))
+            }
+            _ => None,
+        }
+    }
+
+    // Runs the pending call now, without waiting.
+    pub fn flush(&mut self) -> Option<R> {
+        let (arg, _) = self.pending.take()?;
+        Some((self.f)(arg))
+    }
+
+    pub fn is_pending(&self) -> bool {
+        self.pending.is_some()
+    }
+}
+
+// Runs `f` at most once per `interval`. Calls in between are dropped.
+pub struct Throttle<F, C> {
+    f: F,
+    interval: Duration,
+    clock: C,
+    last_run: Option<Duration>,
+}
+
+pub fn throttle<A, R, F, C>(f: F, interval: Duration, clock: C) -> Throttle<F, C>
+where
+    F: FnMut(A) -> R,
+    C: Clock,
+{
+    Throttle {
+        f,
+        interval,
+        clock,
+        last_run: None,
+    }
+}
+
+impl<F, C: Clock> Throttle<F, C> {
+    pub fn call<A, R>(&mut self, arg: A) -> Option<R>
+    where
+        F: FnMut(A) -> R,
+    {
+        let now = self.clock.now();
+        // An interval that would end past `Duration::MAX` never ends.
+        let waiting = self.last_run.is_some_and(|last| {
+            last.checked_add(self.interval)
+                .is_none_or(|next| now < next)
+        });
+        if waiting {
+            return None;
+        }
+        self.last_run = Some(now);
+        Some((self.f)(arg))
+    }
+}
+
+// How long `retry` waits between attempts: `initial`, then multiplied by
+// `factor` each time, but never more than `max_delay`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Backoff {
+    initial: Duration,
+    factor: u32,
+    max_delay: Duration,
+    attempts: u32,
+}
+
+impl Backoff {
+    pub fn new(initial: Duration) -> Backoff {
+        Backoff {
+            initial,
+            factor: 2,
+            max_delay: Duration::MAX,
+            attempts: 3,
+        }
+    }
+
+    pub fn factor(mut self, factor: u32) -> Backoff {
+        self.factor = factor;
+        self
+    }
+
+    pub fn max_delay(mut self, max_delay: Duration) -> Backoff {
+        self.max_delay = max_delay;
+        self
+    }
+
+    // Attempts in total, including the first. At least one is always made.
+    pub fn attempts(mut self, attempts: u32) -> Backoff {
+        self.attempts = attempts;
+        self
+    }
+
+    // The waits between attempts, in order.
+    pub fn delays(&self) -> impl Iterator<Item = Duration> + use<> {
+        let (factor, max_delay) = (self.factor, self.max_delay);
+        std::iter::successors(Some(self.initial.min(max_delay)), move |d| {
+            Some(
+                d.checked_mul(factor)
+                    .unwrap_or(Duration::MAX)
+                    .min(max_delay),
+            )
+        })
+        .take(self.attempts.saturating_sub(1) as usize)
+    }
+}
+
+// Calls `op` until it succeeds or the attempts run out, sleeping on `clock`
+// between tries. `op` is told which attempt it is on, starting from 1. The
+// error from the last attempt is returned.
+pub fn retry<T, E, F, C>(backoff: &Backoff, clock: C, mut op: F) -> Result<T, E>
+where
+    F: FnMut(u32) -> Result<T, E>,
+    C: Clock,
+{
+    let mut delays = backoff.delays();
+    let mut attempt = 1;
+    loop {
+        match op(attempt) {
+            Ok(value) => return Ok(value),
+            Err(e) => match delays.next() {
+                Some(delay) => clock.sleep(delay),
+                None => return Err(e),
+            },
+        }
+        attempt += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ms(n: u64) -> Duration {
+        Duration::from_millis(n)
+    }
+
+    #[test]
+    fn compose_and_pipe() {
+        let double = |x: i32| x * 2;
+        let inc = |x: i32| x + 1;
+        assert_eq!(compose(double, inc)(5), 12);
+        assert_eq!(pipe(double, inc)(5), 11);
+        let describe = pipe(pipe(inc, double), |x: i32| format!("<{}>", x));
+        assert_eq!(describe(1), "<4>");
+        assert_eq!(describe(2), "<6>");
+
+        let mut seen = Vec::new();
+        let mut count = 0;
+        let mut counted = pipe_mut(
+            |x: i32| {
+                count += 1;
+                x
+            },
+            |x| seen.push(x),
+        );
+        counted(7);
+        counted(8);
+        drop(counted);
+        assert_eq!((count, seen), (2, vec![7, 8]));
+
+        let name = String::from("moved");
+        let consume = pipe_once(move |suffix: &str| name + suffix, |s: String| s.len());
+        assert_eq!(consume("!"), 6);
+    }
+
+    #[test]
+    fn currying() {
+        let add = curry2(|a: i32, b: i32| a + b);
+        let add_ten = add(10);
+        assert_eq!(add_ten(1), 11);
+        assert_eq!(add_ten(5), 15);
+        assert_eq!(add(1)(2), 3);
+
+        let join = curry3(|a: String, b: char, c: &str| format!("{a}{b}{c}"));
+        let greet = join(String::from("hello"));
+        let greet_comma = greet(',');
+        assert_eq!(greet_comma(" world"), "hello, world");
+        assert_eq!(greet_comma(" there"), "hello, there");
+        assert_eq!(greet(' ')("you"), "hello you");
+    }
+
+    #[test]
+    fn memoize_caches_and_evicts_least_recently_used() {
+        let mut calls = Vec::new();
+        let mut square = memoize(
+            |x: u64| {
+                calls.push(x);
+                x * x
+            },
+            2,
+        );
+        assert_eq!(square.call(3), 9);
+        assert_eq!(square.call(4), 16);
+        assert_eq!(square.call(3), 9); // 4 is now the least recently used
+        assert_eq!(square.call(5), 25); // evicts 4
+        assert_eq!(square.call(3), 9);
+        assert_eq!(square.call(4), 16); // evicts 5
+        assert_eq!(square.len(), 2);
+        assert_eq!(square.stats(), (2, 4));
+        drop(square);
+        assert_eq!(calls, vec![3, 4, 5, 4]);
+    }
+
+    #[test]
+    fn memoize_with_tuple_arguments_and_no_capacity() {
+        let mut runs = 0;
+        let mut power = memoize(
+            |(base, exp): (u64, u32)| {
+                runs += 1;
+                base.pow(exp)
+            },
+            0,
+        );
+        assert_eq!(power.call((2, 10)), 1024);
+        assert_eq!(power.call((2, 10)), 1024);
+        assert!(power.is_empty());
+        drop(power);
+        assert_eq!(runs, 2);
+    }
+
+    #[test]
+    fn once_runs_a_single_time() {
+        let text = String::from("only once");
+        let mut runs = 0;
+        let mut init = once(|| {
+            runs += 1;
+            text
+        });
+        assert!(!init.has_run());
+        assert_eq!(init.call(), "only once");
+        assert_eq!(init.call(), "only once");
+        assert!(init.has_run());
+        drop(init);
+        assert_eq!(runs, 1);
+    }
+
+    #[test]
+    fn debounce_waits_for_quiet() {
+        let clock = ManualClock::new();
+        let mut saved = Vec::new();
+        {
+            let mut save = debounce(|text: &str| saved.push(String::from(text)), ms(100), &clock);
+
+            assert_eq!(save.call("h"), None);
+            clock.advance(ms(50));
+            assert_eq!(save.call("he"), None);
+            clock.advance(ms(99));
+            assert_eq!(save.poll(), None);
+            clock.advance(ms(1));
+            assert_eq!(save.poll(), Some(()));
+            assert!(!save.is_pending());
+            assert_eq!(save.poll(), None);
+
+            save.call("hel");
+            clock.advance(ms(500));
+            // The overdue call fires before the new one is queued.
+            assert_eq!(save.call("help"), Some(()));
+            assert_eq!(save.flush(), Some(()));
+            assert_eq!(save.flush(), None);
+        }
+        assert_eq!(saved, vec!["he", "hel", "help"]);
+    }
+
+    #[test]
+    fn throttle_drops_calls_within_the_interval() {
+        let clock = ManualClock::new();
+        let mut log = throttle(|n: u32| n * 10, ms(100), &clock);
+        let mut results = Vec::new();
+        for _ in 0..6 {
+            results.push(log.call(results.len() as u32));
+            clock.advance(ms(40));
+        }
+        // Runs at 0ms and 120ms; the next run may be from 220ms on.
+        assert_eq!(results, vec![Some(0), None, None, Some(30), None, None]);
+        clock.advance(ms(100));
+        assert_eq!(log.call(9), Some(90));
+    }
+
+    #[test]
+    fn endless_waits_do_not_overflow() {
+        let clock = ManualClock::new();
+        clock.advance(ms(1));
+        let mut save = debounce(|n: u32| n, Duration::MAX, &clock);
+        assert_eq!(save.call(1), None);
+        assert_eq!(save.poll(), None);
+        assert_eq!(save.flush(), Some(1));
+
+        let mut log = throttle(|n: u32| n, Duration::MAX, &clock);
+        assert_eq!(log.call(1), Some(1));
+        clock.advance(ms(1));
+        assert_eq!(log.call(2), None);
+    }
+
+    #[test]
+    fn backoff_delays() {
+        let backoff = Backoff::new(ms(100))
+            .factor(3)
+            .max_delay(ms(1000))
+            .attempts(6);
+        let delays: Vec<Duration> = backoff.delays().collect();
+        assert_eq!(delays, vec![ms(100), ms(300), ms(900), ms(1000), ms(1000)]);
+        assert_eq!(Backoff::new(ms(5)).attempts(1).delays().count(), 0);
+        assert_eq!(Backoff::new(ms(5)).attempts(0).delays().count(), 0);
+        let huge = Backoff::new(Duration::MAX).attempts(3);
+        assert_eq!(huge.delays().last(), Some(Duration::MAX));
+    }
+
+    #[test]
+    fn retry_backs_off_until_success() {
+        let clock = ManualClock::new();
+        let backoff = Backoff::new(ms(10)).attempts(5);
+        let mut attempts = Vec::new();
+        let result = retry(&backoff, &clock, |attempt| {
+            attempts.push((attempt, clock.now()));
+            if attempt < 3 {
+                Err("busy")
+            } else {
+                Ok(attempt * 100)
+            }
+        });
+        assert_eq!(result, Ok(300));
+        assert_eq!(attempts, vec![(1, ms(0)), (2, ms(10)), (3, ms(30))]);
+        assert_eq!(clock.slept(), ms(30));
+    }
+
+    #[test]
+    fn retry_gives_up_with_the_last_error() {
+        let clock = ManualClock::new();
+        let backoff = Backoff::new(ms(1)).attempts(4);
+        let result: Result<(), String> = retry(&backoff, &clock, |attempt| {
+            Err(format!("attempt {attempt} failed"))
+        });
+        assert_eq!(result, Err(String::from("attempt 4 failed")));
+        assert_eq!(clock.slept(), ms(1 + 2 + 4));
+
+        let once_only: Result<(), &str> =
+            retry(&Backoff::new(ms(1)).attempts(0), &clock, |_| Err("no"));
+        assert_eq!(once_only, Err("no"));
+    }
+}
@@ -1,3 +1,8 @@
+use std::time::Duration;
+
+use functions::fizzbuzz::{FizzBuzz, Rule};
+use functions::functional;
+
 struct Point {
     x: f64,
     y: f64,
@@ -79,6 +84,21 @@ fn main() {
     // We can use this function here, and define it somewhere later
     fizzbuzz_to(100);
 
+    // The same game with rules read from a file, plus one given as a closure.
+    let rules = concat!(env!("CARGO_MANIFEST_DIR"), "/fizzbuzz.rules");
+    match FizzBuzz::load(rules) {
+        Ok(game) => {
+            let game = game.rule(Rule::when("!", |n| n.to_string().ends_with('7')));
+            let said: Vec<String> = game.outputs(95..=105).collect();
+            println!("{}", said.join(" "));
+        }
+        Err(e) => println!("{}", e),
+    }
+    // A rule refuses to be built with a zero divisor.
+    if let Err(e) = Rule::divisor(0, "never") {
+        println!("{}", e);
+    }
+
 
 
     let rectangle = Rectangle {
@@ -210,6 +230,26 @@ fn main() {
     fn_mut();
     fn_once();
 
+    // The `functional` module builds new closures out of existing ones.
+    let add_one_then_double = functional::pipe(|x: i32| x + 1, double);
+    println!("3 plus one, doubled: {}", apply_to_3(add_one_then_double));
+    let add = functional::curry2(|a: i32, b: i32| a + b);
+    println!("add(2)(3) = {}", add(2)(3));
+
+    let mut slow_square = functional::memoize(|n: u64| n * n, 16);
+    for n in [4, 4, 5, 4] {
+        slow_square.call(n);
+    }
+    let (hits, misses) = slow_square.stats();
+    println!("memoized squares: {} hits, {} misses", hits, misses);
+
+    let clock = functional::ManualClock::new();
+    let backoff = functional::Backoff::new(Duration::from_millis(100)).attempts(4);
+    let result = functional::retry(&backoff, &clock, |attempt| {
+        if attempt < 3 { Err("not yet") } else { Ok(attempt) }
+    });
+    println!("retry: {:?} after waiting {:?}", result, clock.slept());
+
 
 
 
@@ -344,35 +384,19 @@ fn main() {
 
 }
 
-// Function that returns a boolean value
-fn is_divisible_by(lhs: u32, rhs: u32) -> bool {
-    // Corner case, early return
-    if rhs == 0 {
-        return false;
-    }
-
-    // This is an expression, the `return` keyword is not necessary here
-    lhs % rhs == 0
-}
-
 // Functions that "don't" return a value, actually return the unit type `()`
-fn fizzbuzz(n: u32) -> () {
-    if is_divisible_by(n, 15) {
-        println!("fizzbuzz");
-    } else if is_divisible_by(n, 3) {
-        println!("fizz");
-    } else if is_divisible_by(n, 5) {
-        println!("buzz");
-    } else {
-        println!("{}", n);
-    }
+fn fizzbuzz(game: &FizzBuzz, n: u32) -> () {
+    // The rules live in the `fizzbuzz` module now, instead of being
+    // hard-coded here as checks for 15, 3 and 5.
+    println!("{}", game.say(i64::from(n)));
 }
 
 // When a function returns `()`, the return type can be omitted from the
 // signature
 fn fizzbuzz_to(n: u32) {
+    let game = FizzBuzz::classic();
     for n in 1..=n {
-        fizzbuzz(n);
+        fizzbuzz(&game, n);
     }
 }
 
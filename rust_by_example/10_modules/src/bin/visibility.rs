@@ -0,0 +1,44 @@
+// Prints a crate's module tree with the visibility of every item, which
+// modules can reach it, and which `pub` items nothing uses:
+//
+//     $ cargo run --bin visibility -- src/main.rs
+//     crate (src/main.rs, binary)
+//       mod my_mod  private -> crate
+//         fn private_function  private -> crate::my_mod
+//         ...
+//
+// `--dot` prints a Graphviz graph instead, e.g. for `dot -Tsvg`. A root named
+// `lib.rs` is treated as a library, so its `pub` items count as exported.
+use std::path::PathBuf;
+use std::process;
+
+use modules::visibility::Crate;
+
+fn main() {
+    let mut dot = false;
+    let mut root = None;
+    for arg in std::env::args().skip(1) {
+        match arg.as_str() {
+            "--dot" => dot = true,
+            "-h" | "--help" => {
+                println!("usage: visibility [--dot] [ROOT_FILE]");
+                return;
+            }
+            _ if root.is_none() => root = Some(PathBuf::from(arg)),
+            _ => {
+                eprintln!("usage: visibility [--dot] [ROOT_FILE]");
+                process::exit(2);
+            }
+        }
+    }
+    let root = root.unwrap_or_else(|| PathBuf::from("src/main.rs"));
+
+    match Crate::load(&root) {
+        Ok(krate) if dot => print!("{}", krate.to_dot()),
+        Ok(krate) => print!("{}", krate.to_text()),
+        Err(e) => {
+            eprintln!("{e}");
+            process::exit(1);
+        }
+    }
+}
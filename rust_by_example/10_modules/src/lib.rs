@@ -0,0 +1,2 @@
+pub mod sealed;
+pub mod visibility;
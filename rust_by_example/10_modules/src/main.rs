@@ -79,6 +79,8 @@ mod my_mod {
 // Bind the `deeply::nested::function` path to `other_function`.
 use deeply::nested::function as other_function;
 
+use modules::sealed::{Audited, ReadOnly, Secret, WriteOnce};
+
 fn function() {
     println!("called `function()`");
 }
@@ -217,6 +219,27 @@ fn main() {
     // Error! The `contents` field is private
     //println!("The closed box contains: {}", _closed_box.contents);
     // TODO ^ Try uncommenting this line
+
+    // The `sealed` wrappers keep their contents private like `ClosedBox`,
+    // but each offers one limited way back in.
+    let name = ReadOnly::new("primary");
+    let mut port = WriteOnce::new();
+    let api_key = Secret::new(String::from("sk-123456"));
+    let mut workers = Audited::new(4);
+
+    port.set(8080).unwrap();
+    if let Err(e) = port.set(9090) {
+        println!("Setting the port again failed: {}", e);
+    }
+    workers.set(8, "more traffic expected").unwrap();
+    workers.update("one per core", |n| *n = 16).unwrap();
+
+    println!("The read-only name is: {}", name);
+    println!("The port is: {:?}", port);
+    println!("The API key prints as {} and is {} bytes", api_key, api_key.expose().len());
+    for change in workers.history() {
+        println!("Workers change {}: {}", change.version, change.reason);
+    }
     
 
 
@@ -0,0 +1,35 @@
+// Wrappers that decide who may read or change a value, grown out of the
+// lesson's `OpenBox` and `ClosedBox`. Like `ClosedBox`, each keeps its
+// contents in a private field; unlike it, each has a deliberate, limited way
+// back in. Every wrapper lives in its own module, so not even the rest of
+// this crate can reach past the methods it chooses to offer.
+use std::fmt;
+
+mod audited;
+mod read_only;
+mod secret;
+mod write_once;
+
+pub use audited::{Audited, Change};
+pub use read_only::ReadOnly;
+pub use secret::{Secret, Zeroize};
+pub use write_once::WriteOnce;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AccessError {
+    // `WriteOnce::set` was called on a value that already has one.
+    AlreadySet,
+    // An `Audited` change was asked for without saying why.
+    MissingReason,
+}
+
+impl fmt::Display for AccessError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            AccessError::AlreadySet => write!(f, "the value has already been set"),
+            AccessError::MissingReason => write!(f, "a change needs a reason"),
+        }
+    }
+}
+
+impl std::error::Error for AccessError {}
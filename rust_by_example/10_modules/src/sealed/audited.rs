@@ -0,0 +1,110 @@
+use std::fmt;
+
+use super::AccessError;
+
+// One mutation of an `Audited` value. `version` counts changes from 1.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Change {
+    pub version: u64,
+    pub reason: String,
+}
+
+// A value that can only be changed by saying why. Every change is kept, in
+// order, and the log can be read but never edited.
+pub struct Audited<T> {
+    value: T,
+    history: Vec<Change>,
+}
+
+impl<T> Audited<T> {
+    pub fn new(value: T) -> Audited<T> {
+        Audited {
+            value,
+            history: Vec::new(),
+        }
+    }
+
+    pub fn get(&self) -> &T {
+        &self.value
+    }
+
+    pub fn set(&mut self, value: T, reason: &str) -> Result<(), AccessError> {
+        self.update(reason, |current| *current = value)
+    }
+
+    // Changes the value in place. `f` isn't called if the reason is blank.
+    pub fn update<F: FnOnce(&mut T)>(&mut self, reason: &str, f: F) -> Result<(), AccessError> {
+        let reason = reason.trim();
+        if reason.is_empty() {
+            return Err(AccessError::MissingReason);
+        }
+        f(&mut self.value);
+        self.history.push(Change {
+            version: self.version() + 1,
+            reason: String::from(reason),
+        });
+        Ok(())
+    }
+
+    pub fn history(&self) -> &[Change] {
+        &self.history
+    }
+
+    // How many times the value has changed.
+    pub fn version(&self) -> u64 {
+        self.history.len() as u64
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for Audited<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Audited")
+            .field("value", &self.value)
+            .field("version", &self.version())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn records_each_change_with_its_reason() {
+        let mut limit = Audited::new(10);
+        assert_eq!(limit.set(20, "traffic spike"), Ok(()));
+        assert_eq!(limit.update("  double it ", |n| *n *= 2), Ok(()));
+        assert_eq!(*limit.get(), 40);
+        assert_eq!(
+            limit.history(),
+            [
+                Change {
+                    version: 1,
+                    reason: String::from("traffic spike")
+                },
+                Change {
+                    version: 2,
+                    reason: String::from("double it")
+                },
+            ]
+        );
+        assert_eq!(format!("{:?}", limit), "Audited { value: 40, version: 2 }");
+    }
+
+    #[test]
+    fn refuses_changes_without_a_reason() {
+        let mut name = Audited::new(String::from("a"));
+        let mut called = false;
+        assert_eq!(
+            name.update(" ", |_| called = true),
+            Err(AccessError::MissingReason)
+        );
+        assert_eq!(
+            name.set(String::from("b"), ""),
+            Err(AccessError::MissingReason)
+        );
+        assert!(!called);
+        assert_eq!(name.get(), "a");
+        assert_eq!(name.version(), 0);
+    }
+}
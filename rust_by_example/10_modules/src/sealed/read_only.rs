@@ -0,0 +1,64 @@
+use std::fmt;
+use std::ops::Deref;
+
+// A `ClosedBox` that can be looked into: anyone holding one gets `&T`, and
+// nothing hands out `&mut T` or the value itself.
+#[derive(Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Default)]
+pub struct ReadOnly<T> {
+    value: T,
+}
+
+impl<T> ReadOnly<T> {
+    pub fn new(value: T) -> ReadOnly<T> {
+        ReadOnly { value }
+    }
+
+    pub fn get(&self) -> &T {
+        &self.value
+    }
+}
+
+impl<T> Deref for ReadOnly<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.value
+    }
+}
+
+impl<T> From<T> for ReadOnly<T> {
+    fn from(value: T) -> ReadOnly<T> {
+        ReadOnly::new(value)
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for ReadOnly<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("ReadOnly").field(&self.value).finish()
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for ReadOnly<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.value.fmt(f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_through_shared_references_only() {
+        let name = ReadOnly::new(String::from("primary"));
+        assert_eq!(name.get(), "primary");
+        // `Deref` gives the contents' own `&self` methods.
+        assert_eq!(name.len(), 7);
+        assert_eq!(name.to_string(), "primary");
+        assert_eq!(format!("{:?}", name), "ReadOnly(\"primary\")");
+
+        let copy = name.clone();
+        assert_eq!(copy, name);
+        assert!(ReadOnly::from(3) < ReadOnly::from(4));
+    }
+}
@@ -0,0 +1,166 @@
+use std::fmt;
+use std::ptr;
+use std::sync::atomic::{Ordering, compiler_fence};
+
+// Overwrites a value's memory with zeros. The writes are volatile so the
+// compiler can't skip them just because the value is about to be freed.
+pub trait Zeroize {
+    fn zeroize(&mut self);
+}
+
+fn zero_bytes(bytes: &mut [u8]) {
+    for byte in bytes {
+        // SAFETY: `byte` is a valid, aligned, exclusive reference.
+        unsafe { ptr::write_volatile(byte, 0) };
+    }
+    compiler_fence(Ordering::SeqCst);
+}
+
+macro_rules! zeroize_integers {
+    ($($t:ty),*) => {
+        $(
+            impl Zeroize for $t {
+                fn zeroize(&mut self) {
+                    // SAFETY: `self` is a valid, aligned, exclusive reference.
+                    unsafe { ptr::write_volatile(self, 0) };
+                    compiler_fence(Ordering::SeqCst);
+                }
+            }
+        )*
+    };
+}
+
+zeroize_integers!(
+    u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize
+);
+
+impl<T: Zeroize, const N: usize> Zeroize for [T; N] {
+    fn zeroize(&mut self) {
+        for item in self {
+            item.zeroize();
+        }
+    }
+}
+
+// Only the bytes currently in use are cleared. Anything left behind by
+// earlier reallocations is out of reach, so build secrets at their final size.
+impl Zeroize for Vec<u8> {
+    fn zeroize(&mut self) {
+        zero_bytes(self);
+        self.clear();
+    }
+}
+
+impl Zeroize for String {
+    fn zeroize(&mut self) {
+        // SAFETY: all zero bytes are valid UTF-8, and the string is emptied
+        // straight after.
+        zero_bytes(unsafe { self.as_bytes_mut() });
+        self.clear();
+    }
+}
+
+// A value that never shows up in logs: `Debug` and `Display` print
+// `[REDACTED]`, it can't be cloned, and its memory is zeroed when dropped.
+// Reading it takes a call to `expose`, which is easy to search for.
+pub struct Secret<T: Zeroize> {
+    value: T,
+}
+
+impl<T: Zeroize> Secret<T> {
+    pub fn new(value: T) -> Secret<T> {
+        Secret { value }
+    }
+
+    pub fn expose(&self) -> &T {
+        &self.value
+    }
+}
+
+impl<T: Zeroize> Drop for Secret<T> {
+    fn drop(&mut self) {
+        self.value.zeroize();
+    }
+}
+
+impl<T: Zeroize> From<T> for Secret<T> {
+    fn from(value: T) -> Secret<T> {
+        Secret::new(value)
+    }
+}
+
+impl<T: Zeroize> fmt::Debug for Secret<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Secret([REDACTED])")
+    }
+}
+
+impl<T: Zeroize> fmt::Display for Secret<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "[REDACTED]")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    #[test]
+    fn never_prints_its_contents() {
+        let key = Secret::new(String::from("hunter2"));
+        assert_eq!(key.to_string(), "[REDACTED]");
+        assert_eq!(format!("{:?}", key), "Secret([REDACTED])");
+        assert_eq!(
+            format!("{:#?}", Some(&key)),
+            "Some(\n    Secret([REDACTED]),\n)"
+        );
+        assert_eq!(key.expose(), "hunter2");
+    }
+
+    #[test]
+    fn zeroizes_common_types() {
+        let mut text = String::from("password");
+        text.zeroize();
+        assert!(text.is_empty());
+
+        let mut bytes = vec![1u8, 2, 3];
+        bytes.zeroize();
+        assert!(bytes.is_empty());
+
+        let mut pin = [7u16, 3, 9];
+        pin.zeroize();
+        assert_eq!(pin, [0, 0, 0]);
+
+        let mut n = -5i64;
+        n.zeroize();
+        assert_eq!(n, 0);
+    }
+
+    // Remembers what its buffer held when it was zeroized.
+    struct Spy {
+        bytes: Vec<u8>,
+        seen: Rc<RefCell<Option<Vec<u8>>>>,
+    }
+
+    impl Zeroize for Spy {
+        fn zeroize(&mut self) {
+            zero_bytes(&mut self.bytes);
+            *self.seen.borrow_mut() = Some(self.bytes.clone());
+        }
+    }
+
+    #[test]
+    fn zeroizes_on_drop() {
+        let seen = Rc::new(RefCell::new(None));
+        let secret = Secret::new(Spy {
+            bytes: b"token".to_vec(),
+            seen: Rc::clone(&seen),
+        });
+        assert_eq!(secret.expose().bytes, b"token");
+        assert_eq!(*seen.borrow(), None);
+        drop(secret);
+        assert_eq!(*seen.borrow(), Some(vec![0; 5]));
+    }
+}
@@ -0,0 +1,66 @@
+use std::fmt;
+
+use super::AccessError;
+
+// Starts empty, takes one value, and is frozen from then on.
+#[derive(Clone, PartialEq, Eq)]
+pub struct WriteOnce<T> {
+    value: Option<T>,
+}
+
+impl<T> WriteOnce<T> {
+    pub fn new() -> WriteOnce<T> {
+        WriteOnce { value: None }
+    }
+
+    // Fails, leaving the first value in place, if a value was already set.
+    pub fn set(&mut self, value: T) -> Result<(), AccessError> {
+        if self.value.is_some() {
+            return Err(AccessError::AlreadySet);
+        }
+        self.value = Some(value);
+        Ok(())
+    }
+
+    pub fn get(&self) -> Option<&T> {
+        self.value.as_ref()
+    }
+
+    pub fn is_set(&self) -> bool {
+        self.value.is_some()
+    }
+}
+
+impl<T> Default for WriteOnce<T> {
+    fn default() -> WriteOnce<T> {
+        WriteOnce::new()
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for WriteOnce<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match &self.value {
+            Some(value) => f.debug_tuple("WriteOnce").field(value).finish(),
+            None => write!(f, "WriteOnce(<unset>)"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn freezes_after_the_first_set() {
+        let mut port = WriteOnce::new();
+        assert!(!port.is_set());
+        assert_eq!(port.get(), None);
+        assert_eq!(format!("{:?}", port), "WriteOnce(<unset>)");
+
+        assert_eq!(port.set(8080), Ok(()));
+        assert_eq!(port.set(9090), Err(AccessError::AlreadySet));
+        assert_eq!(port.get(), Some(&8080));
+        assert!(port.is_set());
+        assert_eq!(format!("{:?}", port), "WriteOnce(8080)");
+    }
+}
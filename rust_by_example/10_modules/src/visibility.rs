@@ -0,0 +1,1067 @@
+use std::collections::HashSet;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use syn::visit::{self, Visit};
+
+// Reads a crate's source with `syn`, rebuilds its module tree the way rustc
+// would (following `mod name;` to `name.rs` or `name/mod.rs`), and works out
+// for every item which modules are allowed to name it. This is the lesson's
+// `pub`, `pub(crate)`, `pub(super)`, `pub(self)` and `pub(in path)` applied
+// mechanically, including the rule that a private parent module limits
+// everything inside it and that `pub use` can widen an item's reach again.
+
+// A module as `crate::a::b`, one segment per entry.
+pub type ModPath = Vec<String>;
+
+pub fn display_path(path: &[String]) -> String {
+    path.join("::")
+}
+
+// Where something may be named from.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Scope {
+    // Any crate that depends on this one.
+    Everywhere,
+    // The module and all modules inside it.
+    Within(ModPath),
+}
+
+impl Scope {
+    pub fn contains(&self, module: &[String]) -> bool {
+        match self {
+            Scope::Everywhere => true,
+            Scope::Within(root) => module.starts_with(root),
+        }
+    }
+
+    fn covers(&self, other: &Scope) -> bool {
+        match other {
+            Scope::Everywhere => *self == Scope::Everywhere,
+            Scope::Within(root) => self.contains(root),
+        }
+    }
+
+    // The scopes along one path to an item are always nested, so their
+    // intersection is simply the narrowest of them.
+    fn narrowest(self, other: Scope) -> Option<Scope> {
+        if self.covers(&other) {
+            Some(other)
+        } else if other.covers(&self) {
+            Some(self)
+        } else {
+            None
+        }
+    }
+}
+
+impl fmt::Display for Scope {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Scope::Everywhere => write!(f, "everywhere"),
+            Scope::Within(root) => write!(f, "{}", display_path(root)),
+        }
+    }
+}
+
+// The set of modules an item can be named from, as the smallest list of
+// scopes that covers it. Empty means nowhere.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Reach {
+    scopes: Vec<Scope>,
+}
+
+impl Reach {
+    fn of(scope: Scope) -> Reach {
+        Reach {
+            scopes: vec![scope],
+        }
+    }
+
+    pub fn scopes(&self) -> &[Scope] {
+        &self.scopes
+    }
+
+    pub fn contains(&self, module: &[String]) -> bool {
+        self.scopes.iter().any(|s| s.contains(module))
+    }
+
+    pub fn is_everywhere(&self) -> bool {
+        self.scopes.contains(&Scope::Everywhere)
+    }
+
+    fn add(&mut self, scope: Scope) -> bool {
+        if self.scopes.iter().any(|s| s.covers(&scope)) {
+            return false;
+        }
+        self.scopes.retain(|s| !scope.covers(s));
+        self.scopes.push(scope);
+        self.scopes.sort_by_key(|s| s.to_string());
+        true
+    }
+
+    fn union(&mut self, other: &Reach) -> bool {
+        let mut changed = false;
+        for scope in &other.scopes {
+            changed |= self.add(scope.clone());
+        }
+        changed
+    }
+
+    fn intersect(&self, other: &Reach) -> Reach {
+        let mut out = Reach::default();
+        for a in &self.scopes {
+            for b in &other.scopes {
+                if let Some(s) = a.clone().narrowest(b.clone()) {
+                    out.add(s);
+                }
+            }
+        }
+        out
+    }
+}
+
+impl fmt::Display for Reach {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.scopes.is_empty() {
+            return write!(f, "nowhere");
+        }
+        let scopes: Vec<String> = self.scopes.iter().map(|s| s.to_string()).collect();
+        write!(f, "{}", scopes.join(", "))
+    }
+}
+
+// Visibility as written in the source.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Declared {
+    Private,
+    Pub,
+    Crate,
+    Super,
+    SelfOnly,
+    In(String),
+}
+
+impl Declared {
+    fn from_syn(vis: &syn::Visibility) -> Declared {
+        match vis {
+            syn::Visibility::Public(_) => Declared::Pub,
+            syn::Visibility::Inherited => Declared::Private,
+            syn::Visibility::Restricted(r) => {
+                let path = syn_path(&r.path);
+                match (r.in_token.is_some(), path.as_str()) {
+                    (false, "crate") => Declared::Crate,
+                    (false, "super") => Declared::Super,
+                    (false, "self") => Declared::SelfOnly,
+                    _ => Declared::In(path),
+                }
+            }
+        }
+    }
+
+    // The scope this visibility grants, for an item declared in `module`.
+    fn scope(&self, module: &[String]) -> Scope {
+        let parent = || module[..module.len().saturating_sub(1).max(1)].to_vec();
+        match self {
+            Declared::Private | Declared::SelfOnly => Scope::Within(module.to_vec()),
+            Declared::Pub => Scope::Everywhere,
+            Declared::Crate => Scope::Within(vec![String::from("crate")]),
+            Declared::Super => Scope::Within(parent()),
+            Declared::In(path) => {
+                Scope::Within(resolve_relative(module, path).unwrap_or_else(|| module.to_vec()))
+            }
+        }
+    }
+}
+
+impl fmt::Display for Declared {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Declared::Private => write!(f, "private"),
+            Declared::Pub => write!(f, "pub"),
+            Declared::Crate => write!(f, "pub(crate)"),
+            Declared::Super => write!(f, "pub(super)"),
+            Declared::SelfOnly => write!(f, "pub(self)"),
+            Declared::In(path) => write!(f, "pub(in {})", path),
+        }
+    }
+}
+
+// Turns a path that starts with `crate`, `self` or `super` into a module path.
+fn resolve_relative(module: &[String], path: &str) -> Option<ModPath> {
+    let mut segments = path.split("::");
+    let mut out: ModPath = match segments.next()? {
+        "crate" => vec![String::from("crate")],
+        "self" => module.to_vec(),
+        "super" if module.len() > 1 => module[..module.len() - 1].to_vec(),
+        _ => return None,
+    };
+    for segment in segments {
+        match segment {
+            "super" if out.len() > 1 => {
+                out.pop();
+            }
+            "super" => return None,
+            "self" => {}
+            name => out.push(String::from(name)),
+        }
+    }
+    Some(out)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ItemKind {
+    Module,
+    Function,
+    Struct,
+    Enum,
+    Union,
+    Trait,
+    Const,
+    Static,
+    TypeAlias,
+    // A named field of a struct.
+    Field,
+    // A function, const or type in an inherent `impl` block.
+    Associated,
+}
+
+impl fmt::Display for ItemKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let word = match self {
+            ItemKind::Module => "mod",
+            ItemKind::Function => "fn",
+            ItemKind::Struct => "struct",
+            ItemKind::Enum => "enum",
+            ItemKind::Union => "union",
+            ItemKind::Trait => "trait",
+            ItemKind::Const => "const",
+            ItemKind::Static => "static",
+            ItemKind::TypeAlias => "type",
+            ItemKind::Field => "field",
+            ItemKind::Associated => "assoc",
+        };
+        write!(f, "{}", word)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Item {
+    pub kind: ItemKind,
+    pub name: String,
+    // For fields and associated items, the type they belong to.
+    pub owner: Option<String>,
+    // The module the item is declared in. For a module, its parent.
+    pub module: ModPath,
+    pub declared: Declared,
+    pub reach: Reach,
+    // Declared `pub`, but not reachable from outside the crate, not named
+    // anywhere else in it and not re-exported.
+    pub unused_pub: bool,
+}
+
+impl Item {
+    // `crate::my::ClosedBox::new`, `crate::my::OpenBox.contents`.
+    pub fn path(&self) -> String {
+        let module = display_path(&self.module);
+        match (&self.owner, self.kind) {
+            (Some(owner), ItemKind::Field) => format!("{}::{}.{}", module, owner, self.name),
+            (Some(owner), _) => format!("{}::{}::{}", module, owner, self.name),
+            (None, _) => format!("{}::{}", module, self.name),
+        }
+    }
+
+    fn own_path(&self) -> ModPath {
+        let mut path = self.module.clone();
+        path.push(self.name.clone());
+        path
+    }
+}
+
+#[derive(Debug)]
+pub enum InspectError {
+    Io { file: PathBuf, error: io::Error },
+    Parse { file: PathBuf, message: String },
+    MissingModule { module: String, tried: Vec<PathBuf> },
+}
+
+impl fmt::Display for InspectError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            InspectError::Io { file, error } => write!(f, "{}: {}", file.display(), error),
+            InspectError::Parse { file, message } => write!(f, "{}: {}", file.display(), message),
+            InspectError::MissingModule { module, tried } => {
+                let tried: Vec<String> = tried.iter().map(|p| p.display().to_string()).collect();
+                write!(
+                    f,
+                    "no file for module `{}` (tried {})",
+                    module,
+                    tried.join(", ")
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for InspectError {}
+
+// A `pub use` (or narrower) found while loading.
+struct Reexport {
+    module: ModPath,
+    declared: Declared,
+    target: ModPath,
+    name: Option<String>,
+}
+
+#[derive(Debug)]
+pub struct Crate {
+    root: PathBuf,
+    library: bool,
+    modules: Vec<ModPath>,
+    items: Vec<Item>,
+}
+
+impl Crate {
+    // A crate whose root is `lib.rs` is a library, so its `pub` items can be
+    // reached from other crates. Any other root is treated as a binary.
+    pub fn load(root: &Path) -> Result<Crate, InspectError> {
+        let library = root.file_name().is_some_and(|name| name == "lib.rs");
+        Crate::load_with(root, library, |file| fs::read_to_string(file))
+    }
+
+    pub fn load_with<F>(root: &Path, library: bool, read: F) -> Result<Crate, InspectError>
+    where
+        F: Fn(&Path) -> io::Result<String>,
+    {
+        let mut loader = Loader {
+            read: &read,
+            modules: Vec::new(),
+            items: Vec::new(),
+            reexports: Vec::new(),
+            used: HashSet::new(),
+        };
+        let file = loader.parse(root)?;
+        let dir = root.parent().unwrap_or(Path::new("")).to_path_buf();
+        loader.module(vec![String::from("crate")], root, &file.items, &dir)?;
+
+        let Loader {
+            modules,
+            mut items,
+            reexports,
+            used,
+            ..
+        } = loader;
+        compute_reach(&mut items, &reexports, library);
+        for item in &mut items {
+            let exported = library && item.reach.is_everywhere();
+            let reexported = reexports.iter().any(|r| {
+                r.target == item.module
+                    && r.declared != Declared::Private
+                    && r.name.as_ref().is_none_or(|n| *n == item.name)
+            });
+            item.unused_pub = item.declared == Declared::Pub
+                && !exported
+                && !reexported
+                && !used.contains(&item.name);
+        }
+        Ok(Crate {
+            root: root.to_path_buf(),
+            library,
+            modules,
+            items,
+        })
+    }
+
+    pub fn modules(&self) -> &[ModPath] {
+        &self.modules
+    }
+
+    pub fn items(&self) -> &[Item] {
+        &self.items
+    }
+
+    // The first item with this path, as printed by `Item::path`.
+    pub fn item(&self, path: &str) -> Option<&Item> {
+        self.items.iter().find(|item| item.path() == path)
+    }
+
+    pub fn unused_pub(&self) -> impl Iterator<Item = &Item> {
+        self.items.iter().filter(|item| item.unused_pub)
+    }
+
+    fn module_item(&self, module: &[String]) -> Option<&Item> {
+        self.items
+            .iter()
+            .find(|item| item.kind == ItemKind::Module && item.own_path() == module)
+    }
+
+    // Every module, indented under its parent, with its items listed as
+    // `kind name  declared -> reachable from`, then the unused `pub` items.
+    pub fn to_text(&self) -> String {
+        let kind = if self.library { "library" } else { "binary" };
+        let mut out = format!("crate ({}, {})\n", self.root.display(), kind);
+        for module in &self.modules {
+            let depth = module.len();
+            if let Some(item) = self.module_item(module) {
+                out.push_str(&format!(
+                    "{}mod {}  {} -> {}\n",
+                    "  ".repeat(depth - 1),
+                    item.name,
+                    item.declared,
+                    item.reach
+                ));
+            }
+            let items = self
+                .items
+                .iter()
+                .filter(|item| item.module == *module && item.kind != ItemKind::Module);
+            for item in items {
+                let name = match (&item.owner, item.kind) {
+                    (Some(owner), ItemKind::Field) => format!("{}.{}", owner, item.name),
+                    (Some(owner), _) => format!("{}::{}", owner, item.name),
+                    (None, _) => item.name.clone(),
+                };
+                out.push_str(&format!(
+                    "{}{} {}  {} -> {}{}\n",
+                    "  ".repeat(depth),
+                    item.kind,
+                    name,
+                    item.declared,
+                    item.reach,
+                    if item.unused_pub {
+                        "  [unused pub]"
+                    } else {
+                        ""
+                    }
+                ));
+            }
+        }
+        let unused: Vec<String> = self.unused_pub().map(|item| item.path()).collect();
+        if !unused.is_empty() {
+            out.push_str("\nunused pub items:\n");
+            for path in unused {
+                out.push_str(&format!("  {}\n", path));
+            }
+        }
+        out
+    }
+
+    // A Graphviz graph: modules are boxes, items are ellipses, exported items
+    // are green and unused `pub` items red.
+    pub fn to_dot(&self) -> String {
+        let mut out = String::from("digraph modules {\n    rankdir=LR;\n    node [shape=box];\n");
+        for module in &self.modules {
+            let id = display_path(module);
+            let label = match self.module_item(module) {
+                Some(item) => format!("mod {}\\n{} -> {}", item.name, item.declared, item.reach),
+                None => String::from("crate"),
+            };
+            out.push_str(&format!("    {} [label={}];\n", quote(&id), quote(&label)));
+            if module.len() > 1 {
+                let parent = display_path(&module[..module.len() - 1]);
+                out.push_str(&format!("    {} -> {};\n", quote(&parent), quote(&id)));
+            }
+        }
+        for (i, item) in self.items.iter().enumerate() {
+            if item.kind == ItemKind::Module {
+                continue;
+            }
+            let id = format!("item{}", i);
+            let color = if item.unused_pub {
+                "red"
+            } else if item.reach.is_everywhere() {
+                "darkgreen"
+            } else {
+                "black"
+            };
+            let label = format!(
+                "{} {}\\n{} -> {}",
+                item.kind,
+                item.path().rsplit("::").next().unwrap_or_default(),
+                item.declared,
+                item.reach
+            );
+            out.push_str(&format!(
+                "    {} [shape=ellipse, color={}, label={}];\n",
+                id,
+                color,
+                quote(&label)
+            ));
+            out.push_str(&format!(
+                "    {} -> {};\n",
+                quote(&display_path(&item.module)),
+                id
+            ));
+        }
+        out.push_str("}\n");
+        out
+    }
+}
+
+fn quote(text: &str) -> String {
+    format!("\"{}\"", text.replace('"', "\\\""))
+}
+
+fn syn_path(path: &syn::Path) -> String {
+    let segments: Vec<String> = path.segments.iter().map(|s| s.ident.to_string()).collect();
+    segments.join("::")
+}
+
+fn is_cfg_test(attrs: &[syn::Attribute]) -> bool {
+    attrs.iter().any(|attr| {
+        attr.path().is_ident("cfg")
+            && matches!(&attr.meta, syn::Meta::List(list) if list.tokens.to_string() == "test")
+    })
+}
+
+fn path_attr(attrs: &[syn::Attribute]) -> Option<String> {
+    attrs.iter().find_map(|attr| match &attr.meta {
+        syn::Meta::NameValue(nv) if nv.path.is_ident("path") => match &nv.value {
+            syn::Expr::Lit(syn::ExprLit {
+                lit: syn::Lit::Str(s),
+                ..
+            }) => Some(s.value()),
+            _ => None,
+        },
+        _ => None,
+    })
+}
+
+struct Loader<'a> {
+    read: &'a dyn Fn(&Path) -> io::Result<String>,
+    modules: Vec<ModPath>,
+    items: Vec<Item>,
+    reexports: Vec<Reexport>,
+    // Every name used in a path, method call, field access or macro.
+    used: HashSet<String>,
+}
+
+impl Loader<'_> {
+    fn parse(&mut self, file: &Path) -> Result<syn::File, InspectError> {
+        let source = (self.read)(file).map_err(|error| InspectError::Io {
+            file: file.to_path_buf(),
+            error,
+        })?;
+        let parsed = syn::parse_file(&source).map_err(|e| InspectError::Parse {
+            file: file.to_path_buf(),
+            message: e.to_string(),
+        })?;
+        let mut uses = Uses(&mut self.used);
+        uses.visit_file(&parsed);
+        Ok(parsed)
+    }
+
+    // `dir` is where files for `mod name;` declared in this module live.
+    fn module(
+        &mut self,
+        path: ModPath,
+        file: &Path,
+        items: &[syn::Item],
+        dir: &Path,
+    ) -> Result<(), InspectError> {
+        self.modules.push(path.clone());
+        for item in items {
+            self.item(&path, file, item, dir)?;
+        }
+        Ok(())
+    }
+
+    fn push(&mut self, kind: ItemKind, name: String, module: &[String], vis: &syn::Visibility) {
+        self.items.push(Item {
+            kind,
+            name,
+            owner: None,
+            module: module.to_vec(),
+            declared: Declared::from_syn(vis),
+            reach: Reach::default(),
+            unused_pub: false,
+        });
+    }
+
+    fn item(
+        &mut self,
+        module: &ModPath,
+        file: &Path,
+        item: &syn::Item,
+        dir: &Path,
+    ) -> Result<(), InspectError> {
+        match item {
+            syn::Item::Mod(m) if !is_cfg_test(&m.attrs) => {
+                let name = m.ident.to_string();
+                self.push(ItemKind::Module, name.clone(), module, &m.vis);
+                let mut child = module.clone();
+                child.push(name.clone());
+                match &m.content {
+                    Some((_, items)) => self.module(child, file, items, &dir.join(&name))?,
+                    None => {
+                        let tried = match path_attr(&m.attrs) {
+                            Some(p) => vec![file.parent().unwrap_or(Path::new("")).join(p)],
+                            None => vec![
+                                dir.join(format!("{}.rs", name)),
+                                dir.join(&name).join("mod.rs"),
+                            ],
+                        };
+                        let found = tried.iter().find(|p| (self.read)(p).is_ok()).cloned();
+                        let Some(child_file) = found else {
+                            return Err(InspectError::MissingModule {
+                                module: display_path(&child),
+                                tried,
+                            });
+                        };
+                        let parsed = self.parse(&child_file)?;
+                        // `a/mod.rs` keeps its children beside it, `a.rs`
+                        // keeps them in `a/`.
+                        let child_dir = if child_file.file_name().is_some_and(|f| f == "mod.rs") {
+                            child_file.parent().unwrap_or(Path::new("")).to_path_buf()
+                        } else {
+                            dir.join(&name)
+                        };
+                        self.module(child, &child_file, &parsed.items, &child_dir)?;
+                    }
+                }
+            }
+            syn::Item::Fn(f) if !is_cfg_test(&f.attrs) => {
+                self.push(ItemKind::Function, f.sig.ident.to_string(), module, &f.vis)
+            }
+            syn::Item::Struct(s) => {
+                let name = s.ident.to_string();
+                self.push(ItemKind::Struct, name.clone(), module, &s.vis);
+                for field in &s.fields {
+                    if let Some(ident) = &field.ident {
+                        self.push(ItemKind::Field, ident.to_string(), module, &field.vis);
+                        self.items.last_mut().unwrap().owner = Some(name.clone());
+                    }
+                }
+            }
+            syn::Item::Enum(e) => self.push(ItemKind::Enum, e.ident.to_string(), module, &e.vis),
+            syn::Item::Union(u) => self.push(ItemKind::Union, u.ident.to_string(), module, &u.vis),
+            syn::Item::Trait(t) => self.push(ItemKind::Trait, t.ident.to_string(), module, &t.vis),
+            syn::Item::Const(c) => self.push(ItemKind::Const, c.ident.to_string(), module, &c.vis),
+            syn::Item::Static(s) => {
+                self.push(ItemKind::Static, s.ident.to_string(), module, &s.vis)
+            }
+            syn::Item::Type(t) => {
+                self.push(ItemKind::TypeAlias, t.ident.to_string(), module, &t.vis)
+            }
+            // Only inherent impls: trait impl items take the trait's visibility.
+            syn::Item::Impl(i) if i.trait_.is_none() && !is_cfg_test(&i.attrs) => {
+                let syn::Type::Path(ty) = &*i.self_ty else {
+                    return Ok(());
+                };
+                let Some(owner) = ty.path.segments.last().map(|s| s.ident.to_string()) else {
+                    return Ok(());
+                };
+                for impl_item in &i.items {
+                    let (name, vis) = match impl_item {
+                        syn::ImplItem::Fn(f) => (f.sig.ident.to_string(), &f.vis),
+                        syn::ImplItem::Const(c) => (c.ident.to_string(), &c.vis),
+                        syn::ImplItem::Type(t) => (t.ident.to_string(), &t.vis),
+                        _ => continue,
+                    };
+                    self.push(ItemKind::Associated, name, module, vis);
+                    self.items.last_mut().unwrap().owner = Some(owner.clone());
+                }
+            }
+            syn::Item::Use(u) => {
+                let declared = Declared::from_syn(&u.vis);
+                self.use_tree(module, &declared, Vec::new(), &u.tree);
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    fn use_tree(
+        &mut self,
+        module: &ModPath,
+        declared: &Declared,
+        prefix: Vec<String>,
+        tree: &syn::UseTree,
+    ) {
+        let target = |prefix: &[String]| resolve_use(module, prefix);
+        match tree {
+            syn::UseTree::Path(p) => {
+                let mut prefix = prefix;
+                prefix.push(p.ident.to_string());
+                self.use_tree(module, declared, prefix, &p.tree);
+            }
+            syn::UseTree::Name(n) => {
+                if let Some(target) = target(&prefix) {
+                    self.reexports.push(Reexport {
+                        module: module.clone(),
+                        declared: declared.clone(),
+                        target,
+                        name: Some(n.ident.to_string()),
+                    });
+                }
+            }
+            syn::UseTree::Rename(r) => {
+                if let Some(target) = target(&prefix) {
+                    self.reexports.push(Reexport {
+                        module: module.clone(),
+                        declared: declared.clone(),
+                        target,
+                        name: Some(r.ident.to_string()),
+                    });
+                }
+            }
+            syn::UseTree::Glob(_) => {
+                if let Some(target) = target(&prefix) {
+                    self.reexports.push(Reexport {
+                        module: module.clone(),
+                        declared: declared.clone(),
+                        target,
+                        name: None,
+                    });
+                }
+            }
+            syn::UseTree::Group(g) => {
+                for tree in &g.items {
+                    self.use_tree(module, declared, prefix.clone(), tree);
+                }
+            }
+        }
+    }
+}
+
+// The module a `use` prefix points into. A path that doesn't start with
+// `crate`, `self` or `super` is taken as relative to the current module, so
+// paths into other crates, like `std::fmt`, end up naming a module that
+// doesn't exist and match no items.
+fn resolve_use(module: &[String], prefix: &[String]) -> Option<ModPath> {
+    let path = match prefix.first().map(String::as_str) {
+        Some("crate" | "self" | "super") => prefix.join("::"),
+        Some(_) => format!("self::{}", prefix.join("::")),
+        None => String::from("self"),
+    };
+    resolve_relative(module, &path)
+}
+
+// Reach along the item's own path: every enclosing module's visibility and
+// its own. Fields and associated items are also limited by their type.
+fn compute_reach(items: &mut [Item], reexports: &[Reexport], library: bool) {
+    let top = if library {
+        Scope::Everywhere
+    } else {
+        Scope::Within(vec![String::from("crate")])
+    };
+    let module_scope = |items: &[Item], module: &[String]| -> Option<Scope> {
+        items
+            .iter()
+            .find(|i| i.kind == ItemKind::Module && i.own_path() == module)
+            .map(|i| i.declared.scope(&i.module))
+    };
+
+    let mut reaches = Vec::with_capacity(items.len());
+    for item in items.iter() {
+        let mut scope = Some(top.clone());
+        for depth in 2..=item.module.len() {
+            let enclosing = module_scope(items, &item.module[..depth]);
+            scope = scope.and_then(|s| match enclosing {
+                Some(e) => s.narrowest(e),
+                None => Some(s),
+            });
+        }
+        scope = scope.and_then(|s| s.narrowest(item.declared.scope(&item.module)));
+        reaches.push(scope.map(Reach::of).unwrap_or_default());
+    }
+
+    // Re-exports add paths; repeat until re-exports of re-exports settle.
+    let mut changed = true;
+    while changed {
+        changed = false;
+        for r in reexports.iter().filter(|r| r.declared != Declared::Private) {
+            let mut via = Some(top.clone());
+            for depth in 2..=r.module.len() {
+                let enclosing = module_scope(items, &r.module[..depth]);
+                via = via.and_then(|s| match enclosing {
+                    Some(e) => s.narrowest(e),
+                    None => Some(s),
+                });
+            }
+            let Some(via) = via.and_then(|s| s.narrowest(r.declared.scope(&r.module))) else {
+                continue;
+            };
+            for i in 0..items.len() {
+                let item = &items[i];
+                let named = r.name.as_ref().is_none_or(|n| *n == item.name);
+                if item.owner.is_none() && item.module == r.target && named {
+                    // A re-export can't make an item visible beyond what the
+                    // item itself allows.
+                    let allowed = Reach::of(item.declared.scope(&item.module));
+                    let extra = Reach::of(via.clone()).intersect(&allowed);
+                    changed |= reaches[i].union(&extra);
+                }
+            }
+        }
+    }
+
+    // Fields and associated items are reached through their type rather than
+    // through the module path, so they go wherever the type does, as far as
+    // their own visibility allows. A type defined elsewhere than its `impl`
+    // isn't found, and the module path is used instead.
+    for i in 0..items.len() {
+        let Some(owner) = &items[i].owner else {
+            continue;
+        };
+        let owner_reach = items.iter().enumerate().find_map(|(j, other)| {
+            (other.owner.is_none() && other.module == items[i].module && other.name == *owner)
+                .then(|| reaches[j].clone())
+        });
+        if let Some(owner_reach) = owner_reach {
+            let own = Reach::of(items[i].declared.scope(&items[i].module));
+            reaches[i] = owner_reach.intersect(&own);
+        }
+    }
+
+    for (item, reach) in items.iter_mut().zip(reaches) {
+        item.reach = reach;
+    }
+}
+
+struct Uses<'a>(&'a mut HashSet<String>);
+
+impl Uses<'_> {
+    fn tokens(&mut self, tokens: proc_macro2::TokenStream) {
+        for token in tokens {
+            match token {
+                proc_macro2::TokenTree::Ident(ident) => {
+                    self.0.insert(ident.to_string());
+                }
+                proc_macro2::TokenTree::Group(group) => self.tokens(group.stream()),
+                _ => {}
+            }
+        }
+    }
+}
+
+impl<'ast> Visit<'ast> for Uses<'_> {
+    fn visit_path_segment(&mut self, segment: &'ast syn::PathSegment) {
+        self.0.insert(segment.ident.to_string());
+        visit::visit_path_segment(self, segment);
+    }
+
+    fn visit_expr_method_call(&mut self, call: &'ast syn::ExprMethodCall) {
+        self.0.insert(call.method.to_string());
+        visit::visit_expr_method_call(self, call);
+    }
+
+    fn visit_member(&mut self, member: &'ast syn::Member) {
+        if let syn::Member::Named(ident) = member {
+            self.0.insert(ident.to_string());
+        }
+    }
+
+    fn visit_use_name(&mut self, name: &'ast syn::UseName) {
+        self.0.insert(name.ident.to_string());
+    }
+
+    fn visit_use_rename(&mut self, rename: &'ast syn::UseRename) {
+        self.0.insert(rename.ident.to_string());
+    }
+
+    fn visit_use_path(&mut self, path: &'ast syn::UsePath) {
+        self.0.insert(path.ident.to_string());
+        visit::visit_use_path(self, path);
+    }
+
+    // Macro arguments aren't parsed, so any name in them counts as used.
+    fn visit_macro(&mut self, mac: &'ast syn::Macro) {
+        self.tokens(mac.tokens.clone());
+        visit::visit_macro(self, mac);
+    }
+
+    // `pub(in crate::a)` names a module but doesn't use it.
+    fn visit_visibility(&mut self, _: &'ast syn::Visibility) {}
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn this_crate(file: &str) -> Crate {
+        Crate::load(&Path::new(env!("CARGO_MANIFEST_DIR")).join("src").join(file)).unwrap()
+    }
+
+    fn reach(krate: &Crate, path: &str) -> String {
+        match krate.item(path) {
+            Some(item) => item.reach.to_string(),
+            None => panic!("no item {path}"),
+        }
+    }
+
+    fn in_memory(files: &[(&str, &str)]) -> Result<Crate, InspectError> {
+        let files: HashMap<PathBuf, String> = files
+            .iter()
+            .map(|(path, source)| (PathBuf::from(path), String::from(*source)))
+            .collect();
+        let root = PathBuf::from(files.keys().find(|p| p.ends_with("lib.rs")).unwrap());
+        Crate::load_with(&root, true, |path| {
+            files
+                .get(path)
+                .cloned()
+                .ok_or_else(|| io::Error::from(io::ErrorKind::NotFound))
+        })
+    }
+
+    #[test]
+    fn matches_the_lessons_visibility_matrix() {
+        let krate = this_crate("main.rs");
+        let expected = [
+            ("crate::my_mod::private_function", "crate::my_mod"),
+            ("crate::my_mod::function", "crate"),
+            ("crate::my_mod::public_function_in_crate", "crate"),
+            ("crate::my_mod::nested::function", "crate"),
+            (
+                "crate::my_mod::nested::private_function",
+                "crate::my_mod::nested",
+            ),
+            (
+                "crate::my_mod::nested::public_function_in_my_mod",
+                "crate::my_mod",
+            ),
+            (
+                "crate::my_mod::nested::public_function_in_nested",
+                "crate::my_mod::nested",
+            ),
+            (
+                "crate::my_mod::nested::public_function_in_super_mod",
+                "crate::my_mod",
+            ),
+            // A private parent caps `pub` and `pub(crate)` alike.
+            ("crate::my_mod::private_nested::function", "crate::my_mod"),
+            (
+                "crate::my_mod::private_nested::restricted_function",
+                "crate::my_mod",
+            ),
+            ("crate::my::OpenBox.contents", "crate"),
+            ("crate::my::ClosedBox.contents", "crate::my"),
+            ("crate::my::ClosedBox::new", "crate"),
+            ("crate::my::function", "crate::my"),
+            ("crate::my::cool::function", "crate::my"),
+            ("crate::deeply::nested::function", "crate"),
+        ];
+        for (path, want) in expected {
+            assert_eq!(reach(&krate, path), want, "{path}");
+        }
+        let declared = |path| krate.item(path).unwrap().declared.to_string();
+        assert_eq!(
+            declared("crate::my_mod::nested::public_function_in_my_mod"),
+            "pub(in crate::my_mod)"
+        );
+        assert_eq!(
+            declared("crate::my_mod::nested::public_function_in_nested"),
+            "pub(self)"
+        );
+    }
+
+    #[test]
+    fn follows_module_files() {
+        let krate = this_crate("split.rs");
+        let modules: Vec<String> = krate.modules().iter().map(|m| display_path(m)).collect();
+        assert_eq!(
+            modules,
+            [
+                "crate",
+                "crate::my",
+                "crate::my::inaccessible",
+                "crate::my::nested"
+            ]
+        );
+        assert_eq!(
+            reach(&krate, "crate::my::inaccessible::public_function"),
+            "crate::my"
+        );
+        assert_eq!(reach(&krate, "crate::my::nested::function"), "crate");
+        let unused: Vec<String> = krate.unused_pub().map(|item| item.path()).collect();
+        assert_eq!(unused, ["crate::my::inaccessible::public_function"]);
+        assert!(
+            krate
+                .to_text()
+                .ends_with("unused pub items:\n  crate::my::inaccessible::public_function\n")
+        );
+    }
+
+    #[test]
+    fn reexports_widen_reach() {
+        let krate = this_crate("lib.rs");
+        // `read_only` is private, but `sealed` re-exports its type.
+        assert_eq!(
+            reach(&krate, "crate::sealed::read_only::ReadOnly"),
+            "everywhere"
+        );
+        assert_eq!(
+            reach(&krate, "crate::sealed::read_only::ReadOnly::get"),
+            "everywhere"
+        );
+        assert_eq!(
+            reach(&krate, "crate::sealed::audited::Change.reason"),
+            "everywhere"
+        );
+        assert_eq!(
+            reach(&krate, "crate::sealed::secret::Secret.value"),
+            "crate::sealed::secret"
+        );
+        assert_eq!(
+            reach(&krate, "crate::sealed::secret::zero_bytes"),
+            "crate::sealed::secret"
+        );
+        assert_eq!(krate.unused_pub().count(), 0);
+    }
+
+    #[test]
+    fn resolves_mod_rs_and_restricted_paths() {
+        let krate = in_memory(&[
+            ("src/lib.rs", "mod a; pub use a::b::Deep as Exported;"),
+            ("src/a/mod.rs", "pub mod b; pub(crate) use b::Hidden;"),
+            (
+                "src/a/b.rs",
+                "pub struct Deep; pub struct Hidden; pub struct Lonely;
+                 pub(in crate::a) fn helper() {}
+                 pub(super) const LIMIT: u32 = 1;
+                 #[cfg(test)] mod tests { pub fn skipped() {} }",
+            ),
+        ])
+        .unwrap();
+        assert_eq!(reach(&krate, "crate::a::b::Deep"), "everywhere");
+        assert_eq!(reach(&krate, "crate::a::b::Hidden"), "crate");
+        assert_eq!(reach(&krate, "crate::a::b::Lonely"), "crate");
+        assert_eq!(reach(&krate, "crate::a::b::helper"), "crate::a");
+        assert_eq!(reach(&krate, "crate::a::b::LIMIT"), "crate::a");
+        assert!(krate.item("crate::a::b::tests::skipped").is_none());
+        let unused: Vec<String> = krate.unused_pub().map(|item| item.path()).collect();
+        assert_eq!(unused, ["crate::a::b::Lonely"]);
+    }
+
+    #[test]
+    fn reports_missing_module_files() {
+        let error = in_memory(&[("src/lib.rs", "mod gone;")]).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "no file for module `crate::gone` (tried src/gone.rs, src/gone/mod.rs)"
+        );
+        let error = in_memory(&[("src/lib.rs", "fn (")]).unwrap_err();
+        assert!(matches!(error, InspectError::Parse { .. }));
+    }
+
+    #[test]
+    fn dot_output() {
+        let krate = in_memory(&[("src/lib.rs", "mod m { pub fn f() {} }")]).unwrap();
+        assert_eq!(
+            krate.to_dot(),
+            r#"digraph modules {
+    rankdir=LR;
+    node [shape=box];
+    "crate" [label="crate"];
+    "crate::m" [label="mod m\nprivate -> crate"];
+    "crate" -> "crate::m";
+    item1 [shape=ellipse, color=red, label="fn f\npub -> crate"];
+    "crate::m" -> item1;
+}
+"#
+        );
+    }
+}
@@ -1,5 +1,62 @@
-pub fn add(left: u64, right: u64) -> u64 {
-    left + right
+// Addition that reports overflow instead of panicking or wrapping. Implemented
+// for every primitive integer and float, so callers can stay generic over the
+// number type.
+pub trait Adder: Copy {
+    const ZERO: Self;
+
+    // `None` if the sum doesn't fit. For floats that means it isn't finite.
+    fn checked_add(self, other: Self) -> Option<Self>;
+
+    fn add(self, other: Self) -> Self {
+        self.checked_add(other)
+            .expect("attempt to add with overflow")
+    }
+}
+
+macro_rules! impl_adder_for_integers {
+    ($($t:ty),*) => {
+        $(
+            impl Adder for $t {
+                const ZERO: $t = 0;
+
+                fn checked_add(self, other: $t) -> Option<$t> {
+                    <$t>::checked_add(self, other)
+                }
+            }
+        )*
+    };
+}
+
+impl_adder_for_integers!(
+    u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize
+);
+
+macro_rules! impl_adder_for_floats {
+    ($($t:ty),*) => {
+        $(
+            impl Adder for $t {
+                const ZERO: $t = 0.0;
+
+                fn checked_add(self, other: $t) -> Option<$t> {
+                    let sum = self + other;
+                    sum.is_finite().then_some(sum)
+                }
+            }
+        )*
+    };
+}
+
+impl_adder_for_floats!(f32, f64);
+
+pub fn add<T: Adder>(left: T, right: T) -> T {
+    left.add(right)
+}
+
+// Adds up every value, or `None` as soon as the total overflows.
+pub fn sum<T: Adder, I: IntoIterator<Item = T>>(values: I) -> Option<T> {
+    values
+        .into_iter()
+        .try_fold(T::ZERO, |total, value| total.checked_add(value))
 }
 
 #[cfg(test)]
@@ -11,4 +68,27 @@ mod tests {
         let result = add(2, 2);
         assert_eq!(result, 4);
     }
+
+    #[test]
+    fn reports_overflow() {
+        assert_eq!(Adder::checked_add(u8::MAX, 1), None);
+        assert_eq!(Adder::checked_add(i64::MIN, -1), None);
+        assert_eq!(Adder::checked_add(-3i32, 5), Some(2));
+        assert_eq!(Adder::checked_add(f64::MAX, f64::MAX), None);
+        assert_eq!(Adder::checked_add(1.5f32, 2.0), Some(3.5));
+    }
+
+    #[test]
+    #[should_panic(expected = "attempt to add with overflow")]
+    fn add_panics_on_overflow() {
+        add(u64::MAX, 1);
+    }
+
+    #[test]
+    fn sums_any_number_type() {
+        assert_eq!(sum([1u64, 2, 3]), Some(6));
+        assert_eq!(sum(Vec::<i8>::new()), Some(0));
+        assert_eq!(sum([100i8, 27, 1]), None);
+        assert_eq!(sum([0.25, 0.5]), Some(0.75));
+    }
 }
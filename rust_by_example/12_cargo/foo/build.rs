@@ -1,3 +1,114 @@
+use std::env;
+use std::fmt::Write;
+use std::fs;
+use std::path::Path;
+
+const AMOUNTS: &str = "data/amounts.txt";
+// Names whose constant would clash with one the generated file always defines.
+const RESERVED: &[&str] = &["all"];
+
 fn main() {
-    println!("cargo:warning=The build script is running right now!");
+    println!("cargo:rerun-if-changed=build.rs");
+    println!("cargo:rerun-if-changed={}", AMOUNTS);
+
+    let out_dir = env::var("OUT_DIR").expect("cargo sets OUT_DIR");
+    let out_dir = Path::new(&out_dir);
+
+    let data =
+        fs::read_to_string(AMOUNTS).unwrap_or_else(|e| panic!("can't read {}: {}", AMOUNTS, e));
+    let amounts = parse_amounts(&data).unwrap_or_else(|e| panic!("{}:{}", AMOUNTS, e));
+    write(&out_dir.join("amounts.rs"), &amounts_source(&amounts));
+    write(&out_dir.join("build_info.rs"), &build_info_source());
+}
+
+// Only rewrites the file when its contents change, so an unchanged table
+// doesn't force the crate to recompile.
+fn write(path: &Path, contents: &str) {
+    if fs::read_to_string(path).is_ok_and(|old| old == contents) {
+        return;
+    }
+    fs::write(path, contents).unwrap_or_else(|e| panic!("can't write {}: {}", path.display(), e));
+}
+
+// Parses `name = value` lines, skipping blanks and `#` comments. Errors are
+// prefixed with the line number.
+fn parse_amounts(data: &str) -> Result<Vec<(String, u64)>, String> {
+    let mut amounts: Vec<(String, u64)> = Vec::new();
+    for (index, line) in data.lines().enumerate() {
+        let number = index + 1;
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let Some((name, value)) = line.split_once('=') else {
+            return Err(format!("{}: expected `name = value`", number));
+        };
+        let (name, value) = (name.trim(), value.trim());
+        let valid_name = name.starts_with(|c: char| c.is_ascii_lowercase())
+            && name
+                .chars()
+                .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_');
+        if !valid_name {
+            return Err(format!("{}: invalid name `{}`", number, name));
+        }
+        if RESERVED.contains(&name) {
+            return Err(format!("{}: `{}` is reserved", number, name));
+        }
+        if amounts.iter().any(|(existing, _)| existing == name) {
+            return Err(format!("{}: `{}` is defined twice", number, name));
+        }
+        let value = value
+            .parse()
+            .map_err(|_| format!("{}: invalid value `{}`", number, value))?;
+        amounts.push((String::from(name), value));
+    }
+    Ok(amounts)
+}
+
+fn amounts_source(amounts: &[(String, u64)]) -> String {
+    let mut source = format!(
+        "// Generated by build.rs from {}. Do not edit.\n\n",
+        AMOUNTS
+    );
+    for (name, value) in amounts {
+        writeln!(
+            source,
+            "pub const {}: u64 = {};",
+            name.to_uppercase(),
+            value
+        )
+        .unwrap();
+    }
+    writeln!(
+        source,
+        "\npub const ALL: [(&str, u64); {}] = [",
+        amounts.len()
+    )
+    .unwrap();
+    for (name, _) in amounts {
+        writeln!(source, "    ({:?}, {}),", name, name.to_uppercase()).unwrap();
+    }
+    source.push_str("];\n");
+    source
+}
+
+// Cargo tells build scripts about the build through environment variables:
+// one `CARGO_FEATURE_<NAME>` per enabled feature, uppercased with `-` turned
+// into `_`. Changes to any of them already rerun the script.
+fn build_info_source() -> String {
+    let profile = env::var("PROFILE").expect("cargo sets PROFILE");
+    let target = env::var("TARGET").expect("cargo sets TARGET");
+    let mut features: Vec<String> = env::vars()
+        .filter_map(|(key, _)| {
+            key.strip_prefix("CARGO_FEATURE_")
+                .map(|name| name.to_lowercase().replace('_', "-"))
+        })
+        .collect();
+    features.sort();
+
+    let mut source = String::from("// Generated by build.rs. Do not edit.\n\n");
+    writeln!(source, "pub const PROFILE: &str = {:?};", profile).unwrap();
+    writeln!(source, "pub const TARGET: &str = {:?};", target).unwrap();
+    writeln!(source, "pub const FEATURES: &[&str] = &{:?};", features).unwrap();
+    source
 }
@@ -1,3 +1,10 @@
-fn main() {
-    println!("This is the other bin");
+// The same calculator as the default binary, showing that every file in
+// `src/bin` becomes its own executable:
+//
+//     $ cargo run --bin my_other_bin -- add --float 0.5 0.25
+//     0.75
+use std::process::ExitCode;
+
+fn main() -> ExitCode {
+    foo::cli::main(std::env::args())
 }
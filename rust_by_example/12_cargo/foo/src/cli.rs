@@ -0,0 +1,231 @@
+use std::fmt;
+use std::process::ExitCode;
+use std::str::FromStr;
+
+use bar::Adder;
+
+use crate::{build_info, checked_add_two};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Command {
+    Add(Vec<i64>),
+    AddFloats(Vec<f64>),
+    AddTwo(u64),
+    Help,
+    Version,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CliError {
+    MissingCommand,
+    UnknownCommand(String),
+    UnknownOption(String),
+    MissingArgument(&'static str),
+    UnexpectedArgument(String),
+    InvalidNumber(String),
+    Overflow,
+}
+
+impl fmt::Display for CliError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CliError::MissingCommand => write!(f, "no command given"),
+            CliError::UnknownCommand(command) => write!(f, "unknown command `{}`", command),
+            CliError::UnknownOption(option) => write!(f, "unknown option `{}`", option),
+            CliError::MissingArgument(what) => write!(f, "missing {}", what),
+            CliError::UnexpectedArgument(arg) => write!(f, "unexpected argument `{}`", arg),
+            CliError::InvalidNumber(text) => write!(f, "`{}` is not a valid number", text),
+            CliError::Overflow => write!(f, "the result is too large"),
+        }
+    }
+}
+
+impl std::error::Error for CliError {}
+
+impl CliError {
+    // Mistakes in how the program was called, as opposed to failures while
+    // running a well-formed command.
+    pub fn is_usage(&self) -> bool {
+        !matches!(self, CliError::Overflow)
+    }
+}
+
+pub fn usage(program: &str) -> String {
+    format!(
+        "usage: {program} <command> [args]
+
+commands:
+  add [--float] <a> <b>...  print the sum of the numbers
+  add-two <n>               print n + 2
+  help                      print this message
+  version                   print how this binary was built
+"
+    )
+}
+
+// Parses the arguments after the program name.
+pub fn parse<I: IntoIterator<Item = String>>(args: I) -> Result<Command, CliError> {
+    let mut args = args.into_iter();
+    let command = args.next().ok_or(CliError::MissingCommand)?;
+    match command.as_str() {
+        "add" => {
+            let mut float = false;
+            let mut numbers = Vec::new();
+            for arg in args {
+                match arg.as_str() {
+                    "--float" => float = true,
+                    // Anything else starting with `--` is a typo, not a number.
+                    _ if arg.starts_with("--") => return Err(CliError::UnknownOption(arg)),
+                    _ => numbers.push(arg),
+                }
+            }
+            if numbers.len() < 2 {
+                return Err(CliError::MissingArgument(
+                    "numbers to add (need at least two)",
+                ));
+            }
+            if float {
+                Ok(Command::AddFloats(parse_numbers(&numbers)?))
+            } else {
+                Ok(Command::Add(parse_numbers(&numbers)?))
+            }
+        }
+        "add-two" => {
+            let n = args.next().ok_or(CliError::MissingArgument("a number"))?;
+            if let Some(extra) = args.next() {
+                return Err(CliError::UnexpectedArgument(extra));
+            }
+            Ok(Command::AddTwo(parse_number(&n)?))
+        }
+        "help" | "-h" | "--help" => Ok(Command::Help),
+        "version" | "-V" | "--version" => Ok(Command::Version),
+        _ => Err(CliError::UnknownCommand(command)),
+    }
+}
+
+fn parse_number<T: FromStr>(text: &str) -> Result<T, CliError> {
+    text.parse()
+        .map_err(|_| CliError::InvalidNumber(String::from(text)))
+}
+
+fn parse_numbers<T: FromStr>(texts: &[String]) -> Result<Vec<T>, CliError> {
+    texts.iter().map(|text| parse_number(text)).collect()
+}
+
+fn sum<T: Adder + fmt::Display>(numbers: &[T]) -> Result<String, CliError> {
+    let total = bar::sum(numbers.iter().copied()).ok_or(CliError::Overflow)?;
+    Ok(total.to_string())
+}
+
+impl Command {
+    // Returns what the command prints, without a trailing newline.
+    pub fn run(&self, program: &str) -> Result<String, CliError> {
+        match self {
+            Command::Add(numbers) => sum(numbers),
+            Command::AddFloats(numbers) => sum(numbers),
+            Command::AddTwo(n) => checked_add_two(*n)
+                .map(|result| result.to_string())
+                .ok_or(CliError::Overflow),
+            Command::Help => Ok(String::from(usage(program).trim_end())),
+            Command::Version => Ok(build_info().to_string()),
+        }
+    }
+}
+
+// Everything a binary's `main` needs: takes `std::env::args()` as is.
+pub fn main<I: IntoIterator<Item = String>>(args: I) -> ExitCode {
+    let mut args = args.into_iter();
+    let program = args.next().unwrap_or_else(|| String::from("foo"));
+    let program = program.rsplit(['/', '\\']).next().unwrap_or("foo");
+
+    match parse(args).and_then(|command| command.run(program)) {
+        Ok(output) => {
+            println!("{}", output);
+            ExitCode::SUCCESS
+        }
+        Err(e) if e.is_usage() => {
+            eprintln!("{}: {}\n\n{}", program, e, usage(program));
+            ExitCode::from(2)
+        }
+        Err(e) => {
+            eprintln!("{}: {}", program, e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(line: &str) -> Vec<String> {
+        line.split_whitespace().map(String::from).collect()
+    }
+
+    fn run(line: &str) -> Result<String, CliError> {
+        parse(args(line))?.run("foo")
+    }
+
+    #[test]
+    fn parses_subcommands() {
+        assert_eq!(parse(args("add 1 -2 3")), Ok(Command::Add(vec![1, -2, 3])));
+        assert_eq!(
+            parse(args("add 1.5 --float 2")),
+            Ok(Command::AddFloats(vec![1.5, 2.0]))
+        );
+        assert_eq!(parse(args("add-two 40")), Ok(Command::AddTwo(40)));
+        assert_eq!(parse(args("--help")), Ok(Command::Help));
+        assert_eq!(parse(args("-V")), Ok(Command::Version));
+    }
+
+    #[test]
+    fn rejects_bad_arguments() {
+        assert_eq!(parse(args("")), Err(CliError::MissingCommand));
+        assert_eq!(
+            parse(args("sub 1 2")),
+            Err(CliError::UnknownCommand(String::from("sub")))
+        );
+        assert_eq!(
+            parse(args("add 1 --floats 2")),
+            Err(CliError::UnknownOption(String::from("--floats")))
+        );
+        assert!(matches!(
+            parse(args("add 1")),
+            Err(CliError::MissingArgument(_))
+        ));
+        assert_eq!(
+            parse(args("add 1 2.5")),
+            Err(CliError::InvalidNumber(String::from("2.5")))
+        );
+        assert_eq!(
+            parse(args("add-two -1")),
+            Err(CliError::InvalidNumber(String::from("-1")))
+        );
+        assert_eq!(
+            parse(args("add-two 1 2")),
+            Err(CliError::UnexpectedArgument(String::from("2")))
+        );
+    }
+
+    #[test]
+    fn runs_commands() {
+        assert_eq!(run("add 2 2"), Ok(String::from("4")));
+        assert_eq!(run("add 10 -3 -7"), Ok(String::from("0")));
+        assert_eq!(run("add --float 0.5 0.25"), Ok(String::from("0.75")));
+        assert_eq!(run("add-two 40"), Ok(String::from("42")));
+        assert!(run("help").unwrap().starts_with("usage: foo <command>"));
+        assert!(run("version").unwrap().starts_with("foo 0.1.0 ("));
+    }
+
+    #[test]
+    fn reports_overflow() {
+        let max = i64::MAX.to_string();
+        assert_eq!(run(&format!("add {} 1", max)), Err(CliError::Overflow));
+        assert_eq!(
+            run(&format!("add-two {}", u64::MAX)),
+            Err(CliError::Overflow)
+        );
+        assert!(!CliError::Overflow.is_usage());
+        assert!(CliError::MissingCommand.is_usage());
+    }
+}
@@ -1,8 +1,116 @@
+use std::fmt;
+
+// Constants generated by build.rs from data/amounts.txt.
+pub mod amounts {
+    include!(concat!(env!("OUT_DIR"), "/amounts.rs"));
+
+    pub fn get(name: &str) -> Option<u64> {
+        ALL.iter()
+            .find(|(candidate, _)| *candidate == name)
+            .map(|(_, value)| *value)
+    }
+}
+
+mod generated {
+    include!(concat!(env!("OUT_DIR"), "/build_info.rs"));
+}
+
+// How this copy of the crate was built, as recorded by build.rs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BuildInfo {
+    pub version: &'static str,
+    pub profile: &'static str,
+    pub target: &'static str,
+    pub features: &'static [&'static str],
+}
+
+pub fn build_info() -> BuildInfo {
+    BuildInfo {
+        version: env!("CARGO_PKG_VERSION"),
+        profile: generated::PROFILE,
+        target: generated::TARGET,
+        features: generated::FEATURES,
+    }
+}
+
+// "foo 0.1.0 (debug, x86_64-unknown-linux-gnu, features: none)"
+impl fmt::Display for BuildInfo {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let features = if self.features.is_empty() {
+            String::from("none")
+        } else {
+            self.features.join(", ")
+        };
+        write!(
+            f,
+            "foo {} ({}, {}, features: {})",
+            self.version, self.profile, self.target, features
+        )
+    }
+}
+
+pub mod cli;
+
 pub fn add_two(a: u64) -> u64 {
-    internal_adder(a, 2)
+    bar::add(a, amounts::TWO)
 }
 
-fn internal_adder(left: u64, right: u64) -> u64 {
-    left + right
+pub fn checked_add_two(a: u64) -> Option<u64> {
+    bar::Adder::checked_add(a, amounts::TWO)
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn generates_amounts_from_the_data_file() {
+        assert_eq!(amounts::ONE, 1);
+        assert_eq!(amounts::TWO, 2);
+        assert_eq!(
+            amounts::ALL,
+            [("one", 1), ("two", 2), ("ten", 10), ("hundred", 100)]
+        );
+        assert_eq!(amounts::get("ten"), Some(10));
+        assert_eq!(amounts::get("TEN"), None);
+        assert_eq!(add_two(40), 42);
+        assert_eq!(checked_add_two(u64::MAX - 2), Some(u64::MAX));
+        assert_eq!(checked_add_two(u64::MAX - 1), None);
+    }
+
+    #[test]
+    fn generated_source_is_stable() {
+        let source = include_str!(concat!(env!("OUT_DIR"), "/amounts.rs"));
+        assert!(source.starts_with("// Generated by build.rs from data/amounts.txt."));
+        assert!(source.contains("pub const HUNDRED: u64 = 100;\n"));
+        assert!(source.contains("    (\"hundred\", HUNDRED),\n"));
+    }
+
+    #[test]
+    fn records_how_the_crate_was_built() {
+        let info = build_info();
+        assert_eq!(info.version, "0.1.0");
+        let profile = if cfg!(debug_assertions) {
+            "debug"
+        } else {
+            "release"
+        };
+        assert_eq!(info.profile, profile);
+        assert!(info.target.starts_with(std::env::consts::ARCH));
+        assert!(info.target.contains(std::env::consts::OS));
+        assert!(info.features.is_sorted());
+        assert_eq!(
+            info.to_string(),
+            format!(
+                "foo 0.1.0 ({}, {}, features: {})",
+                profile,
+                info.target,
+                if info.features.is_empty() {
+                    String::from("none")
+                } else {
+                    info.features.join(", ")
+                }
+            )
+        );
+    }
+}
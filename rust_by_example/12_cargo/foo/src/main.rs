@@ -1,3 +1,13 @@
-fn main() {
-    println!("Hello, world!");
+// A small calculator built on `bar::Adder`:
+//
+//     $ cargo run -- add 1 2 3
+//     6
+//     $ cargo run -- add-two 40
+//     42
+//
+// Run `cargo run -- help` for the full list of commands.
+use std::process::ExitCode;
+
+fn main() -> ExitCode {
+    foo::cli::main(std::env::args())
 }
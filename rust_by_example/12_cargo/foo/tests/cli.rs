@@ -0,0 +1,45 @@
+use std::process::{Command, Output};
+
+fn run(bin: &str, args: &[&str]) -> Output {
+    Command::new(bin).args(args).output().unwrap()
+}
+
+fn stdout(output: &Output) -> &str {
+    std::str::from_utf8(&output.stdout).unwrap()
+}
+
+#[test]
+fn both_binaries_run_the_calculator() {
+    for bin in [
+        env!("CARGO_BIN_EXE_foo"),
+        env!("CARGO_BIN_EXE_my_other_bin"),
+    ] {
+        let output = run(bin, &["add", "1", "2", "3"]);
+        assert!(output.status.success());
+        assert_eq!(stdout(&output), "6\n");
+
+        let output = run(bin, &["add-two", "40"]);
+        assert_eq!(stdout(&output), "42\n");
+    }
+}
+
+#[test]
+fn usage_errors_exit_with_two() {
+    let output = run(env!("CARGO_BIN_EXE_foo"), &["subtract", "1"]);
+    assert_eq!(output.status.code(), Some(2));
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.starts_with("foo: unknown command `subtract`\n\nusage: foo <command>"));
+}
+
+#[test]
+fn overflow_exits_with_one() {
+    let output = run(
+        env!("CARGO_BIN_EXE_my_other_bin"),
+        &["add-two", &u64::MAX.to_string()],
+    );
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(
+        String::from_utf8(output.stderr).unwrap(),
+        "my_other_bin: the result is too large\n"
+    );
+}
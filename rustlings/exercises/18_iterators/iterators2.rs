@@ -1,28 +1,27 @@
 // In this exercise, you'll learn some of the unique advantages that iterators
 // can offer.
 
-// TODO: Complete the `capitalize_first` function.
+use chapter4::case::{self, Locale};
+
 // "hello" -> "Hello"
+// The case conversion itself lives in chapter 4's `case` module, which uses
+// Unicode titlecasing, so "élan" -> "Élan" and "ßig" -> "Ssig".
 fn capitalize_first(input: &str) -> String {
-    let mut chars = input.chars();
-    match chars.next() {
-        None => String::new(),
-        Some(first) => todo!(),
-    }
+    case::capitalize_first(input, Locale::Default)
 }
 
-// TODO: Apply the `capitalize_first` function to a slice of string slices.
+// Apply the `capitalize_first` function to a slice of string slices.
 // Return a vector of strings.
 // ["hello", "world"] -> ["Hello", "World"]
 fn capitalize_words_vector(words: &[&str]) -> Vec<String> {
-    // ???
+    words.iter().map(|word| capitalize_first(word)).collect()
 }
 
-// TODO: Apply the `capitalize_first` function again to a slice of string
-// slices. Return a single string.
+// Apply the `capitalize_first` function again to a slice of string slices.
+// Return a single string.
 // ["hello", " ", "world"] -> "Hello World"
 fn capitalize_words_string(words: &[&str]) -> String {
-    // ???
+    words.iter().map(|word| capitalize_first(word)).collect()
 }
 
 fn main() {
@@ -43,6 +42,13 @@ mod tests {
         assert_eq!(capitalize_first(""), "");
     }
 
+    #[test]
+    fn test_unicode() {
+        assert_eq!(capitalize_first("élan"), "Élan");
+        assert_eq!(capitalize_first("ßig"), "Ssig");
+        assert_eq!(capitalize_first("ǆungla"), "ǅungla");
+    }
+
     #[test]
     fn test_iterate_string_vec() {
         let words = vec!["hello", "world"];
@@ -8,20 +8,594 @@
 // - However, if Mary buys more than 40 apples, the price of each apple in the
 // entire order is reduced to only 1 rustbuck!
 
-// TODO: Write a function that calculates the price of an order of apples given
-// the quantity bought.
-// fn calculate_price_of_apples(???) -> ??? { ??? }
-fn calculate_price_of_apples(n: u32) -> u32 {
-    if n > 40 {
-        n
-    } else {
-        n * 2
+// The rule is a special case of a small pricing engine: `PriceBook` reads
+// per-product prices and rules from a config file (see quiz1.pricing) and
+// quotes orders in exact integer cents, listing every rule it applied.
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::path::Path;
+use std::str::FromStr;
+
+// An amount of money in hundredths of a rustbuck. Never a float, so sums and
+// discounts are exact.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default)]
+struct Cents(i64);
+
+// Prices and quantities come from the config and the order, so every step is
+// checked rather than trusted to fit in an `i64`.
+impl Cents {
+    fn plus(self, other: Cents) -> Result<Cents, PricingError> {
+        self.0
+            .checked_add(other.0)
+            .map(Cents)
+            .ok_or(PricingError::Overflow)
+    }
+
+    fn minus(self, other: Cents) -> Result<Cents, PricingError> {
+        self.0
+            .checked_sub(other.0)
+            .map(Cents)
+            .ok_or(PricingError::Overflow)
+    }
+
+    fn times(self, quantity: u32) -> Result<Cents, PricingError> {
+        self.0
+            .checked_mul(i64::from(quantity))
+            .map(Cents)
+            .ok_or(PricingError::Overflow)
+    }
+
+    // `basis_points` hundredths of a percent of this amount, rounded to the
+    // nearest cent with halves rounded up.
+    fn percent(self, basis_points: u32) -> Result<Cents, PricingError> {
+        self.0
+            .checked_mul(i64::from(basis_points))
+            .and_then(|x| x.checked_add(5_000))
+            .map(|x| Cents(x / 10_000))
+            .ok_or(PricingError::Overflow)
+    }
+}
+
+impl fmt::Display for Cents {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let sign = if self.0 < 0 { "-" } else { "" };
+        let cents = self.0.unsigned_abs();
+        write!(f, "{}{}.{:02}", sign, cents / 100, cents % 100)
+    }
+}
+
+// Accepts `2`, `2.5` and `2.05`, but nothing negative or finer than a cent.
+impl FromStr for Cents {
+    type Err = ();
+
+    fn from_str(text: &str) -> Result<Cents, ()> {
+        let (whole, fraction) = text.split_once('.').unwrap_or((text, "0"));
+        let digits = |s: &str| !s.is_empty() && s.bytes().all(|b| b.is_ascii_digit());
+        if !digits(whole) || !digits(fraction) || fraction.len() > 2 {
+            return Err(());
+        }
+        let whole: i64 = whole.parse().map_err(|_| ())?;
+        let mut fraction: i64 = fraction.parse().map_err(|_| ())?;
+        if text.split_once('.').is_some_and(|(_, f)| f.len() == 1) {
+            fraction *= 10;
+        }
+        whole
+            .checked_mul(100)
+            .and_then(|cents| cents.checked_add(fraction))
+            .map(Cents)
+            .ok_or(())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Target {
+    Product(String),
+    Order,
+}
+
+impl fmt::Display for Target {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Target::Product(sku) => write!(f, "{}", sku),
+            Target::Order => write!(f, "order"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Rule {
+    // Units `from..=to` of a line cost `price` each. `to: None` is open-ended.
+    Tier {
+        sku: String,
+        from: u32,
+        to: Option<u32>,
+        price: Cents,
+    },
+    // Every unit costs `price` once the line has at least `min` units.
+    Volume {
+        sku: String,
+        min: u32,
+        price: Cents,
+    },
+    // Out of every `buy + free` units, `free` of them cost nothing.
+    BuyGet {
+        sku: String,
+        buy: u32,
+        free: u32,
+    },
+    Percent {
+        target: Target,
+        basis_points: u32,
+    },
+    Fixed {
+        target: Target,
+        amount: Cents,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum ConfigError {
+    Io(String),
+    // A line that doesn't match any rule; `expected` describes the closest one.
+    Syntax { line: usize, expected: &'static str },
+    InvalidNumber { line: usize },
+    InvalidPrice { line: usize },
+    UnknownProduct { line: usize, sku: String },
+    DuplicateProduct { line: usize, sku: String },
+    OverlappingTier { line: usize },
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ConfigError::Io(message) => write!(f, "can't read prices: {}", message),
+            ConfigError::Syntax { line, expected } => {
+                write!(f, "line {}: expected `{}`", line, expected)
+            }
+            ConfigError::InvalidNumber { line } => {
+                write!(f, "line {}: expected a positive whole number", line)
+            }
+            ConfigError::InvalidPrice { line } => {
+                write!(f, "line {}: expected an amount like 2.50", line)
+            }
+            ConfigError::UnknownProduct { line, sku } => {
+                write!(f, "line {}: {} has no price yet", line, sku)
+            }
+            ConfigError::DuplicateProduct { line, sku } => {
+                write!(f, "line {}: {} already has a price", line, sku)
+            }
+            ConfigError::OverlappingTier { line } => {
+                write!(f, "line {}: the tier overlaps an earlier one", line)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum PricingError {
+    UnknownProduct(String),
+    Overflow,
+}
+
+impl fmt::Display for PricingError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PricingError::UnknownProduct(sku) => write!(f, "{} isn't for sale", sku),
+            PricingError::Overflow => write!(f, "the order is too large to price"),
+        }
     }
 }
 
+impl std::error::Error for PricingError {}
+
+// One thing that changed the price: a charge is positive, a discount negative.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Step {
+    description: String,
+    amount: Cents,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Quote {
+    steps: Vec<Step>,
+    total: Cents,
+}
+
+// Every step on its own line, then the total.
+impl fmt::Display for Quote {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for step in &self.steps {
+            writeln!(f, "{}: {}", step.description, step.amount)?;
+        }
+        write!(f, "total: {}", self.total)
+    }
+}
+
+// Prices and rules, applied to each product line in a fixed order:
+//
+// 1. Unit prices. A `volume` rule the quantity reaches replaces everything
+//    else (the highest one wins). Otherwise each `tier` prices the units in
+//    its range, and units outside every tier cost the product's `price`.
+// 2. Promotions. Only one `promo` applies per line: the one that saves most.
+//    Free units are valued at the cheapest unit price paid on the line.
+// 3. The product's percentage discounts, then its fixed discounts, in file
+//    order. Percentages compound.
+//
+// After that, order-wide (`*`) percentage and then fixed discounts apply to
+// the sum of the lines. No discount takes a total below zero. The config has
+// one rule per line; blank lines and lines starting with `#` are ignored:
+//
+//     price apple 2.00             # every product needs a price first
+//     tier apple 1-10 2.00         # units 1 to 10
+//     tier apple 11+ 1.50          # unit 11 onwards
+//     volume apple 100+ 1.00       # every unit, from 100 units up
+//     promo apple buy 2 get 1      # every third apple is free
+//     discount apple 10%           # 10% off apples
+//     discount * 5.00              # 5 rustbucks off the whole order
+#[derive(Debug, Clone, Default)]
+struct PriceBook {
+    prices: BTreeMap<String, Cents>,
+    rules: Vec<Rule>,
+}
+
+impl PriceBook {
+    fn load<P: AsRef<Path>>(path: P) -> Result<PriceBook, ConfigError> {
+        fs::read_to_string(path)
+            .map_err(|e| ConfigError::Io(e.to_string()))?
+            .parse()
+    }
+
+    // Orders may list a product more than once; its quantities are added up.
+    fn quote(&self, order: &[(&str, u32)]) -> Result<Quote, PricingError> {
+        let mut lines: Vec<(&str, u32)> = Vec::new();
+        for &(sku, quantity) in order {
+            match lines.iter_mut().find(|(s, _)| *s == sku) {
+                Some((_, total)) => {
+                    *total = total.checked_add(quantity).ok_or(PricingError::Overflow)?;
+                }
+                None => lines.push((sku, quantity)),
+            }
+        }
+
+        let mut steps = Vec::new();
+        let mut total = Cents(0);
+        for (sku, quantity) in lines {
+            total = total.plus(self.price_line(sku, quantity, &mut steps)?)?;
+        }
+        total = self.discount(&Target::Order, total, &mut steps)?;
+        Ok(Quote { steps, total })
+    }
+
+    fn price_line(
+        &self,
+        sku: &str,
+        quantity: u32,
+        steps: &mut Vec<Step>,
+    ) -> Result<Cents, PricingError> {
+        let base = *self
+            .prices
+            .get(sku)
+            .ok_or_else(|| PricingError::UnknownProduct(String::from(sku)))?;
+
+        // (units, unit price, why) for each group of units priced alike.
+        let mut groups: Vec<(u32, Cents, String)> = Vec::new();
+        let volume = self
+            .rules
+            .iter()
+            .filter_map(|rule| match rule {
+                Rule::Volume { sku: s, min, price } if s == sku && quantity >= *min => {
+                    Some((*min, *price))
+                }
+                _ => None,
+            })
+            .max_by_key(|(min, _)| *min);
+        if let Some((min, price)) = volume {
+            groups.push((quantity, price, format!("volume price for {} or more", min)));
+        } else {
+            let mut covered = 0;
+            for rule in &self.rules {
+                if let Rule::Tier {
+                    sku: s,
+                    from,
+                    to,
+                    price,
+                } = rule
+                    && s == sku
+                    && *from <= quantity
+                {
+                    let last = to.map_or(quantity, |to| to.min(quantity));
+                    let units = last - from + 1;
+                    let range = match to {
+                        Some(to) => format!("{}-{}", from, to),
+                        None => format!("{}+", from),
+                    };
+                    groups.push((units, *price, format!("tier {}", range)));
+                    covered += units;
+                }
+            }
+            if quantity > covered {
+                groups.insert(0, (quantity - covered, base, String::from("list price")));
+            }
+        }
+
+        let mut subtotal = Cents(0);
+        for (units, price, why) in &groups {
+            let amount = price.times(*units)?;
+            steps.push(Step {
+                description: format!("{}: {} x {} ({})", sku, units, price, why),
+                amount,
+            });
+            subtotal = subtotal.plus(amount)?;
+        }
+
+        let cheapest = groups
+            .iter()
+            .map(|(_, price, _)| *price)
+            .min()
+            .unwrap_or(base);
+        let mut promo = None;
+        for rule in &self.rules {
+            if let Rule::BuyGet { sku: s, buy, free } = rule
+                && s == sku
+            {
+                let free_units = quantity / buy.saturating_add(*free) * free;
+                let saving = cheapest.times(free_units)?;
+                if saving.0 > 0 && promo.is_none_or(|(best, _, _)| saving >= best) {
+                    promo = Some((saving, *buy, *free));
+                }
+            }
+        }
+        if let Some((saving, buy, free)) = promo {
+            steps.push(Step {
+                description: format!("{}: buy {} get {} free", sku, buy, free),
+                amount: Cents(-saving.0),
+            });
+            subtotal = subtotal.minus(saving)?;
+        }
+
+        self.discount(&Target::Product(String::from(sku)), subtotal, steps)
+    }
+
+    // Applies `target`'s percentage discounts, then its fixed ones.
+    fn discount(
+        &self,
+        target: &Target,
+        mut amount: Cents,
+        steps: &mut Vec<Step>,
+    ) -> Result<Cents, PricingError> {
+        let percents = self.rules.iter().filter_map(|rule| match rule {
+            Rule::Percent {
+                target: t,
+                basis_points,
+            } if t == target => Some(*basis_points),
+            _ => None,
+        });
+        for basis_points in percents {
+            let off = amount.percent(basis_points)?.min(amount);
+            steps.push(Step {
+                description: format!("{}: {} off", target, Percent(basis_points)),
+                amount: Cents(-off.0),
+            });
+            amount = amount.minus(off)?;
+        }
+
+        let fixed = self.rules.iter().filter_map(|rule| match rule {
+            Rule::Fixed { target: t, amount } if t == target => Some(*amount),
+            _ => None,
+        });
+        for discount in fixed {
+            let off = discount.min(amount);
+            steps.push(Step {
+                description: format!("{}: {} off", target, discount),
+                amount: Cents(-off.0),
+            });
+            amount = amount.minus(off)?;
+        }
+        Ok(amount)
+    }
+}
+
+// Basis points shown as a percentage: `10%`, `12.5%`.
+struct Percent(u32);
+
+impl fmt::Display for Percent {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let fraction = format!("{:02}", self.0 % 100);
+        let fraction = fraction.trim_end_matches('0');
+        if fraction.is_empty() {
+            write!(f, "{}%", self.0 / 100)
+        } else {
+            write!(f, "{}.{}%", self.0 / 100, fraction)
+        }
+    }
+}
+
+// Parses a tier range: `1-10` or `11+`.
+fn parse_range(text: &str) -> Option<(u32, Option<u32>)> {
+    let (from, to) = match text.strip_suffix('+') {
+        Some(from) => (from.parse().ok()?, None),
+        None => {
+            let (from, to) = text.split_once('-')?;
+            (from.parse().ok()?, Some(to.parse().ok()?))
+        }
+    };
+    let valid = from > 0 && to.is_none_or(|to| to >= from);
+    valid.then_some((from, to))
+}
+
+// Parses `10%` or `12.5%` into basis points, at most 100%.
+fn parse_percent(text: &str) -> Option<u32> {
+    let Cents(basis_points) = text.strip_suffix('%')?.parse().ok()?;
+    (basis_points <= 10_000).then_some(basis_points as u32)
+}
+
+impl FromStr for PriceBook {
+    type Err = ConfigError;
+
+    fn from_str(text: &str) -> Result<PriceBook, ConfigError> {
+        let mut book = PriceBook::default();
+        for (i, line) in text.lines().enumerate() {
+            let line_no = i + 1;
+            let line = line.split('#').next().unwrap_or("").trim();
+            if line.is_empty() {
+                continue;
+            }
+            let words: Vec<&str> = line.split_whitespace().collect();
+            let price = |text: &str| {
+                text.parse()
+                    .map_err(|_| ConfigError::InvalidPrice { line: line_no })
+            };
+            let count = |text: &str| match text.parse() {
+                Ok(n) if n > 0 => Ok(n),
+                _ => Err(ConfigError::InvalidNumber { line: line_no }),
+            };
+            let known = |sku: &str| {
+                if book.prices.contains_key(sku) {
+                    Ok(String::from(sku))
+                } else {
+                    Err(ConfigError::UnknownProduct {
+                        line: line_no,
+                        sku: String::from(sku),
+                    })
+                }
+            };
+
+            let rule = match words.as_slice() {
+                ["price", sku, amount] => {
+                    if book.prices.contains_key(*sku) || *sku == "*" {
+                        return Err(ConfigError::DuplicateProduct {
+                            line: line_no,
+                            sku: String::from(*sku),
+                        });
+                    }
+                    book.prices.insert(String::from(*sku), price(amount)?);
+                    continue;
+                }
+                ["tier", sku, range, amount] => {
+                    let sku = known(sku)?;
+                    let (from, to) = parse_range(range).ok_or(ConfigError::Syntax {
+                        line: line_no,
+                        expected: "tier <product> <from>-<to>|<from>+ <price>",
+                    })?;
+                    let overlaps = book.rules.iter().any(|rule| match rule {
+                        Rule::Tier {
+                            sku: s,
+                            from: f,
+                            to: t,
+                            ..
+                        } => {
+                            *s == sku && to.is_none_or(|to| to >= *f) && t.is_none_or(|t| t >= from)
+                        }
+                        _ => false,
+                    });
+                    if overlaps {
+                        return Err(ConfigError::OverlappingTier { line: line_no });
+                    }
+                    Rule::Tier {
+                        sku,
+                        from,
+                        to,
+                        price: price(amount)?,
+                    }
+                }
+                ["volume", sku, min, amount] => {
+                    let sku = known(sku)?;
+                    let min = min.strip_suffix('+').ok_or(ConfigError::Syntax {
+                        line: line_no,
+                        expected: "volume <product> <min>+ <price>",
+                    })?;
+                    Rule::Volume {
+                        sku,
+                        min: count(min)?,
+                        price: price(amount)?,
+                    }
+                }
+                ["promo", sku, "buy", buy, "get", free] => Rule::BuyGet {
+                    sku: known(sku)?,
+                    buy: count(buy)?,
+                    free: count(free)?,
+                },
+                ["discount", target, amount] => {
+                    let target = match *target {
+                        "*" => Target::Order,
+                        sku => Target::Product(known(sku)?),
+                    };
+                    if amount.ends_with('%') {
+                        let basis_points = parse_percent(amount)
+                            .ok_or(ConfigError::InvalidPrice { line: line_no })?;
+                        Rule::Percent {
+                            target,
+                            basis_points,
+                        }
+                    } else {
+                        Rule::Fixed {
+                            target,
+                            amount: price(amount)?,
+                        }
+                    }
+                }
+                [kind, ..] => {
+                    let expected = match *kind {
+                        "price" => "price <product> <price>",
+                        "tier" => "tier <product> <from>-<to>|<from>+ <price>",
+                        "volume" => "volume <product> <min>+ <price>",
+                        "promo" => "promo <product> buy <n> get <m>",
+                        "discount" => "discount <product>|* <price>|<percent>%",
+                        _ => "price, tier, volume, promo or discount",
+                    };
+                    return Err(ConfigError::Syntax {
+                        line: line_no,
+                        expected,
+                    });
+                }
+                [] => unreachable!("blank lines are skipped"),
+            };
+            book.rules.push(rule);
+        }
+        Ok(book)
+    }
+}
+
+fn calculate_price_of_apples(n: u32) -> u32 {
+    let prices: PriceBook = include_str!("quiz1.pricing")
+        .parse()
+        .expect("quiz1.pricing is valid");
+    let quote = prices.quote(&[("apple", n)]).expect("apples are for sale");
+    (quote.total.0 / 100) as u32
+}
 
 fn main() {
-    // You can optionally experiment here.
+    // Pass a price file and `product=quantity` pairs to get a quote, e.g.
+    // `quiz1 exercises/quizzes/quiz1.pricing apple=41`.
+    let mut args = std::env::args().skip(1);
+    let Some(path) = args.next() else {
+        println!("41 apples cost {} rustbucks", calculate_price_of_apples(41));
+        return;
+    };
+    let prices = match PriceBook::load(&path) {
+        Ok(prices) => prices,
+        Err(e) => {
+            eprintln!("{}: {}", path, e);
+            std::process::exit(1);
+        }
+    };
+    let order: Vec<(String, u32)> = args
+        .filter_map(|arg| {
+            let (sku, quantity) = arg.split_once('=')?;
+            Some((String::from(sku), quantity.parse().ok()?))
+        })
+        .collect();
+    let order: Vec<(&str, u32)> = order.iter().map(|(sku, n)| (sku.as_str(), *n)).collect();
+    match prices.quote(&order) {
+        Ok(quote) => println!("{}", quote),
+        Err(e) => {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+    }
 }
 
 // Don't change the tests!
@@ -37,3 +611,190 @@ mod tests {
         assert_eq!(calculate_price_of_apples(65), 65);
     }
 }
+
+#[cfg(test)]
+mod pricing_tests {
+    use super::*;
+
+    fn book(config: &str) -> PriceBook {
+        config.parse().unwrap()
+    }
+
+    fn total(book: &PriceBook, order: &[(&str, u32)]) -> String {
+        book.quote(order).unwrap().total.to_string()
+    }
+
+    #[test]
+    fn handles_exact_cents() {
+        assert_eq!("2".parse(), Ok(Cents(200)));
+        assert_eq!("0.5".parse(), Ok(Cents(50)));
+        assert_eq!("1.05".parse(), Ok(Cents(105)));
+        for bad in ["1.234", "-1", "1.", ".5", "1e3", ""] {
+            assert_eq!(bad.parse::<Cents>(), Err(()), "{}", bad);
+        }
+        assert_eq!(Cents(-150).to_string(), "-1.50");
+        assert_eq!(Cents(7).to_string(), "0.07");
+        assert_eq!(Cents(1999).percent(1250), Ok(Cents(250)));
+        assert_eq!(Cents(10).percent(5000), Ok(Cents(5)));
+        assert_eq!(Cents(5).percent(5000), Ok(Cents(3)));
+    }
+
+    #[test]
+    fn the_quiz_is_one_rule_set() {
+        let apples = book(include_str!("quiz1.pricing"));
+        assert_eq!(total(&apples, &[("apple", 40)]), "80.00");
+        assert_eq!(total(&apples, &[("apple", 41)]), "41.00");
+        assert_eq!(
+            apples.quote(&[("apple", 41)]).unwrap().to_string(),
+            "apple: 41 x 1.00 (volume price for 41 or more): 41.00\n\
+             total: 41.00"
+        );
+    }
+
+    #[test]
+    fn prices_tiers_and_volume() {
+        let widgets = book(
+            "price widget 10.00
+             tier widget 11-20 8.00
+             tier widget 21+ 5.00
+             volume widget 100+ 4.00
+             volume widget 50+ 4.50",
+        );
+        assert_eq!(total(&widgets, &[("widget", 5)]), "50.00");
+        assert_eq!(
+            widgets.quote(&[("widget", 25)]).unwrap().to_string(),
+            "widget: 10 x 10.00 (list price): 100.00\n\
+             widget: 10 x 8.00 (tier 11-20): 80.00\n\
+             widget: 5 x 5.00 (tier 21+): 25.00\n\
+             total: 205.00"
+        );
+        assert_eq!(total(&widgets, &[("widget", 60)]), "270.00");
+        assert_eq!(total(&widgets, &[("widget", 100)]), "400.00");
+    }
+
+    #[test]
+    fn applies_the_best_promotion_once() {
+        let apples = book(
+            "price apple 1.00
+             promo apple buy 2 get 1
+             promo apple buy 3 get 2",
+        );
+        let quote = apples.quote(&[("apple", 10)]).unwrap();
+        assert_eq!(quote.total, Cents(600));
+        assert_eq!(
+            quote.steps[1],
+            Step {
+                description: String::from("apple: buy 3 get 2 free"),
+                amount: Cents(-400),
+            }
+        );
+        assert_eq!(total(&apples, &[("apple", 2)]), "2.00");
+        assert_eq!(total(&apples, &[("apple", 3)]), "2.00");
+    }
+
+    #[test]
+    fn applies_discounts_in_order() {
+        let shop = book(
+            "price pear 10.00  # pears
+             price plum 3.00
+             discount * 1.00
+             discount pear 2.00
+             discount pear 12.5%
+             discount * 10%
+             discount plum 5.00",
+        );
+        // Pears: 40.00 - 12.5% = 35.00, - 2.00 = 33.00. Plums can't go below
+        // zero. The order: 33.00 - 10% = 29.70, - 1.00 = 28.70.
+        let quote = shop
+            .quote(&[("pear", 3), ("plum", 1), ("pear", 1)])
+            .unwrap();
+        assert_eq!(quote.total, Cents(2870));
+        assert_eq!(
+            quote.to_string(),
+            "pear: 4 x 10.00 (list price): 40.00\n\
+             pear: 12.5% off: -5.00\n\
+             pear: 2.00 off: -2.00\n\
+             plum: 1 x 3.00 (list price): 3.00\n\
+             plum: 5.00 off: -3.00\n\
+             order: 10% off: -3.30\n\
+             order: 1.00 off: -1.00\n\
+             total: 28.70"
+        );
+        assert_eq!(
+            shop.quote(&[("kiwi", 1)]),
+            Err(PricingError::UnknownProduct(String::from("kiwi")))
+        );
+    }
+
+    #[test]
+    fn reports_overflow_instead_of_panicking() {
+        let shop = book(
+            "price pear 90000000000000000.00
+             price plum 1.00
+             discount plum 50%",
+        );
+        assert_eq!(
+            shop.quote(&[("plum", u32::MAX), ("plum", 1)]),
+            Err(PricingError::Overflow)
+        );
+        assert_eq!(shop.quote(&[("pear", 2)]), Err(PricingError::Overflow));
+        assert_eq!(
+            shop.quote(&[("pear", 1), ("pear", 1)]),
+            Err(PricingError::Overflow)
+        );
+        assert_eq!(total(&shop, &[("plum", 3)]), "1.50");
+    }
+
+    #[test]
+    fn reports_config_errors_by_line() {
+        let error = |config: &str| config.parse::<PriceBook>().unwrap_err();
+        assert_eq!(
+            error("# nothing yet\ntier apple 1-5 1.00"),
+            ConfigError::UnknownProduct {
+                line: 2,
+                sku: String::from("apple")
+            }
+        );
+        assert_eq!(
+            error("price apple 1.00\nprice apple 2.00"),
+            ConfigError::DuplicateProduct {
+                line: 2,
+                sku: String::from("apple")
+            }
+        );
+        assert_eq!(
+            error("price apple 1,00"),
+            ConfigError::InvalidPrice { line: 1 }
+        );
+        assert_eq!(
+            error("price apple 1.00\npromo apple buy 0 get 1"),
+            ConfigError::InvalidNumber { line: 2 }
+        );
+        assert_eq!(
+            error("price apple 1.00\ntier apple 1-10 1.00\ntier apple 10+ 0.50"),
+            ConfigError::OverlappingTier { line: 3 }
+        );
+        assert_eq!(
+            error("price apple 1.00\ntier apple 5-1 1.00"),
+            ConfigError::Syntax {
+                line: 2,
+                expected: "tier <product> <from>-<to>|<from>+ <price>"
+            }
+        );
+        assert_eq!(
+            error("price apple 1.00\ndiscount apple 150%"),
+            ConfigError::InvalidPrice { line: 2 }
+        );
+        assert_eq!(
+            error("sell apple"),
+            ConfigError::Syntax {
+                line: 1,
+                expected: "price, tier, volume, promo or discount"
+            }
+        );
+        assert!(matches!(
+            PriceBook::load("does/not/exist.pricing"),
+            Err(ConfigError::Io(_))
+        ));
+    }
+}
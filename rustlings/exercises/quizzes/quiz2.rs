@@ -7,71 +7,429 @@
 //
 // Let's build a little machine in the form of a function. As input, we're going
 // to give a list of strings and commands. These commands determine what action
-// is going to be applied to the string. It can either be:
-// - Uppercase the string
+// is going to be applied to the string. It can be:
+// - Uppercase or lowercase the string
 // - Trim the string
-// - Append "bar" to the string a specified amount of times
+// - Replace every occurrence of one substring with another
+// - Prepend a string, or append a string a specified amount of times
+// - Truncate the string to a number of characters
+// - Reverse the string
 //
 // The exact form of this will be:
 // - The input is going to be a Vector of 2-length tuples,
-//   the first element is the string, the second one is the command.
+//   the first element is the string, the second one is the list of commands,
+//   applied in order.
 // - The output element is going to be a vector of strings.
+//
+// Lists of commands can also be written as text, one command per `|`:
+//
+//     trim | upper | replace "A" "4" | append "!" x3
 
+#[derive(Debug, Clone, PartialEq, Eq)]
 enum Command {
     Uppercase,
+    Lowercase,
     Trim,
-    Append(usize),
+    Replace(String, String),
+    Prepend(String),
+    Append(String, usize),
+    Truncate(usize),
+    Reverse,
 }
 
 mod my_module {
     use super::Command;
+    use std::fmt;
+    use std::str::FromStr;
 
-    // TODO: Complete the function as described above.
-    // pub fn transformer(input: ???) -> ??? { ??? }
-    
-    pub fn transformer(input: Vec<(String, Command)>) -> Vec<String> {
-
-        let mut output = Vec::<String>::new();
-        for (word, command) in input {
-            match command {
-                Command::Uppercase => output.push(word.to_uppercase()),
-                Command::Trim => output.push(word.trim().to_string()),
-                Command::Append(n) => output.push(word + &"bar".repeat(n)),
+    impl Command {
+        pub fn apply(&self, string: String) -> String {
+            match self {
+                Command::Uppercase => string.to_uppercase(),
+                Command::Lowercase => string.to_lowercase(),
+                Command::Trim => string.trim().to_string(),
+                Command::Replace(from, to) => string.replace(from.as_str(), to),
+                Command::Prepend(prefix) => prefix.clone() + &string,
+                Command::Append(suffix, n) => string + &suffix.repeat(*n),
+                // Counts characters, not bytes, so it never splits one in half.
+                Command::Truncate(n) => string.chars().take(*n).collect(),
+                Command::Reverse => string.chars().rev().collect(),
             }
         }
+    }
+
+    // Runs every command in order, handing each one the previous result.
+    pub fn apply_all(commands: &[Command], string: String) -> String {
+        commands
+            .iter()
+            .fold(string, |string, command| command.apply(string))
+    }
+
+    // The solution with a loop. Check out `transformer_iter` for a version
+    // with iterators.
+    pub fn transformer(input: Vec<(String, Vec<Command>)>) -> Vec<String> {
+        let mut output = Vec::new();
+        for (string, commands) in input {
+            output.push(apply_all(&commands, string));
+        }
         output
     }
+
+    // Equivalent to `transformer` but built on the lazy `transform`.
+    pub fn transformer_iter(input: Vec<(String, Vec<Command>)>) -> Vec<String> {
+        transform(input).collect()
+    }
+
+    // Nothing is transformed until the returned iterator is advanced, so this
+    // works on input that is too big, or too slow, to collect up front.
+    pub fn transform<I>(input: I) -> impl Iterator<Item = String>
+    where
+        I: IntoIterator<Item = (String, Vec<Command>)>,
+    {
+        input
+            .into_iter()
+            .map(|(string, commands)| apply_all(&commands, string))
+    }
+
+    // Like `transform`, but runs the same commands on every string.
+    pub fn transform_each<'a, I>(
+        commands: &'a [Command],
+        input: I,
+    ) -> impl Iterator<Item = String> + 'a
+    where
+        I: IntoIterator<Item = String> + 'a,
+    {
+        input
+            .into_iter()
+            .map(move |string| apply_all(commands, string))
+    }
+
+    // The largest count `append "..." xN` accepts.
+    pub const MAX_APPEND_COUNT: usize = 10_000;
+
+    #[derive(Debug, PartialEq, Eq)]
+    pub enum ParseError {
+        EmptyCommand,
+        UnknownCommand(String),
+        MissingArgument(&'static str),
+        UnexpectedArgument(String),
+        InvalidCount(String),
+        UnterminatedString,
+    }
+
+    impl fmt::Display for ParseError {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            match self {
+                ParseError::EmptyCommand => write!(f, "empty command"),
+                ParseError::UnknownCommand(name) => write!(f, "unknown command `{}`", name),
+                ParseError::MissingArgument(what) => write!(f, "missing {}", what),
+                ParseError::UnexpectedArgument(arg) => {
+                    write!(f, "unexpected argument `{}`", arg)
+                }
+                ParseError::InvalidCount(text) => write!(f, "`{}` is not a valid count", text),
+                ParseError::UnterminatedString => write!(f, "unterminated string"),
+            }
+        }
+    }
+
+    impl std::error::Error for ParseError {}
+
+    #[derive(Debug, PartialEq)]
+    enum Token {
+        Word(String),
+        Quoted(String),
+        Pipe,
+    }
+
+    // Splits on whitespace and `|`. Double-quoted strings may contain both,
+    // and `\"` or `\\` inside them stand for a quote and a backslash.
+    fn tokenize(text: &str) -> Result<Vec<Token>, ParseError> {
+        let mut tokens = Vec::new();
+        let mut chars = text.chars().peekable();
+        while let Some(&c) = chars.peek() {
+            if c.is_whitespace() {
+                chars.next();
+            } else if c == '|' {
+                chars.next();
+                tokens.push(Token::Pipe);
+            } else if c == '"' {
+                chars.next();
+                let mut string = String::new();
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some('\\') => match chars.next() {
+                            Some(escaped @ ('"' | '\\')) => string.push(escaped),
+                            Some(other) => {
+                                string.push('\\');
+                                string.push(other);
+                            }
+                            None => return Err(ParseError::UnterminatedString),
+                        },
+                        Some(other) => string.push(other),
+                        None => return Err(ParseError::UnterminatedString),
+                    }
+                }
+                tokens.push(Token::Quoted(string));
+            } else {
+                let mut word = String::new();
+                while let Some(&c) = chars.peek() {
+                    if c.is_whitespace() || c == '|' || c == '"' {
+                        break;
+                    }
+                    word.push(c);
+                    chars.next();
+                }
+                tokens.push(Token::Word(word));
+            }
+        }
+        Ok(tokens)
+    }
+
+    fn parse_command(tokens: Vec<Token>) -> Result<Command, ParseError> {
+        let mut tokens = tokens.into_iter();
+        let name = match tokens.next() {
+            Some(Token::Word(name)) => name,
+            Some(Token::Quoted(text)) => return Err(ParseError::UnknownCommand(text)),
+            Some(Token::Pipe) | None => return Err(ParseError::EmptyCommand),
+        };
+
+        // Arguments are strings (quoted) or counts (bare words).
+        let mut string = |what| match tokens.next() {
+            Some(Token::Quoted(text)) => Ok(text),
+            Some(Token::Word(word)) => Err(ParseError::UnexpectedArgument(word)),
+            _ => Err(ParseError::MissingArgument(what)),
+        };
+
+        let command = match name.to_lowercase().as_str() {
+            "upper" | "uppercase" => Command::Uppercase,
+            "lower" | "lowercase" => Command::Lowercase,
+            "trim" => Command::Trim,
+            "reverse" => Command::Reverse,
+            "replace" => Command::Replace(string("the text to replace")?, string("a replacement")?),
+            "prepend" => Command::Prepend(string("the text to prepend")?),
+            "append" => {
+                let suffix = string("the text to append")?;
+                // The count is optional and written `x3`. It's capped so a typo
+                // can't ask `repeat` for more memory than there is.
+                let times = match tokens.next() {
+                    None => 1,
+                    Some(Token::Word(word)) => match word.strip_prefix('x') {
+                        Some(count) => count
+                            .parse()
+                            .ok()
+                            .filter(|&n| n <= MAX_APPEND_COUNT)
+                            .ok_or_else(|| ParseError::InvalidCount(word.clone()))?,
+                        None => return Err(ParseError::InvalidCount(word)),
+                    },
+                    Some(Token::Quoted(text)) => return Err(ParseError::UnexpectedArgument(text)),
+                    Some(Token::Pipe) => unreachable!("pipes are split off before parsing"),
+                };
+                Command::Append(suffix, times)
+            }
+            "truncate" => match tokens.next() {
+                Some(Token::Word(word)) => {
+                    Command::Truncate(word.parse().map_err(|_| ParseError::InvalidCount(word))?)
+                }
+                Some(Token::Quoted(text)) => return Err(ParseError::InvalidCount(text)),
+                _ => return Err(ParseError::MissingArgument("a length")),
+            },
+            _ => return Err(ParseError::UnknownCommand(name)),
+        };
+
+        match tokens.next() {
+            None => Ok(command),
+            Some(Token::Word(extra) | Token::Quoted(extra)) => {
+                Err(ParseError::UnexpectedArgument(extra))
+            }
+            Some(Token::Pipe) => unreachable!("pipes are split off before parsing"),
+        }
+    }
+
+    // Parses a whole pipeline such as `trim | upper | append "!" x3`.
+    pub fn parse_pipeline(text: &str) -> Result<Vec<Command>, ParseError> {
+        let mut commands = Vec::new();
+        let mut current = Vec::new();
+        for token in tokenize(text)? {
+            if token == Token::Pipe {
+                commands.push(parse_command(std::mem::take(&mut current))?);
+            } else {
+                current.push(token);
+            }
+        }
+        commands.push(parse_command(current)?);
+        Ok(commands)
+    }
+
+    impl FromStr for Command {
+        type Err = ParseError;
+
+        fn from_str(text: &str) -> Result<Command, ParseError> {
+            let mut commands = parse_pipeline(text)?;
+            if commands.len() > 1 {
+                return Err(ParseError::UnexpectedArgument(String::from("|")));
+            }
+            Ok(commands.remove(0))
+        }
+    }
 }
 
 fn main() {
-    // You can optionally experiment here.
+    use my_module::{parse_pipeline, transform_each};
+
+    let shout = parse_pipeline(r#"trim | upper | append "!" x3"#).unwrap();
+    let words = ["  hello ", "rust", " pipelines"].map(String::from);
+    for line in transform_each(&shout, words) {
+        println!("{}", line);
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    // TODO: What do we need to import to have `transformer` in scope?
-    // use ???;
     use super::Command;
-    use super::my_module::transformer;
+    use super::my_module::*;
+    use std::cell::Cell;
 
     #[test]
     fn it_works() {
-        let input = vec![
-            ("hello".to_string(), Command::Uppercase),
-            (" all roads lead to rome! ".to_string(), Command::Trim),
-            ("foo".to_string(), Command::Append(1)),
-            ("bar".to_string(), Command::Append(5)),
+        for transformer in [transformer, transformer_iter] {
+            let input = vec![
+                ("hello".to_string(), vec![Command::Uppercase]),
+                (" all roads lead to rome! ".to_string(), vec![Command::Trim]),
+                (
+                    "foo".to_string(),
+                    vec![Command::Append("bar".to_string(), 1)],
+                ),
+                (
+                    "bar".to_string(),
+                    vec![Command::Append("bar".to_string(), 5)],
+                ),
+            ];
+            let output = transformer(input);
+
+            assert_eq!(
+                output,
+                [
+                    "HELLO",
+                    "all roads lead to rome!",
+                    "foobar",
+                    "barbarbarbarbarbar",
+                ]
+            );
+        }
+    }
+
+    #[test]
+    fn runs_commands_in_order() {
+        let commands = vec![
+            Command::Trim,
+            Command::Lowercase,
+            Command::Replace("o".to_string(), "0".to_string()),
+            Command::Prepend(">> ".to_string()),
+            Command::Truncate(6),
+            Command::Reverse,
         ];
-        let output = transformer(input);
+        assert_eq!(apply_all(&commands, "  HELLO WORLD ".to_string()), "leh >>");
+        assert_eq!(apply_all(&[], "same".to_string()), "same");
+        assert_eq!(Command::Truncate(2).apply("żółw".to_string()), "żó");
+        assert_eq!(Command::Reverse.apply("żółw".to_string()), "włóż");
+    }
+
+    #[test]
+    fn parses_pipelines() {
+        assert_eq!(
+            parse_pipeline(r#"trim | upper | append "!" x3"#),
+            Ok(vec![
+                Command::Trim,
+                Command::Uppercase,
+                Command::Append("!".to_string(), 3),
+            ])
+        );
+        assert_eq!(
+            parse_pipeline(r#"replace "a|b" "\"c\\"|prepend "" | truncate 4 | REVERSE"#),
+            Ok(vec![
+                Command::Replace("a|b".to_string(), "\"c\\".to_string()),
+                Command::Prepend(String::new()),
+                Command::Truncate(4),
+                Command::Reverse,
+            ])
+        );
+        assert_eq!(
+            "append \"bar\"".parse(),
+            Ok(Command::Append("bar".to_string(), 1))
+        );
+        assert_eq!("lowercase".parse(), Ok(Command::Lowercase));
+    }
 
+    #[test]
+    fn rejects_malformed_pipelines() {
+        assert_eq!(parse_pipeline(""), Err(ParseError::EmptyCommand));
+        assert_eq!(
+            parse_pipeline("trim ||upper"),
+            Err(ParseError::EmptyCommand)
+        );
+        assert_eq!(
+            parse_pipeline("trim | shout"),
+            Err(ParseError::UnknownCommand("shout".to_string()))
+        );
         assert_eq!(
-            output,
-            [
-                "HELLO",
-                "all roads lead to rome!",
-                "foobar",
-                "barbarbarbarbarbar",
-            ]
+            parse_pipeline("append"),
+            Err(ParseError::MissingArgument("the text to append"))
         );
+        assert_eq!(
+            parse_pipeline("append bar"),
+            Err(ParseError::UnexpectedArgument("bar".to_string()))
+        );
+        assert_eq!(
+            parse_pipeline(r#"append "!" 3"#),
+            Err(ParseError::InvalidCount("3".to_string()))
+        );
+        assert_eq!(
+            parse_pipeline(r#"append "!" x18446744073709551615"#),
+            Err(ParseError::InvalidCount("x18446744073709551615".to_string()))
+        );
+        assert_eq!(
+            parse_pipeline(r#"append "!" x10001"#),
+            Err(ParseError::InvalidCount("x10001".to_string()))
+        );
+        assert_eq!(
+            parse_pipeline(r#"append "!" x10000"#),
+            Ok(vec![Command::Append("!".to_string(), MAX_APPEND_COUNT)])
+        );
+        assert_eq!(
+            parse_pipeline("truncate ten"),
+            Err(ParseError::InvalidCount("ten".to_string()))
+        );
+        assert_eq!(
+            parse_pipeline("trim now"),
+            Err(ParseError::UnexpectedArgument("now".to_string()))
+        );
+        assert_eq!(
+            parse_pipeline(r#"prepend "oops"#),
+            Err(ParseError::UnterminatedString)
+        );
+        assert_eq!(
+            "trim | upper".parse::<Command>(),
+            Err(ParseError::UnexpectedArgument("|".to_string()))
+        );
+    }
+
+    #[test]
+    fn transforms_lazily() {
+        let pulled = Cell::new(0);
+        let commands = parse_pipeline("upper").unwrap();
+        let input = (0..).map(|n| {
+            pulled.set(pulled.get() + 1);
+            format!("item{}", n)
+        });
+        let mut output = transform_each(&commands, input);
+        assert_eq!(pulled.get(), 0);
+        assert_eq!(output.next().as_deref(), Some("ITEM0"));
+        assert_eq!(output.nth(1).as_deref(), Some("ITEM2"));
+        assert_eq!(pulled.get(), 3);
+
+        let pairs = vec![("a".to_string(), vec![Command::Reverse]); 2];
+        let mut output = transform(pairs);
+        assert_eq!(output.next().as_deref(), Some("a"));
     }
 }
@@ -10,7 +10,13 @@
 //
 // Make the necessary code changes in the struct `ReportCard` and the impl
 // block to support alphabetical report cards in addition to numerical ones.
-use std::fmt::Display;
+//
+// The school now also keeps a `Gradebook`: many students, each graded on
+// several weighted assessments. Grades can use any `GradeScale` (numeric,
+// letter or pass/fail) and be converted between them, and every student's
+// weighted average can be printed as a `ReportCard`.
+use std::fmt::{self, Display};
+use std::str::FromStr;
 
 // TODO: Adjust the struct as described above.
 struct ReportCard<T> {
@@ -32,8 +38,464 @@ where
     }
 }
 
+// Every grade maps onto a shared score from 0.0 (worst) to 1.0 (best), which
+// is what lets grades be averaged and converted between scales.
+trait GradeScale: Copy + Display {
+    fn to_score(self) -> f64;
+
+    // `score` is clamped to 0.0..=1.0 first.
+    fn from_score(score: f64) -> Self;
+
+    fn convert<G: GradeScale>(self) -> G {
+        G::from_score(self.to_score())
+    }
+}
+
+// The lowest score that passes, on every scale.
+const PASS_MARK: f64 = 0.6;
+
+#[derive(Debug, Clone, PartialEq)]
+enum GradeError {
+    OutOfRange(f64),
+    InvalidGrade(String),
+    InvalidWeight(f64),
+    DuplicateStudent(String),
+    DuplicateAssessment(String),
+    UnknownStudent(String),
+    UnknownAssessment(String),
+}
+
+impl Display for GradeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            GradeError::OutOfRange(grade) => {
+                write!(f, "{} is outside the 1.0 to 5.0 scale", grade)
+            }
+            GradeError::InvalidGrade(text) => write!(f, "`{}` is not a grade", text),
+            GradeError::InvalidWeight(weight) => {
+                write!(f, "{} is not a valid weight", weight)
+            }
+            GradeError::DuplicateStudent(name) => write!(f, "{} is already enrolled", name),
+            GradeError::DuplicateAssessment(name) => {
+                write!(f, "there is already an assessment called {}", name)
+            }
+            GradeError::UnknownStudent(name) => write!(f, "no student called {}", name),
+            GradeError::UnknownAssessment(name) => write!(f, "no assessment called {}", name),
+        }
+    }
+}
+
+impl std::error::Error for GradeError {}
+
+// A grade from 1.0 to 5.0 in steps of 0.1, where higher is better.
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+struct Numeric(f64);
+
+impl Numeric {
+    const MIN: f64 = 1.0;
+    const MAX: f64 = 5.0;
+
+    fn new(grade: f64) -> Result<Numeric, GradeError> {
+        if (Numeric::MIN..=Numeric::MAX).contains(&grade) {
+            Ok(Numeric(grade))
+        } else {
+            Err(GradeError::OutOfRange(grade))
+        }
+    }
+
+    fn value(self) -> f64 {
+        self.0
+    }
+}
+
+impl GradeScale for Numeric {
+    fn to_score(self) -> f64 {
+        (self.0 - Numeric::MIN) / (Numeric::MAX - Numeric::MIN)
+    }
+
+    fn from_score(score: f64) -> Numeric {
+        let grade = Numeric::MIN + score.clamp(0.0, 1.0) * (Numeric::MAX - Numeric::MIN);
+        Numeric((grade * 10.0).round() / 10.0)
+    }
+}
+
+impl Display for Numeric {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:.1}", self.0)
+    }
+}
+
+impl FromStr for Numeric {
+    type Err = GradeError;
+
+    fn from_str(text: &str) -> Result<Numeric, GradeError> {
+        let grade = text
+            .trim()
+            .parse()
+            .map_err(|_| GradeError::InvalidGrade(String::from(text)))?;
+        Numeric::new(grade)
+    }
+}
+
+// Letter grades from best to worst, so the derived ordering has `APlus` first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Letter {
+    APlus,
+    A,
+    AMinus,
+    BPlus,
+    B,
+    BMinus,
+    CPlus,
+    C,
+    CMinus,
+    DPlus,
+    D,
+    DMinus,
+    F,
+}
+
+impl Letter {
+    // Each letter with the lowest percentage that earns it.
+    const BANDS: [(Letter, &'static str, u32); 13] = [
+        (Letter::APlus, "A+", 97),
+        (Letter::A, "A", 93),
+        (Letter::AMinus, "A-", 90),
+        (Letter::BPlus, "B+", 87),
+        (Letter::B, "B", 83),
+        (Letter::BMinus, "B-", 80),
+        (Letter::CPlus, "C+", 77),
+        (Letter::C, "C", 73),
+        (Letter::CMinus, "C-", 70),
+        (Letter::DPlus, "D+", 67),
+        (Letter::D, "D", 63),
+        (Letter::DMinus, "D-", 60),
+        (Letter::F, "F", 0),
+    ];
+
+    fn band(self) -> (Letter, &'static str, u32) {
+        Letter::BANDS[self as usize]
+    }
+}
+
+impl GradeScale for Letter {
+    // The bottom of the letter's band, so converting back gives the same letter.
+    fn to_score(self) -> f64 {
+        f64::from(self.band().2) / 100.0
+    }
+
+    fn from_score(score: f64) -> Letter {
+        // The small nudge keeps 0.93 from landing just below 93% after rounding.
+        let percent = score.clamp(0.0, 1.0) * 100.0 + 1e-9;
+        Letter::BANDS
+            .iter()
+            .find(|(_, _, min)| percent >= f64::from(*min))
+            .map_or(Letter::F, |(letter, _, _)| *letter)
+    }
+}
+
+impl Display for Letter {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.band().1)
+    }
+}
+
+impl FromStr for Letter {
+    type Err = GradeError;
+
+    fn from_str(text: &str) -> Result<Letter, GradeError> {
+        let wanted = text.trim().to_uppercase();
+        Letter::BANDS
+            .iter()
+            .find(|(_, name, _)| *name == wanted)
+            .map(|(letter, _, _)| *letter)
+            .ok_or_else(|| GradeError::InvalidGrade(String::from(text)))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum PassFail {
+    Pass,
+    Fail,
+}
+
+impl GradeScale for PassFail {
+    fn to_score(self) -> f64 {
+        match self {
+            PassFail::Pass => 1.0,
+            PassFail::Fail => 0.0,
+        }
+    }
+
+    fn from_score(score: f64) -> PassFail {
+        if score >= PASS_MARK {
+            PassFail::Pass
+        } else {
+            PassFail::Fail
+        }
+    }
+}
+
+impl Display for PassFail {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PassFail::Pass => write!(f, "pass"),
+            PassFail::Fail => write!(f, "fail"),
+        }
+    }
+}
+
+impl FromStr for PassFail {
+    type Err = GradeError;
+
+    fn from_str(text: &str) -> Result<PassFail, GradeError> {
+        match text.trim().to_lowercase().as_str() {
+            "pass" | "p" => Ok(PassFail::Pass),
+            "fail" | "f" => Ok(PassFail::Fail),
+            _ => Err(GradeError::InvalidGrade(String::from(text))),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Assessment {
+    name: String,
+    weight: f64,
+}
+
+#[derive(Debug, Clone)]
+struct Student<G> {
+    name: String,
+    age: u8,
+    // One slot per assessment, in the same order as `Gradebook::assessments`.
+    grades: Vec<Option<G>>,
+}
+
+// Averages are taken over the students that have at least one grade.
+#[derive(Debug, Clone, PartialEq)]
+struct ClassStats<G> {
+    students: usize,
+    passing: usize,
+    mean: G,
+    median: G,
+    highest: G,
+    lowest: G,
+    // In score units (0.0 to 1.0), since a spread isn't itself a grade.
+    std_dev: f64,
+}
+
+#[derive(Debug, Clone)]
+struct Gradebook<G> {
+    assessments: Vec<Assessment>,
+    students: Vec<Student<G>>,
+}
+
+impl<G: GradeScale> Gradebook<G> {
+    fn new() -> Gradebook<G> {
+        Gradebook {
+            assessments: Vec::new(),
+            students: Vec::new(),
+        }
+    }
+
+    // Weights are relative: 2.0 counts twice as much as 1.0.
+    fn add_assessment(&mut self, name: &str, weight: f64) -> Result<(), GradeError> {
+        if !(weight.is_finite() && weight > 0.0) {
+            return Err(GradeError::InvalidWeight(weight));
+        }
+        if self.assessment_index(name).is_ok() {
+            return Err(GradeError::DuplicateAssessment(String::from(name)));
+        }
+        self.assessments.push(Assessment {
+            name: String::from(name),
+            weight,
+        });
+        for student in &mut self.students {
+            student.grades.push(None);
+        }
+        Ok(())
+    }
+
+    fn add_student(&mut self, name: &str, age: u8) -> Result<(), GradeError> {
+        if self.student(name).is_ok() {
+            return Err(GradeError::DuplicateStudent(String::from(name)));
+        }
+        self.students.push(Student {
+            name: String::from(name),
+            age,
+            grades: vec![None; self.assessments.len()],
+        });
+        Ok(())
+    }
+
+    // Replaces any grade already recorded for that assessment.
+    fn record(&mut self, student: &str, assessment: &str, grade: G) -> Result<(), GradeError> {
+        let index = self.assessment_index(assessment)?;
+        let student = self
+            .students
+            .iter_mut()
+            .find(|s| s.name == student)
+            .ok_or_else(|| GradeError::UnknownStudent(String::from(student)))?;
+        student.grades[index] = Some(grade);
+        Ok(())
+    }
+
+    fn grade(&self, student: &str, assessment: &str) -> Result<Option<G>, GradeError> {
+        let index = self.assessment_index(assessment)?;
+        Ok(self.student(student)?.grades[index])
+    }
+
+    fn assessment_index(&self, name: &str) -> Result<usize, GradeError> {
+        self.assessments
+            .iter()
+            .position(|a| a.name == name)
+            .ok_or_else(|| GradeError::UnknownAssessment(String::from(name)))
+    }
+
+    fn student(&self, name: &str) -> Result<&Student<G>, GradeError> {
+        self.students
+            .iter()
+            .find(|s| s.name == name)
+            .ok_or_else(|| GradeError::UnknownStudent(String::from(name)))
+    }
+
+    // Missing grades are left out rather than counted as zero. `None` if the
+    // student has no grades at all.
+    fn weighted_score(&self, student: &Student<G>) -> Option<f64> {
+        let (total, weights) = student
+            .grades
+            .iter()
+            .zip(&self.assessments)
+            .filter_map(|(grade, assessment)| grade.map(|g| (g, assessment.weight)))
+            .fold((0.0, 0.0), |(total, weights), (grade, weight)| {
+                (total + grade.to_score() * weight, weights + weight)
+            });
+        (weights > 0.0).then(|| total / weights)
+    }
+
+    fn weighted_average(&self, student: &str) -> Result<Option<G>, GradeError> {
+        let student = self.student(student)?;
+        Ok(self.weighted_score(student).map(G::from_score))
+    }
+
+    fn stats(&self) -> Option<ClassStats<G>> {
+        let mut scores: Vec<f64> = self
+            .students
+            .iter()
+            .filter_map(|s| self.weighted_score(s))
+            .collect();
+        if scores.is_empty() {
+            return None;
+        }
+        scores.sort_by(f64::total_cmp);
+
+        let count = scores.len() as f64;
+        let mean = scores.iter().sum::<f64>() / count;
+        let middle = scores.len() / 2;
+        let median = if scores.len().is_multiple_of(2) {
+            (scores[middle - 1] + scores[middle]) / 2.0
+        } else {
+            scores[middle]
+        };
+        let variance = scores.iter().map(|s| (s - mean).powi(2)).sum::<f64>() / count;
+
+        Some(ClassStats {
+            students: scores.len(),
+            passing: scores.iter().filter(|s| **s >= PASS_MARK).count(),
+            mean: G::from_score(mean),
+            median: G::from_score(median),
+            highest: G::from_score(scores[scores.len() - 1]),
+            lowest: G::from_score(scores[0]),
+            std_dev: variance.sqrt(),
+        })
+    }
+
+    // The same class with every grade moved to another scale.
+    fn convert<H: GradeScale>(&self) -> Gradebook<H> {
+        Gradebook {
+            assessments: self.assessments.clone(),
+            students: self
+                .students
+                .iter()
+                .map(|s| Student {
+                    name: s.name.clone(),
+                    age: s.age,
+                    grades: s.grades.iter().map(|g| g.map(G::convert)).collect(),
+                })
+                .collect(),
+        }
+    }
+
+    // One card per student with a grade, showing their weighted average.
+    fn report_cards(&self) -> Vec<ReportCard<G>> {
+        self.students
+            .iter()
+            .filter_map(|s| {
+                self.weighted_score(s).map(|score| ReportCard {
+                    grade: G::from_score(score),
+                    student_name: s.name.clone(),
+                    student_age: s.age,
+                })
+            })
+            .collect()
+    }
+
+    // Every report card in `ReportCard::print` format, one per line.
+    fn to_text(&self) -> String {
+        self.report_cards()
+            .iter()
+            .map(|card| card.print() + "\n")
+            .collect()
+    }
+
+    // A header row, then one row per student with each assessment's grade
+    // (blank if missing) and the weighted average.
+    fn to_csv(&self) -> String {
+        let mut header = vec![String::from("student"), String::from("age")];
+        header.extend(self.assessments.iter().map(|a| csv_field(&a.name)));
+        header.push(String::from("average"));
+
+        let mut csv = header.join(",") + "\n";
+        for student in &self.students {
+            let mut row = vec![csv_field(&student.name), student.age.to_string()];
+            row.extend(
+                student
+                    .grades
+                    .iter()
+                    .map(|g| g.map(|g| csv_field(&g.to_string())).unwrap_or_default()),
+            );
+            row.push(
+                self.weighted_score(student)
+                    .map(|score| csv_field(&G::from_score(score).to_string()))
+                    .unwrap_or_default(),
+            );
+            csv += &(row.join(",") + "\n");
+        }
+        csv
+    }
+}
+
+// Quotes a field if it contains a comma, quote or newline, doubling any quotes.
+fn csv_field(text: &str) -> String {
+    if text.contains([',', '"', '\n']) {
+        format!("\"{}\"", text.replace('"', "\"\""))
+    } else {
+        String::from(text)
+    }
+}
+
 fn main() {
-    // You can optionally experiment here.
+    let mut class = Gradebook::new();
+    class.add_assessment("homework", 1.0).unwrap();
+    class.add_assessment("exam", 3.0).unwrap();
+    class.add_student("Tom Wriggle", 12).unwrap();
+    class.add_student("Gary Plotter", 11).unwrap();
+    class.record("Tom Wriggle", "homework", Letter::B).unwrap();
+    class.record("Tom Wriggle", "exam", Letter::CPlus).unwrap();
+    class.record("Gary Plotter", "exam", Letter::APlus).unwrap();
+
+    print!("{}", class.to_text());
+    print!("{}", class.convert::<Numeric>().to_csv());
 }
 
 #[cfg(test)]
@@ -65,4 +527,129 @@ mod tests {
             "Gary Plotter (11) - achieved a grade of A+",
         );
     }
+
+    #[test]
+    fn converts_between_scales() {
+        assert_eq!(Letter::B.convert::<Numeric>(), Numeric(4.3));
+        assert_eq!(Numeric(5.0).convert::<Letter>(), Letter::APlus);
+        assert_eq!(Numeric(1.0).convert::<Letter>(), Letter::F);
+        assert_eq!(Letter::DMinus.convert::<PassFail>(), PassFail::Pass);
+        assert_eq!(Letter::F.convert::<PassFail>(), PassFail::Fail);
+        assert_eq!(PassFail::Pass.convert::<Numeric>(), Numeric(5.0));
+        assert_eq!(PassFail::Fail.convert::<Letter>(), Letter::F);
+        for (letter, _, _) in Letter::BANDS {
+            assert_eq!(letter.convert::<Letter>(), letter);
+        }
+    }
+
+    #[test]
+    fn parses_and_prints_grades() {
+        assert_eq!("a-".parse(), Ok(Letter::AMinus));
+        assert_eq!(Letter::BMinus.to_string(), "B-");
+        assert_eq!(" 4.5".parse(), Ok(Numeric(4.5)));
+        assert_eq!("5.5".parse::<Numeric>(), Err(GradeError::OutOfRange(5.5)));
+        assert_eq!(Numeric::new(2.0).unwrap().to_string(), "2.0");
+        assert_eq!("PASS".parse(), Ok(PassFail::Pass));
+        assert_eq!(
+            "E".parse::<Letter>(),
+            Err(GradeError::InvalidGrade("E".to_string()))
+        );
+    }
+
+    fn class() -> Gradebook<Numeric> {
+        let mut class = Gradebook::new();
+        class.add_assessment("homework", 1.0).unwrap();
+        class.add_assessment("exam", 3.0).unwrap();
+        class.add_student("Alice", 15).unwrap();
+        class.add_student("Bob", 14).unwrap();
+        class.add_student("Carol", 13).unwrap();
+        class.record("Alice", "homework", Numeric(5.0)).unwrap();
+        class.record("Alice", "exam", Numeric(3.0)).unwrap();
+        class.record("Bob", "exam", Numeric(2.0)).unwrap();
+        class
+    }
+
+    #[test]
+    fn computes_weighted_averages_and_stats() {
+        let class = class();
+        assert_eq!(class.weighted_average("Alice"), Ok(Some(Numeric(3.5))));
+        assert_eq!(class.weighted_average("Bob"), Ok(Some(Numeric(2.0))));
+        assert_eq!(class.weighted_average("Carol"), Ok(None));
+
+        let stats = class.stats().unwrap();
+        assert_eq!(stats.students, 2);
+        assert_eq!(stats.passing, 1);
+        assert_eq!(stats.mean, Numeric(2.8));
+        assert_eq!(stats.median, Numeric(2.8));
+        assert_eq!(stats.highest, Numeric(3.5));
+        assert_eq!(stats.lowest, Numeric(2.0));
+        assert!((stats.std_dev - 0.1875).abs() < 1e-12);
+
+        assert_eq!(Gradebook::<Letter>::new().stats(), None);
+    }
+
+    #[test]
+    fn exports_report_cards() {
+        let mut class = class();
+        assert_eq!(
+            class.to_text(),
+            "Alice (15) - achieved a grade of 3.5\n\
+             Bob (14) - achieved a grade of 2.0\n"
+        );
+        class.add_student("Plotter, \"Gary\"", 11).unwrap();
+        assert_eq!(
+            class.to_csv(),
+            "student,age,homework,exam,average\n\
+             Alice,15,5.0,3.0,3.5\n\
+             Bob,14,,2.0,2.0\n\
+             Carol,13,,,\n\
+             \"Plotter, \"\"Gary\"\"\",11,,,\n"
+        );
+    }
+
+    #[test]
+    fn converts_a_whole_gradebook() {
+        let letters = class().convert::<Letter>();
+        assert_eq!(letters.grade("Alice", "homework"), Ok(Some(Letter::APlus)));
+        assert_eq!(letters.grade("Alice", "exam"), Ok(Some(Letter::F)));
+        assert_eq!(letters.weighted_average("Alice"), Ok(Some(Letter::F)));
+
+        let passes = class().convert::<PassFail>();
+        assert_eq!(passes.grade("Bob", "homework"), Ok(None));
+        assert_eq!(
+            passes.to_text(),
+            "Alice (15) - achieved a grade of fail\n\
+             Bob (14) - achieved a grade of fail\n"
+        );
+    }
+
+    #[test]
+    fn rejects_bad_changes() {
+        let mut class = class();
+        assert_eq!(
+            class.add_assessment("quiz", 0.0),
+            Err(GradeError::InvalidWeight(0.0))
+        );
+        assert_eq!(
+            class.add_assessment("exam", 1.0),
+            Err(GradeError::DuplicateAssessment("exam".to_string()))
+        );
+        assert_eq!(
+            class.add_student("Bob", 9),
+            Err(GradeError::DuplicateStudent("Bob".to_string()))
+        );
+        assert_eq!(
+            class.record("Dave", "exam", Numeric(3.0)),
+            Err(GradeError::UnknownStudent("Dave".to_string()))
+        );
+        assert_eq!(
+            class.record("Bob", "essay", Numeric(3.0)),
+            Err(GradeError::UnknownAssessment("essay".to_string()))
+        );
+
+        class.add_assessment("essay", 2.0).unwrap();
+        assert_eq!(class.grade("Alice", "essay"), Ok(None));
+        class.record("Bob", "essay", Numeric(5.0)).unwrap();
+        assert_eq!(class.weighted_average("Bob"), Ok(Some(Numeric(3.2))));
+    }
 }